@@ -0,0 +1,115 @@
+//! Benchmarks of the render phases (projection, sorting and whole renders)
+//! on a fixed-size synthetic scene. Run them with `cargo bench`.
+//!
+//! The crate only has a binary target, so the modules the renderer needs
+//! are compiled into this benchmark directly.
+
+#![allow(dead_code, unused_imports)]
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use nalgebra::{Point3, Vector3, Vector4};
+
+use self::sources::{
+    cli,
+    configuration,
+    renderer::{self, SplatRenderer},
+    splat_decoder::{self, Splat, Splats},
+};
+
+#[path = "../src"]
+mod sources {
+    pub mod cli;
+    pub mod configuration;
+    pub mod renderer;
+    pub mod splat_decoder;
+}
+
+
+/// Number of splats in the benchmark scene.
+const SCENE_SPLAT_COUNT: usize = 200_000;
+
+/// Resolution the benchmark scene is rendered at.
+const RENDER_DIMENSIONS: (u32, u32) = (512, 512);
+
+
+/// Builds the benchmark scene: [`SCENE_SPLAT_COUNT`] small splats scattered (pseudo-randomly,
+/// but always in the same way) inside a unit cube around the origin.
+fn get_benchmark_splats() -> Splats {
+    let mut random_state = 0x5EED_u64;
+    let mut next_unit_value = move || {
+        // A linear congruential generator is good enough for scattering splats.
+        random_state = random_state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (random_state >> 40) as f32 / (1u64 << 24) as f32
+    };
+
+    (0..SCENE_SPLAT_COUNT)
+        .map(|_| {
+            let position = Vector3::new(
+                next_unit_value() - 0.5,
+                next_unit_value() - 0.5,
+                next_unit_value() - 0.5,
+            );
+            let color = Vector4::new(
+                (next_unit_value() * 255.0) as u8,
+                (next_unit_value() * 255.0) as u8,
+                (next_unit_value() * 255.0) as u8,
+                128 + (next_unit_value() * 127.0) as u8,
+            );
+
+            Splat::new(
+                position,
+                Vector3::new(0.005, 0.005, 0.005),
+                color,
+                Vector4::new(1.0, 0.0, 0.0, 0.0),
+            )
+        })
+        .collect()
+}
+
+fn get_benchmark_renderer() -> SplatRenderer {
+    SplatRenderer::new_for_tests(
+        get_benchmark_splats(),
+        RENDER_DIMENSIONS,
+        Point3::new(0.8, 0.6, 1.2),
+        Point3::origin(),
+        "",
+    )
+}
+
+
+fn benchmark_render_phases(criterion: &mut Criterion) {
+    let renderer = get_benchmark_renderer();
+
+    criterion.bench_function("projection", |bencher| {
+        bencher.iter(|| renderer.project_splats_with_current_camera())
+    });
+
+    criterion.bench_function("sorting", |bencher| {
+        bencher.iter_batched(
+            || renderer.project_splats_with_current_camera(),
+            |mut prepared_splats| {
+                renderer.sort_projected_splats(&mut prepared_splats);
+                prepared_splats
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    criterion.bench_function("render_in_place", |bencher| {
+        bencher.iter(|| {
+            // Without this, the cached projection would be reused after the first render.
+            renderer.invalidate_projection();
+            renderer.render_in_place()
+        })
+    });
+}
+
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = benchmark_render_phases
+}
+criterion_main!(benches);
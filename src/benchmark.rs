@@ -0,0 +1,103 @@
+//! Headless render benchmarking (see the `--benchmark` command-line flag).
+
+use std::time::Duration;
+
+use tracing::info;
+
+use crate::renderer::{RenderTimings, SplatRenderer};
+
+
+/// Extracts the duration of a single phase from [`RenderTimings`].
+type PhaseDurationGetter = fn(&RenderTimings) -> Duration;
+
+
+/// Summary statistics of a single render phase across all benchmark runs (in milliseconds).
+struct PhaseStatistics {
+    minimum: f64,
+    median: f64,
+    mean: f64,
+    percentile_95: f64,
+}
+
+impl PhaseStatistics {
+    /// Computes the statistics of the given durations. Returns `None` if there are none.
+    fn from_durations(durations: impl Iterator<Item = Duration>) -> Option<Self> {
+        let mut durations_in_milliseconds = durations
+            .map(|duration| duration.as_secs_f64() * 1000.0)
+            .collect::<Vec<_>>();
+
+        if durations_in_milliseconds.is_empty() {
+            return None;
+        }
+
+        durations_in_milliseconds.sort_unstable_by(f64::total_cmp);
+
+
+        let sample_count = durations_in_milliseconds.len();
+
+        let median = if sample_count % 2 == 0 {
+            (durations_in_milliseconds[sample_count / 2 - 1]
+                + durations_in_milliseconds[sample_count / 2])
+                / 2.0
+        } else {
+            durations_in_milliseconds[sample_count / 2]
+        };
+
+        // Nearest-rank percentile.
+        let percentile_95_rank =
+            ((0.95 * sample_count as f64).ceil() as usize).clamp(1, sample_count);
+
+        Some(Self {
+            minimum: durations_in_milliseconds[0],
+            median,
+            mean: durations_in_milliseconds.iter().sum::<f64>() / sample_count as f64,
+            percentile_95: durations_in_milliseconds[percentile_95_rank - 1],
+        })
+    }
+}
+
+
+/// Renders the scene `run_count` times (always projecting and sorting all splats again)
+/// and prints per-phase timing statistics to standard output as CSV.
+pub fn run_render_benchmark(splat_renderer: &SplatRenderer, run_count: usize) {
+    info!(
+        "Running render benchmark with {} runs.",
+        run_count
+    );
+
+    let all_timings = (0..run_count)
+        .map(|_| {
+            splat_renderer.invalidate_projection();
+            splat_renderer.render_in_place().timings
+        })
+        .collect::<Vec<_>>();
+
+
+    let phases: [(&str, PhaseDurationGetter); 5] = [
+        ("preparation", |timings| timings.preparation),
+        ("sorting", |timings| timings.sorting),
+        ("canvas_reset", |timings| timings.canvas_reset),
+        ("compositing", |timings| timings.compositing),
+        ("total", |timings| timings.total),
+    ];
+
+    println!("phase,runs,min_ms,median_ms,mean_ms,p95_ms");
+
+    for (phase_name, phase_duration) in phases {
+        let Some(statistics) =
+            PhaseStatistics::from_durations(all_timings.iter().map(phase_duration))
+        else {
+            continue;
+        };
+
+        println!(
+            "{},{},{:.3},{:.3},{:.3},{:.3}",
+            phase_name,
+            run_count,
+            statistics.minimum,
+            statistics.median,
+            statistics.mean,
+            statistics.percentile_95
+        );
+    }
+}
@@ -1,10 +1,15 @@
 //! Command-line interface definitions for the server binary.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use miette::{miette, Context, IntoDiagnostic, Result};
-use nalgebra::{Point3, Vector3};
+use nalgebra::{Matrix4, Point3, Unit, Vector3};
+
+use crate::{
+    configuration::{BillboardSizeModel, RenderMode},
+    splat_decoder::{Axis, DownsampleStrategy},
+};
 
 
 
@@ -26,12 +31,147 @@ pub struct CLIArgs {
     )]
     pub configuration_file_path: Option<PathBuf>,
 
+    #[arg(
+        short = 'v',
+        long = "verbose",
+        action = clap::ArgAction::Count,
+        conflicts_with = "quiet",
+        help = "Increases console log verbosity (-v: info, -vv: debug, -vvv: trace). \
+                Takes precedence over both the RUST_LOG environment variable \
+                and console_output_level_filter in the configuration file."
+    )]
+    pub verbose: u8,
+
+    #[arg(
+        short = 'q',
+        long = "quiet",
+        action = clap::ArgAction::Count,
+        help = "Decreases console log verbosity (-q: errors only, -qq: nothing). \
+                Takes precedence over both the RUST_LOG environment variable \
+                and console_output_level_filter in the configuration file."
+    )]
+    pub quiet: u8,
+
     #[arg(
         short = 'i',
         long = "input-file-path",
-        help = "*.splat file to use. If unspecified, a small testing scene is shown."
+        help = "*.splat file to use. Can be specified multiple times to view several files together. \
+                If unspecified, a small testing scene is shown."
+    )]
+    pub input_file_paths: Vec<PathBuf>,
+
+    #[arg(
+        long = "stdin",
+        conflicts_with = "input_file_paths",
+        help = "If this flag is present, splats are read from standard input instead of a file \
+                (e.g. `cat scene.splat | nrg-dn1 --stdin`). Can't be combined with --input-file-path."
+    )]
+    pub read_from_stdin: bool,
+
+    #[arg(
+        long = "strict-decoding",
+        help = "If this flag is present, loading fails on splats with non-finite position, \
+                scale or rotation values (by default, such splats are skipped with a warning)."
+    )]
+    pub strict_decoding: bool,
+
+    #[arg(
+        long = "deterministic",
+        help = "If this flag is present, parsed splats are always kept in their file order \
+                (otherwise this is only the case in debug builds, if enabled in the configuration file, \
+                or if --seed, --dedup or random downsampling is used)."
+    )]
+    pub deterministic: bool,
+
+    #[arg(
+        long = "skip-transparent-splats",
+        help = "If this flag is present, fully transparent splats (zero alpha) are discarded on load."
     )]
-    pub input_file_path: Option<PathBuf>,
+    pub skip_transparent_splats: bool,
+
+    #[arg(
+        long = "alpha-threshold",
+        default_value_t = 0,
+        help = "Splats with an opacity (0-255) below this threshold are discarded on load. \
+                Defaults to 0, which keeps all splats."
+    )]
+    pub alpha_threshold: u8,
+
+    #[arg(
+        long = "dedup",
+        help = "If this flag is present, coincident splats (closer than --dedup-epsilon, \
+                with a similar color) are merged into one on load."
+    )]
+    pub deduplicate: bool,
+
+    #[arg(
+        long = "dedup-epsilon",
+        default_value_t = 0.0001,
+        help = "Largest distance (in world units) between two splats that are merged with --dedup."
+    )]
+    pub deduplication_epsilon: f32,
+
+    #[arg(
+        long = "max-splats",
+        help = "Maximum number of splats to render. If the input contains more, \
+                only a subset is kept (see --downsample-strategy)."
+    )]
+    pub max_splats: Option<usize>,
+
+    #[arg(
+        long = "downsample-strategy",
+        value_enum,
+        default_value_t = DownsampleStrategy::Nearest,
+        help = "Which splats to keep when the input exceeds --max-splats: \
+                the ones nearest to the scene centroid, or a deterministic random subset."
+    )]
+    pub downsample_strategy: DownsampleStrategy,
+
+    #[arg(
+        long = "seed",
+        help = "Seed of the pseudo-random generator used by randomized operations \
+                (e.g. --downsample-strategy random). Overrides the seed from the configuration file \
+                and keeps the parsed splats in their file order, so results are reproducible."
+    )]
+    pub seed: Option<u64>,
+
+    #[arg(
+        long = "flip-axis",
+        value_enum,
+        help = "Mirrors the loaded splats along the given axis (e.g. for data from tools using \
+                a left-handed coordinate system, which appears mirrored otherwise). \
+                Can be specified multiple times. Applied before --scale, --rotate and --translate."
+    )]
+    pub flip_axes: Vec<Axis>,
+
+    #[arg(
+        long = "translate",
+        help = "Translates the loaded splats by the given offset. Format: \"x,y,z\". \
+                Applied after --scale and --rotate."
+    )]
+    pub translate: Option<String>,
+
+    #[arg(
+        long = "scale",
+        help = "Uniformly scales the loaded splats (around the origin) by the given factor."
+    )]
+    pub scale: Option<f32>,
+
+    #[arg(
+        long = "rotate",
+        help = "Rotates the loaded splats (around the origin) around an axis by an angle in degrees. \
+                Format: \"x,y,z,degrees\". Applied after --scale."
+    )]
+    pub rotate: Option<String>,
+
+    #[arg(
+        long = "clip-plane",
+        value_name = "NX,NY,NZ,D",
+        help = "Culls the splats at positions p outside the half-space nx*px + ny*py + nz*pz + d >= 0 \
+                (e.g. --clip-plane=0,-1,0,1 only keeps splats with y <= 1). \
+                Can be specified multiple times: only splats inside all half-spaces are drawn."
+    )]
+    pub clip_planes: Vec<String>,
 
     #[arg(
         long = "export-screenshot-and-exit",
@@ -40,13 +180,179 @@ pub struct CLIArgs {
     )]
     pub export_screenshot_and_exit: bool,
 
+    #[arg(
+        long = "screenshot-width",
+        requires_all = ["screenshot_height", "export_screenshot_and_exit"],
+        help = "Width of the screenshot saved with --export-screenshot-and-exit, \
+                if it should differ from the render width (e.g. for a high-resolution export). \
+                Must be combined with --screenshot-height."
+    )]
+    pub screenshot_width: Option<u32>,
+
+    #[arg(
+        long = "screenshot-height",
+        requires_all = ["screenshot_width", "export_screenshot_and_exit"],
+        help = "Height of the screenshot saved with --export-screenshot-and-exit \
+                (see --screenshot-width)."
+    )]
+    pub screenshot_height: Option<u32>,
+
+    #[arg(
+        long = "screenshot-name",
+        help = "Fixed file name to save screenshots as (overwriting any previous screenshot). \
+                If unspecified, the value from the configuration file is used, \
+                and if that is unspecified as well, screenshots are named by their timestamp."
+    )]
+    pub screenshot_name: Option<String>,
+
+    #[arg(
+        long = "threads",
+        value_name = "N",
+        help = "Number of threads to use for parallel work (parsing, projection, sorting and compositing). \
+                0 means all cores. If unspecified, the value from the configuration file is used, \
+                and if that is unspecified as well, all cores are used."
+    )]
+    pub threads: Option<usize>,
+
+    #[arg(
+        long = "dump-projection",
+        value_name = "FILE",
+        help = "Writes the projection of every splat from the initial camera (world and clip-space \
+                position, distance, pixel coordinates and whether it was culled) to the given CSV file. \
+                The program then continues as usual (combine with --export-screenshot-and-exit to exit)."
+    )]
+    pub dump_projection: Option<PathBuf>,
+
+    #[arg(
+        long = "print-bounds",
+        help = "If this flag is present, the program will load the splats, print their count, \
+                axis-aligned bounding box and centroid to standard output \
+                (one \"key=value\" pair per line) and exit without rendering."
+    )]
+    pub print_bounds: bool,
+
+    #[arg(
+        long = "dry-run",
+        help = "If this flag is present, the program will load and validate the configuration, \
+                the splats and the other options, print the number of loaded splats and exit \
+                without rendering (e.g. for validating inputs in CI). Fails with an error if anything is invalid."
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long = "list-keys",
+        help = "If this flag is present, the program will print the keyboard shortcuts \
+                of the interactive window (with the keys remapped by the configuration file, \
+                if one is given with --configuration-file-path) and exit."
+    )]
+    pub list_keys: bool,
+
+    #[arg(
+        long = "benchmark",
+        value_name = "RUNS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Renders the scene the given number of times without opening a window, \
+                prints per-phase render timings (min, median, mean and p95, in milliseconds) \
+                to standard output as CSV and exits."
+    )]
+    pub benchmark: Option<u32>,
+
+    #[arg(
+        long = "camera-path",
+        help = "Path to a file with camera poses, one per line in the format \
+                \"x,y,z x,y,z\" (camera position, then look target). If present, the program \
+                renders and saves a numbered screenshot for each pose, then exits."
+    )]
+    pub camera_path: Option<PathBuf>,
+
     #[arg(
         short = 's',
         long = "splat-scaling-factor",
-        help = "Splat perspective closeness scaling factor (float), defaults to 2.0."
+        help = "Splat footprint scaling factor (float), defaults to 2.0."
     )]
     pub splat_scaling_factor: Option<f32>,
 
+    #[arg(
+        long = "camera-movement-speed",
+        help = "How far the camera moves on each movement or zoom step (float). \
+                If unspecified, the value from the configuration file is used (defaults to 0.1)."
+    )]
+    pub camera_movement_speed: Option<f32>,
+
+    #[arg(
+        long = "fov",
+        help = "Vertical field of view of the camera, in degrees (float). \
+                If unspecified, the value from the configuration file is used (defaults to 45)."
+    )]
+    pub vertical_field_of_view: Option<f32>,
+
+    #[arg(
+        long = "near-plane",
+        help = "Distance of the near clipping plane (float). \
+                If unspecified, the value from the configuration file is used (defaults to 0.1)."
+    )]
+    pub near_plane: Option<f32>,
+
+    #[arg(
+        long = "far-plane",
+        help = "Distance of the far clipping plane (float). \
+                If unspecified, the value from the configuration file is used (defaults to 100.0)."
+    )]
+    pub far_plane: Option<f32>,
+
+    #[arg(
+        long = "billboard-size-model",
+        value_enum,
+        help = "How the size of billboards depends on the distance from the camera. \
+                If unspecified, the value from the configuration file is used (defaults to inverse-distance)."
+    )]
+    pub billboard_size_model: Option<BillboardSizeModel>,
+
+    #[arg(
+        long = "render-mode",
+        value_enum,
+        help = "How splats are drawn: as alpha-composited billboards, or as single opaque pixels \
+                at their projected centers (useful for diagnosing splat placement). \
+                If unspecified, the value from the configuration file is used (defaults to billboards)."
+    )]
+    pub render_mode: Option<RenderMode>,
+
+    #[arg(
+        long = "ssaa",
+        value_name = "FACTOR",
+        help = "Supersampling anti-aliasing factor (integer between 1 and 4): splats are rendered \
+                at this multiple of the output resolution and then downsampled. \
+                If unspecified, the value from the configuration file is used (defaults to 1, i.e. off)."
+    )]
+    pub ssaa_factor: Option<u32>,
+
+    #[arg(
+        long = "clamp-offscreen",
+        help = "Debugging aid: if this flag is present, splats whose centers project outside \
+                the viewport are clamped to its border instead of being culled, \
+                and the number of clamped splats is logged."
+    )]
+    pub clamp_offscreen: bool,
+
+    #[arg(
+        long = "fit",
+        help = "If this flag is present and no camera position is given, the camera is placed \
+                so that it frames all splats (looking at their centroid)."
+    )]
+    pub fit_camera_to_scene: bool,
+
+    #[arg(
+        long = "turntable",
+        value_name = "DEGREES_PER_SECOND",
+        allow_negative_numbers = true,
+        conflicts_with = "export_screenshot_and_exit",
+        help = "Turntable mode (e.g. for recording demos): the camera continuously orbits its look target \
+                around the up axis at the given angular speed (negative speeds orbit the other way), \
+                rendering frame after frame. Only available in the interactive window, \
+                so it can't be combined with --export-screenshot-and-exit."
+    )]
+    pub turntable_degrees_per_second: Option<f32>,
+
     #[arg(
         long = "camera-position",
         help = "Initial camera position (in world space). Format: \"x,y,z\". \
@@ -81,6 +387,69 @@ pub struct CLIArgs {
     pub render_height: Option<u32>,
 }
 
+impl CLIArgs {
+    /// Returns the console log level filter directive requested with the `-v` or `-q` flags,
+    /// or `None` if neither flag was specified.
+    pub fn console_level_filter_override(&self) -> Option<&'static str> {
+        verbosity_to_level_filter(self.verbose, self.quiet)
+    }
+
+    /// Composes the `--scale`, `--rotate` and `--translate` options (applied in that order)
+    /// into a single transform, or returns `None` if none of them were specified.
+    pub fn scene_transform(&self) -> Result<Option<Matrix4<f32>>> {
+        if self.translate.is_none() && self.scale.is_none() && self.rotate.is_none() {
+            return Ok(None);
+        }
+
+
+        let mut transform = Matrix4::identity();
+
+        if let Some(scale) = self.scale {
+            if !scale.is_finite() || scale <= 0.0 {
+                return Err(miette!(
+                    "Scale must be a positive number, got {}.",
+                    scale
+                ));
+            }
+
+            transform = Matrix4::new_scaling(scale) * transform;
+        }
+
+        if let Some(rotation_as_string) = self.rotate.as_ref() {
+            let (rotation_axis, rotation_angle_in_degrees) =
+                parse_str_as_axis_angle(rotation_as_string)?;
+
+            transform = Matrix4::from_axis_angle(
+                &Unit::new_normalize(rotation_axis),
+                rotation_angle_in_degrees.to_radians(),
+            ) * transform;
+        }
+
+        if let Some(translation_as_string) = self.translate.as_ref() {
+            let translation = parse_str_as_vector3(translation_as_string)?;
+
+            transform = Matrix4::new_translation(&translation) * transform;
+        }
+
+        Ok(Some(transform))
+    }
+}
+
+
+/// Maps the number of `-v` and `-q` flags to a level filter directive
+/// (`-v`: `info`, `-vv`: `debug`, `-vvv`: `trace`, `-q`: `error`, `-qq`: `off`).
+/// Returns `None` if neither flag was specified.
+pub fn verbosity_to_level_filter(verbose_count: u8, quiet_count: u8) -> Option<&'static str> {
+    match (verbose_count, quiet_count) {
+        (0, 0) => None,
+        (0, 1) => Some("error"),
+        (0, _) => Some("off"),
+        (1, _) => Some("info"),
+        (2, _) => Some("debug"),
+        (_, _) => Some("trace"),
+    }
+}
+
 
 /// Parse a string of the format `1,2.5,3` or `(1,2.0,-3.1)` into
 /// a tuple with three `f32` elements (representing `x`, `y`, and `z`).
@@ -146,3 +515,257 @@ pub fn parse_str_as_vector3(value: &str) -> Result<Vector3<f32>> {
     let (x, y, z) = parse_str_as_three_f32_points(value)?;
     Ok(Vector3::new(x, y, z))
 }
+
+/// Parse a string of the format `x,y,z,degrees` (e.g. `0,1,0,90`) into a rotation axis
+/// and an angle (in degrees). The axis must not be zero.
+pub fn parse_str_as_axis_angle(value: &str) -> Result<(Vector3<f32>, f32)> {
+    let Some((axis_as_string, angle_as_string)) = value.rsplit_once(',') else {
+        return Err(miette!(
+            "Failed to decode string to axis-angle rotation: expected format x,y,z,degrees, got {}.",
+            value
+        ));
+    };
+
+    let axis = parse_str_as_vector3(axis_as_string)?;
+    if axis.norm() <= f32::EPSILON || !axis.iter().all(|component| component.is_finite()) {
+        return Err(miette!(
+            "Rotation axis must be a finite, non-zero vector, got {}.",
+            axis_as_string
+        ));
+    }
+
+    let angle_in_degrees = angle_as_string
+        .trim()
+        .parse::<f32>()
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            miette!(
+                "Failed to parse rotation angle: {}",
+                angle_as_string
+            )
+        })?;
+
+    Ok((axis, angle_in_degrees))
+}
+
+
+/// Parse a string of the format `nx,ny,nz,d` (e.g. `0,1,0,-0.5`) into a plane normal
+/// and an offset. The normal must not be zero.
+pub fn parse_str_as_clip_plane(value: &str) -> Result<(Vector3<f32>, f32)> {
+    let Some((normal_as_string, offset_as_string)) = value.rsplit_once(',') else {
+        return Err(miette!(
+            "Failed to decode string to clip plane: expected format nx,ny,nz,d, got {}.",
+            value
+        ));
+    };
+
+    let normal = parse_str_as_vector3(normal_as_string)?;
+    if normal.norm() <= f32::EPSILON || !normal.iter().all(|component| component.is_finite()) {
+        return Err(miette!(
+            "Clip plane normal must be a finite, non-zero vector, got {}.",
+            normal_as_string
+        ));
+    }
+
+    let offset = offset_as_string
+        .trim()
+        .parse::<f32>()
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            miette!(
+                "Failed to parse clip plane offset: {}",
+                offset_as_string
+            )
+        })?;
+
+    if !offset.is_finite() {
+        return Err(miette!(
+            "Clip plane offset must be finite, got {}.",
+            offset
+        ));
+    }
+
+    Ok((normal, offset))
+}
+
+
+/// Parse a camera path file: each non-empty line contains a camera position and
+/// a look target, separated by whitespace (e.g. `(3,3,3) (0,0,0)`).
+/// Lines starting with `#` are treated as comments.
+pub fn parse_camera_path_file(
+    camera_path_file_path: &Path,
+) -> Result<Vec<(Point3<f32>, Point3<f32>)>> {
+    let file_contents = std::fs::read_to_string(camera_path_file_path)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            miette!(
+                "Failed to read camera path file: {}",
+                camera_path_file_path.display()
+            )
+        })?;
+
+
+    let mut camera_poses = Vec::new();
+
+    for (line_index, line) in file_contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let components = line.split_whitespace().collect::<Vec<_>>();
+        if components.len() != 2 {
+            return Err(miette!(
+                "Invalid camera pose on line {}: expected \"x,y,z x,y,z\", got {}.",
+                line_index + 1,
+                line
+            ));
+        }
+
+        let camera_position = parse_str_as_point3(components[0]).wrap_err_with(|| {
+            miette!(
+                "Invalid camera position on line {}.",
+                line_index + 1
+            )
+        })?;
+        let camera_look_target = parse_str_as_point3(components[1]).wrap_err_with(|| {
+            miette!(
+                "Invalid camera look target on line {}.",
+                line_index + 1
+            )
+        })?;
+
+        camera_poses.push((camera_position, camera_look_target));
+    }
+
+
+    if camera_poses.is_empty() {
+        return Err(miette!(
+            "Camera path file contains no poses: {}",
+            camera_path_file_path.display()
+        ));
+    }
+
+    Ok(camera_poses)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_counts_map_to_level_filters() {
+        assert_eq!(verbosity_to_level_filter(0, 0), None);
+        assert_eq!(verbosity_to_level_filter(1, 0), Some("info"));
+        assert_eq!(verbosity_to_level_filter(2, 0), Some("debug"));
+        assert_eq!(verbosity_to_level_filter(3, 0), Some("trace"));
+        assert_eq!(verbosity_to_level_filter(5, 0), Some("trace"));
+        assert_eq!(verbosity_to_level_filter(0, 1), Some("error"));
+        assert_eq!(verbosity_to_level_filter(0, 2), Some("off"));
+    }
+
+    #[test]
+    fn verbosity_flags_are_counted() {
+        let cli_args = CLIArgs::try_parse_from(["nrg-dn1", "-vv"]).expect("flags should parse");
+
+        assert_eq!(
+            cli_args.console_level_filter_override(),
+            Some("debug")
+        );
+    }
+
+    #[test]
+    fn verbose_and_quiet_flags_conflict() {
+        assert!(CLIArgs::try_parse_from(["nrg-dn1", "-v", "-q"]).is_err());
+    }
+
+
+    #[test]
+    fn stdin_and_input_file_path_conflict() {
+        assert!(CLIArgs::try_parse_from(["nrg-dn1", "--stdin"]).is_ok());
+        assert!(CLIArgs::try_parse_from(["nrg-dn1", "--stdin", "-i", "scene.splat"]).is_err());
+    }
+
+
+    #[test]
+    fn input_file_path_can_be_repeated() {
+        let cli_args = CLIArgs::try_parse_from(["nrg-dn1", "-i", "a.splat", "-i", "b.splat"])
+            .expect("flags should parse");
+
+        assert_eq!(
+            cli_args.input_file_paths,
+            [PathBuf::from("a.splat"), PathBuf::from("b.splat")]
+        );
+    }
+
+
+    #[test]
+    fn scene_transform_applies_scale_rotation_and_translation_in_order() {
+        let cli_args = CLIArgs::try_parse_from([
+            "nrg-dn1",
+            "--scale",
+            "2",
+            "--rotate",
+            "0,0,1,90",
+            "--translate",
+            "1,0,0",
+        ])
+        .expect("flags should parse");
+
+        let transform = cli_args
+            .scene_transform()
+            .expect("transform should be valid")
+            .expect("transform should be specified");
+        let transformed_point = transform.transform_point(&Point3::new(1.0, 0.0, 0.0));
+
+        assert!((transformed_point - Point3::new(1.0, 2.0, 0.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn scene_transform_is_unspecified_without_flags() {
+        let cli_args = CLIArgs::try_parse_from(["nrg-dn1"]).expect("flags should parse");
+
+        assert!(cli_args.scene_transform().unwrap().is_none());
+    }
+
+    #[test]
+    fn non_positive_scale_is_rejected() {
+        let cli_args =
+            CLIArgs::try_parse_from(["nrg-dn1", "--scale=-1"]).expect("flags should parse");
+
+        let error = cli_args
+            .scene_transform()
+            .expect_err("negative scale should be rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains("Scale must be a positive number"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+
+    #[test]
+    fn clip_planes_are_parsed_from_four_numbers() {
+        assert_eq!(
+            parse_str_as_clip_plane("1,0,0,-0.5").unwrap(),
+            (Vector3::new(1.0, 0.0, 0.0), -0.5)
+        );
+
+        assert!(parse_str_as_clip_plane("1,0,0").is_err());
+        assert!(parse_str_as_clip_plane("0,0,0,1").is_err());
+        assert!(parse_str_as_clip_plane("1,0,0,far").is_err());
+    }
+
+
+    #[test]
+    fn flip_axis_can_be_repeated() {
+        let cli_args = CLIArgs::try_parse_from(["nrg-dn1", "--flip-axis", "x", "--flip-axis", "z"])
+            .expect("flags should parse");
+
+        assert_eq!(cli_args.flip_axes, [Axis::X, Axis::Z]);
+    }
+}
@@ -1,26 +1,126 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use miette::{Context, IntoDiagnostic, Result};
+use miette::{miette, Context, IntoDiagnostic, Result};
 use serde::Deserialize;
 
 pub use self::base_paths::BasePathsConfiguration;
 use self::base_paths::UnresolvedBasePathsConfiguration;
-pub use self::logging::LoggingConfiguration;
+pub use self::camera::CameraConfiguration;
+use self::camera::UnresolvedCameraConfiguration;
+use self::keymap::UnresolvedKeymapConfiguration;
+pub use self::loading::LoadingConfiguration;
+use self::loading::UnresolvedLoadingConfiguration;
 use self::logging::UnresolvedLoggingConfiguration;
-pub use self::screenshot::ScreenshotConfiguration;
+pub use self::logging::{LogFileFormat, LogRotation, LoggingConfiguration};
+pub use self::performance::PerformanceConfiguration;
+use self::performance::UnresolvedPerformanceConfiguration;
+use self::rendering::UnresolvedRenderingConfiguration;
+pub use self::rendering::{
+    AlphaMode,
+    Backdrop,
+    BillboardShape,
+    BillboardSizeModel,
+    InputColorSpace,
+    RenderMode,
+    RenderingConfiguration,
+    SortKey,
+    ToneMapping,
+};
 use self::screenshot::UnresolvedScreenshotConfiguration;
+pub use self::screenshot::{ScreenshotConfiguration, ScreenshotFormat};
 use super::traits::{ResolvableConfiguration, ResolvableConfigurationWithContext};
 use super::utilities::get_default_configuration_file_path;
+use crate::renderer::keybindings::Keymap;
 
 mod base_paths;
+mod camera;
+mod keymap;
+mod loading;
 mod logging;
+mod performance;
+mod rendering;
 mod screenshot;
 
 
 
+/// Version of the configuration file format this program supports. Configuration files
+/// must declare it in the top-level `version` field.
+pub const CONFIGURATION_VERSION: u32 = 1;
+
+/// Changes to the configuration file format, as `(version, description)` pairs
+/// (the description explains how to update a file from the previous version).
+const CONFIGURATION_VERSION_CHANGES: [(u32, &str); 1] = [(
+    1,
+    "Added the top-level `version` field. The rendering, camera, loading and performance \
+     tables are optional (all of their fields have defaults).",
+)];
+
+/// Ensures that the configuration file `version` (`None` if the file does not declare one)
+/// is the one this program supports, explaining how to update the file otherwise.
+fn validate_configuration_version(version: Option<u32>) -> Result<()> {
+    let Some(version) = version else {
+        return Err(miette!(
+            help = format!(
+                "Add `version = {}` at the top of the file (before any table). Changes:\n{}",
+                CONFIGURATION_VERSION,
+                format_configuration_version_changes(0)
+            ),
+            "Configuration file does not declare its format version \
+             (it was likely written for an older version of this program)."
+        ));
+    };
+
+    if version < CONFIGURATION_VERSION {
+        return Err(miette!(
+            help = format!(
+                "Update the file and set `version = {}`. Changes:\n{}",
+                CONFIGURATION_VERSION,
+                format_configuration_version_changes(version)
+            ),
+            "Configuration file is of version {}, which is no longer supported \
+             (the current version is {}).",
+            version,
+            CONFIGURATION_VERSION
+        ));
+    }
+
+    if version > CONFIGURATION_VERSION {
+        return Err(miette!(
+            "Configuration file is of version {}, but this program only supports \
+             version {} (the file was likely written for a newer version of this program).",
+            version,
+            CONFIGURATION_VERSION
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the changes made to the configuration file format after `since_version`, one per line.
+fn format_configuration_version_changes(since_version: u32) -> String {
+    CONFIGURATION_VERSION_CHANGES
+        .iter()
+        .filter(|(version, _)| *version > since_version)
+        .map(|(version, description)| format!("- version {}: {}", version, description))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+
+/// Only the version of the configuration file, which is checked
+/// before the rest of the file is parsed (see [`validate_configuration_version`]).
+#[derive(Deserialize, Debug)]
+struct UnresolvedConfigurationVersion {
+    version: Option<u32>,
+}
+
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct UnresolvedConfiguration {
+    /// Version of the configuration file format (see [`CONFIGURATION_VERSION`]).
+    version: u32,
+
     /// Base paths.
     base_paths: UnresolvedBasePathsConfiguration,
 
@@ -29,6 +129,26 @@ pub(crate) struct UnresolvedConfiguration {
 
     /// Screenshotting configuration.
     screenshot: UnresolvedScreenshotConfiguration,
+
+    /// Rendering configuration (optional, all fields have defaults).
+    #[serde(default)]
+    rendering: UnresolvedRenderingConfiguration,
+
+    /// Camera configuration (optional, all fields have defaults).
+    #[serde(default)]
+    camera: UnresolvedCameraConfiguration,
+
+    /// Input loading configuration (optional, all fields have defaults).
+    #[serde(default)]
+    loading: UnresolvedLoadingConfiguration,
+
+    /// Performance configuration (optional, all fields have defaults).
+    #[serde(default)]
+    performance: UnresolvedPerformanceConfiguration,
+
+    /// Remapped keyboard shortcuts (optional, unlisted actions keep their default keys).
+    #[serde(default)]
+    keymap: UnresolvedKeymapConfiguration,
 }
 
 
@@ -39,6 +159,7 @@ pub struct Configuration {
     pub file_path: PathBuf,
 
     /// Base paths
+    #[allow(dead_code)]
     pub base_paths: BasePathsConfiguration,
 
     /// Logging-related configuration.
@@ -46,6 +167,21 @@ pub struct Configuration {
 
     /// Screenshotting configuration.
     pub screenshot: ScreenshotConfiguration,
+
+    /// Rendering configuration.
+    pub rendering: RenderingConfiguration,
+
+    /// Camera configuration.
+    pub camera: CameraConfiguration,
+
+    /// Input loading configuration.
+    pub loading: LoadingConfiguration,
+
+    /// Performance configuration.
+    pub performance: PerformanceConfiguration,
+
+    /// Keyboard shortcuts of the interactive window.
+    pub keymap: Keymap,
 }
 
 
@@ -54,6 +190,8 @@ impl ResolvableConfigurationWithContext for UnresolvedConfiguration {
     type Context = PathBuf;
 
     fn resolve(self, context: Self::Context) -> Result<Self::Resolved> {
+        validate_configuration_version(Some(self.version))?;
+
         let base_paths = self
             .base_paths
             .resolve()
@@ -69,12 +207,42 @@ impl ResolvableConfigurationWithContext for UnresolvedConfiguration {
             .resolve(base_paths.clone())
             .wrap_err("Failed ot resolve screenshot table.")?;
 
+        let rendering = self
+            .rendering
+            .resolve()
+            .wrap_err("Failed to resolve rendering table.")?;
+
+        let camera = self
+            .camera
+            .resolve()
+            .wrap_err("Failed to resolve camera table.")?;
+
+        let loading = self
+            .loading
+            .resolve()
+            .wrap_err("Failed to resolve loading table.")?;
+
+        let performance = self
+            .performance
+            .resolve()
+            .wrap_err("Failed to resolve performance table.")?;
+
+        let keymap = self
+            .keymap
+            .resolve()
+            .wrap_err("Failed to resolve keymap table.")?;
+
 
         Ok(Configuration {
             base_paths,
             file_path: context,
             logging,
             screenshot,
+            rendering,
+            camera,
+            loading,
+            performance,
+            keymap,
         })
     }
 }
@@ -87,18 +255,37 @@ impl Configuration {
         let configuration_string = fs::read_to_string(configuration_file_path.as_ref())
             .expect("Could not read configuration file!");
 
+        let configuration_file_path = dunce::canonicalize(configuration_file_path)
+            .into_diagnostic()
+            .wrap_err("Could not canonicalize configuration file path!")?;
+
+        Self::load_from_string(&configuration_string, configuration_file_path)
+    }
+
+    /// Load the configuration from the contents of a configuration file
+    /// (`configuration_file_path` is only recorded, not read).
+    fn load_from_string(
+        configuration_string: &str,
+        configuration_file_path: PathBuf,
+    ) -> Result<Self> {
+        // Check the version first, so outdated files are reported as such
+        // (instead of failing to parse because of some changed field).
+        let configuration_version =
+            toml::from_str::<UnresolvedConfigurationVersion>(configuration_string)
+                .into_diagnostic()
+                .wrap_err("Could not load configuration file!")?;
+
+        validate_configuration_version(configuration_version.version)
+            .wrap_err("Unsupported configuration file version.")?;
+
 
         // Parse the string into the `UnresolvedConfiguration` structure and then resolve it.
         let unresolved_configuration =
-            toml::from_str::<UnresolvedConfiguration>(&configuration_string)
+            toml::from_str::<UnresolvedConfiguration>(configuration_string)
                 .into_diagnostic()
                 .wrap_err("Could not load configuration file!")?;
 
 
-        let configuration_file_path = dunce::canonicalize(configuration_file_path)
-            .into_diagnostic()
-            .wrap_err("Could not canonicalize configuration file path!")?;
-
         let resolved_configuration = unresolved_configuration
             .resolve(configuration_file_path)
             .wrap_err("Failed to resolve configuration.")?;
@@ -113,4 +300,113 @@ impl Configuration {
                 .wrap_err_with(|| "Could not load configuration file at default path.")?,
         )
     }
+
+    /// Load a configuration for tests: the required tables (with the data directory
+    /// in the system's temporary directory), followed by the given `additional_tables`.
+    /// The `[screenshot]` table comes last, so any keys before the first table header
+    /// in `additional_tables` belong to it. Also used by the benchmarks in `benches/`.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn load_for_tests(additional_tables: &str) -> Self {
+        let base_data_directory_path = std::env::temp_dir().join("nrg-dn1-tests");
+
+        let configuration_string = format!(
+            "version = {}\n\n\
+            [base_paths]\n\
+            base_data_directory_path = {:?}\n\n\
+            [logging]\n\
+            console_output_level_filter = \"info\"\n\
+            log_file_output_level_filter = \"info\"\n\
+            log_file_output_directory = \"{{BASE_DATA_DIRECTORY}}/logs\"\n\n\
+            [screenshot]\n\
+            screenshot_directory_path = \"{{BASE_DATA_DIRECTORY}}/screenshots\"\n\n\
+            {}",
+            CONFIGURATION_VERSION,
+            base_data_directory_path.to_string_lossy(),
+            additional_tables
+        );
+
+        Self::load_from_string(&configuration_string, base_data_directory_path)
+            .expect("test configuration should be valid")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loads a minimal configuration whose top-level version line is `version_line`.
+    fn load_with_version_line(version_line: &str) -> Result<Configuration> {
+        let base_data_directory_path = std::env::temp_dir().join("nrg-dn1-tests");
+
+        let configuration_string = format!(
+            "{}\n\n\
+            [base_paths]\n\
+            base_data_directory_path = {:?}\n\n\
+            [logging]\n\
+            console_output_level_filter = \"info\"\n\
+            log_file_output_level_filter = \"info\"\n\
+            log_file_output_directory = \"{{BASE_DATA_DIRECTORY}}/logs\"\n\n\
+            [screenshot]\n\
+            screenshot_directory_path = \"{{BASE_DATA_DIRECTORY}}/screenshots\"\n",
+            version_line,
+            base_data_directory_path.to_string_lossy()
+        );
+
+        Configuration::load_from_string(&configuration_string, base_data_directory_path)
+    }
+
+    /// Returns whether any error in the chain of `error` contains `message`.
+    fn error_chain_contains(error: &miette::Report, message: &str) -> bool {
+        error
+            .chain()
+            .any(|cause| cause.to_string().contains(message))
+    }
+
+    #[test]
+    fn current_configuration_version_is_accepted() {
+        assert!(load_with_version_line(&format!("version = {}", CONFIGURATION_VERSION)).is_ok());
+    }
+
+    #[test]
+    fn missing_configuration_version_is_rejected() {
+        let error = load_with_version_line("").expect_err("missing version should be rejected");
+
+        assert!(
+            error_chain_contains(&error, "does not declare its format version"),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn unsupported_configuration_versions_are_rejected() {
+        let error =
+            load_with_version_line("version = 0").expect_err("old version should be rejected");
+        assert!(
+            error_chain_contains(
+                &error,
+                "is of version 0, which is no longer supported"
+            ),
+            "unexpected error: {:?}",
+            error
+        );
+
+        let error = load_with_version_line(&format!(
+            "version = {}",
+            CONFIGURATION_VERSION + 1
+        ))
+        .expect_err("newer version should be rejected");
+        assert!(
+            error_chain_contains(&error, "only supports version"),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn version_changes_since_an_old_version_are_listed() {
+        assert!(format_configuration_version_changes(0).starts_with("- version 1: "));
+        assert!(format_configuration_version_changes(CONFIGURATION_VERSION).is_empty());
+    }
 }
@@ -3,7 +3,10 @@ use std::{collections::HashMap, path::PathBuf};
 use miette::{miette, Context, IntoDiagnostic, Result};
 use serde::Deserialize;
 
-use crate::configuration::traits::ResolvableConfiguration;
+use crate::configuration::{
+    traits::ResolvableConfiguration,
+    utilities::expand_environment_variables,
+};
 
 
 #[derive(Deserialize, Debug)]
@@ -20,7 +23,10 @@ impl ResolvableConfiguration for UnresolvedBasePathsConfiguration {
     type Resolved = BasePathsConfiguration;
 
     fn resolve(self) -> Result<Self::Resolved> {
-        let base_data_directory_path = PathBuf::from(self.base_data_directory_path);
+        let base_data_directory_path = PathBuf::from(
+            expand_environment_variables(&self.base_data_directory_path)
+                .wrap_err("Failed to resolve field base_data_directory_path.")?,
+        );
 
         if base_data_directory_path.exists() && !base_data_directory_path.is_dir() {
             return Err(miette!(
@@ -0,0 +1,260 @@
+use miette::{miette, Context, Result};
+use nalgebra::{Point3, Vector3};
+use serde::Deserialize;
+
+use crate::{cli::parse_str_as_vector3, configuration::traits::ResolvableConfiguration};
+
+
+/// A 3D vector in the configuration file, either as a `"x,y,z"` string or an `[x, y, z]` array.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+enum UnresolvedVector3 {
+    Components([f32; 3]),
+    Text(String),
+}
+
+impl UnresolvedVector3 {
+    /// Parses the vector, ensuring all of its components are finite
+    /// (`field_name` is only used in error messages).
+    fn resolve(&self, field_name: &str) -> Result<Vector3<f32>> {
+        let vector = match self {
+            Self::Components([x, y, z]) => Vector3::new(*x, *y, *z),
+            Self::Text(text) => parse_str_as_vector3(text).wrap_err_with(|| {
+                miette!(
+                    "Field {} must be formatted as \"x,y,z\".",
+                    field_name
+                )
+            })?,
+        };
+
+        if !vector.iter().all(|component| component.is_finite()) {
+            return Err(miette!(
+                "Field {} must only contain finite numbers, got {:?}.",
+                field_name,
+                vector
+            ));
+        }
+
+        Ok(vector)
+    }
+}
+
+
+#[derive(Deserialize, Clone, Debug)]
+pub(super) struct UnresolvedCameraConfiguration {
+    /// How many radians the camera orbits for each pixel the mouse is dragged.
+    /// Defaults to `0.005`.
+    #[serde(default = "UnresolvedCameraConfiguration::default_orbit_sensitivity")]
+    orbit_sensitivity: f32,
+
+    /// How far (in world units) the camera moves on each movement or zoom step.
+    /// Defaults to `0.1`.
+    #[serde(default = "UnresolvedCameraConfiguration::default_movement_speed")]
+    movement_speed: f32,
+
+    /// Vertical field of view of the camera, in degrees.
+    /// Must be between 0 and 180 (exclusive). Defaults to `45`.
+    #[serde(default = "UnresolvedCameraConfiguration::default_vertical_field_of_view")]
+    vertical_field_of_view: f32,
+
+    /// Initial camera position, either as `"x,y,z"` or `[x, y, z]`.
+    /// Overridden by `--camera-position`. Unspecified by default (`3,3,3`).
+    #[serde(default)]
+    position: Option<UnresolvedVector3>,
+
+    /// Initial point the camera looks at, either as `"x,y,z"` or `[x, y, z]`.
+    /// Overridden by `--camera-look-target`. Unspecified by default (the centroid of the splats).
+    #[serde(default)]
+    look_target: Option<UnresolvedVector3>,
+
+    /// Initial up vector of the camera, either as `"x,y,z"` or `[x, y, z]`. Must not be zero.
+    /// Overridden by `--initial-up-vector`. Unspecified by default (`0,1,0`).
+    #[serde(default)]
+    up_vector: Option<UnresolvedVector3>,
+}
+
+impl UnresolvedCameraConfiguration {
+    fn default_orbit_sensitivity() -> f32 {
+        0.005
+    }
+
+    fn default_movement_speed() -> f32 {
+        0.1
+    }
+
+    fn default_vertical_field_of_view() -> f32 {
+        45.0
+    }
+}
+
+impl Default for UnresolvedCameraConfiguration {
+    fn default() -> Self {
+        Self {
+            orbit_sensitivity: Self::default_orbit_sensitivity(),
+            movement_speed: Self::default_movement_speed(),
+            vertical_field_of_view: Self::default_vertical_field_of_view(),
+            position: None,
+            look_target: None,
+            up_vector: None,
+        }
+    }
+}
+
+
+#[derive(Clone, Debug)]
+pub struct CameraConfiguration {
+    pub orbit_sensitivity: f32,
+
+    pub movement_speed: f32,
+
+    /// Vertical field of view, in degrees.
+    pub vertical_field_of_view_in_degrees: f32,
+
+    /// Initial camera position, if configured.
+    pub initial_position: Option<Point3<f32>>,
+
+    /// Initial camera look target, if configured.
+    pub initial_look_target: Option<Point3<f32>>,
+
+    /// Initial (non-zero) camera up vector, if configured.
+    pub initial_up_vector: Option<Vector3<f32>>,
+}
+
+impl ResolvableConfiguration for UnresolvedCameraConfiguration {
+    type Resolved = CameraConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        if !self.orbit_sensitivity.is_finite() || self.orbit_sensitivity <= 0.0 {
+            return Err(miette!(
+                "Field orbit_sensitivity must be a positive number, got {}.",
+                self.orbit_sensitivity
+            ));
+        }
+
+
+        if !self.movement_speed.is_finite() || self.movement_speed <= 0.0 {
+            return Err(miette!(
+                "Field movement_speed must be a positive number, got {}.",
+                self.movement_speed
+            ));
+        }
+
+
+        if !self.vertical_field_of_view.is_finite()
+            || self.vertical_field_of_view <= 0.0
+            || self.vertical_field_of_view >= 180.0
+        {
+            return Err(miette!(
+                "Field vertical_field_of_view must be between 0 and 180 degrees (exclusive), got {}.",
+                self.vertical_field_of_view
+            ));
+        }
+
+
+        let initial_position = self
+            .position
+            .map(|position| position.resolve("position"))
+            .transpose()?
+            .map(Point3::from);
+
+        let initial_look_target = self
+            .look_target
+            .map(|look_target| look_target.resolve("look_target"))
+            .transpose()?
+            .map(Point3::from);
+
+        let initial_up_vector = self
+            .up_vector
+            .map(|up_vector| up_vector.resolve("up_vector"))
+            .transpose()?;
+
+        if let Some(initial_up_vector) = initial_up_vector {
+            if initial_up_vector.norm() <= f32::EPSILON {
+                return Err(miette!(
+                    "Field up_vector must not be a zero vector, got {:?}.",
+                    initial_up_vector
+                ));
+            }
+        }
+
+
+        Ok(Self::Resolved {
+            orbit_sensitivity: self.orbit_sensitivity,
+            movement_speed: self.movement_speed,
+            vertical_field_of_view_in_degrees: self.vertical_field_of_view,
+            initial_position,
+            initial_look_target,
+            initial_up_vector,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_of_view_defaults_to_45_degrees() {
+        let camera_configuration = UnresolvedCameraConfiguration::default().resolve().unwrap();
+
+        assert_eq!(
+            camera_configuration.vertical_field_of_view_in_degrees,
+            45.0
+        );
+    }
+
+    #[test]
+    fn field_of_view_outside_of_the_valid_range_is_rejected() {
+        for vertical_field_of_view in [0.0, -10.0, 180.0, f32::NAN] {
+            let camera_configuration = UnresolvedCameraConfiguration {
+                vertical_field_of_view,
+                ..Default::default()
+            };
+
+            assert!(
+                camera_configuration.resolve().is_err(),
+                "field of view {} was accepted",
+                vertical_field_of_view
+            );
+        }
+    }
+
+
+    #[test]
+    fn camera_vectors_can_be_strings_or_arrays() {
+        let camera_configuration = toml::from_str::<UnresolvedCameraConfiguration>(
+            "position = \"1,2,3\"\nlook_target = [4.0, 5.0, 6.0]\n",
+        )
+        .unwrap()
+        .resolve()
+        .unwrap();
+
+        assert_eq!(
+            camera_configuration.initial_position,
+            Some(Point3::new(1.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            camera_configuration.initial_look_target,
+            Some(Point3::new(4.0, 5.0, 6.0))
+        );
+        assert_eq!(camera_configuration.initial_up_vector, None);
+    }
+
+    #[test]
+    fn zero_up_vector_is_rejected() {
+        let camera_configuration =
+            toml::from_str::<UnresolvedCameraConfiguration>("up_vector = [0.0, 0.0, 0.0]\n")
+                .unwrap();
+
+        let error = camera_configuration
+            .resolve()
+            .expect_err("zero up vector should be rejected");
+
+        assert!(
+            error.to_string().contains("must not be a zero vector"),
+            "unexpected error: {}",
+            error
+        );
+    }
+}
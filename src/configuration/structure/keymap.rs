@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+use miette::Result;
+use serde::Deserialize;
+
+use crate::configuration::traits::ResolvableConfiguration;
+use crate::renderer::keybindings::Keymap;
+
+
+/// Remapped keyboard shortcuts, from the action name (e.g. `move_forward`) to the key
+/// (in the format of [`KeyBinding::key`](crate::renderer::keybindings::KeyBinding::key)).
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub(super) struct UnresolvedKeymapConfiguration {
+    remapped_keys: HashMap<String, String>,
+}
+
+
+impl ResolvableConfiguration for UnresolvedKeymapConfiguration {
+    type Resolved = Keymap;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        Keymap::new(&self.remapped_keys)
+    }
+}
@@ -0,0 +1,55 @@
+use miette::Result;
+use serde::Deserialize;
+
+use crate::configuration::traits::ResolvableConfiguration;
+use crate::splat_decoder::DEFAULT_RANDOM_SEED;
+
+
+#[derive(Deserialize, Clone, Debug)]
+pub(super) struct UnresolvedLoadingConfiguration {
+    /// Whether to always keep the parsed splats in their original file order.
+    /// Parsing happens in parallel, so without this the order is not deterministic
+    /// (except in debug builds, which always reorder). Defaults to `false`.
+    #[serde(default)]
+    deterministic: bool,
+
+    /// Seed of the pseudo-random generator used by randomized operations
+    /// (e.g. random downsampling), which makes them reproducible across runs.
+    /// Can be overridden with `--seed`.
+    #[serde(default = "UnresolvedLoadingConfiguration::default_seed")]
+    seed: u64,
+}
+
+impl UnresolvedLoadingConfiguration {
+    fn default_seed() -> u64 {
+        DEFAULT_RANDOM_SEED
+    }
+}
+
+impl Default for UnresolvedLoadingConfiguration {
+    fn default() -> Self {
+        Self {
+            deterministic: false,
+            seed: Self::default_seed(),
+        }
+    }
+}
+
+
+#[derive(Clone, Debug)]
+pub struct LoadingConfiguration {
+    pub deterministic: bool,
+
+    pub seed: u64,
+}
+
+impl ResolvableConfiguration for UnresolvedLoadingConfiguration {
+    type Resolved = LoadingConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        Ok(Self::Resolved {
+            deterministic: self.deterministic,
+            seed: self.seed,
+        })
+    }
+}
@@ -11,6 +11,38 @@ use crate::configuration::{
 };
 
 
+/// Format of the log file output.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFileFormat {
+    /// Human-readable lines (the default).
+    #[default]
+    Pretty,
+
+    /// One JSON object per line (for log ingestion).
+    Json,
+}
+
+
+/// How often a new log file is started.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// Always write to a single log file.
+    Never,
+
+    Minutely,
+
+    Hourly,
+
+    /// Start a new log file every day (the default).
+    #[default]
+    Daily,
+
+    Weekly,
+}
+
+
 #[derive(Deserialize, Clone, Debug)]
 pub(super) struct UnresolvedLoggingConfiguration {
     console_output_level_filter: String,
@@ -18,6 +50,21 @@ pub(super) struct UnresolvedLoggingConfiguration {
     log_file_output_level_filter: String,
 
     log_file_output_directory: String,
+
+    /// Format of the log file: `pretty` or `json`. Defaults to `pretty`.
+    /// Console output is not affected.
+    #[serde(default)]
+    log_file_format: LogFileFormat,
+
+    /// How often a new log file is started: `never`, `minutely`, `hourly`, `daily` or `weekly`.
+    /// Defaults to `daily`.
+    #[serde(default)]
+    log_rotation: LogRotation,
+
+    /// Maximum number of log files to keep (older ones are deleted when rotating).
+    /// If unspecified, all log files are kept.
+    #[serde(default)]
+    log_max_files: Option<usize>,
 }
 
 #[derive(Clone, Debug)]
@@ -27,6 +74,12 @@ pub struct LoggingConfiguration {
     pub log_file_output_level_filter: String,
 
     pub log_file_output_directory: PathBuf,
+
+    pub log_file_format: LogFileFormat,
+
+    pub log_rotation: LogRotation,
+
+    pub log_max_files: Option<usize>,
 }
 
 impl ResolvableConfigurationWithContext for UnresolvedLoggingConfiguration {
@@ -44,16 +97,33 @@ impl ResolvableConfigurationWithContext for UnresolvedLoggingConfiguration {
             .wrap_err_with(|| miette!("Failed to parse field log_file_output_level_filter"))?;
 
 
+        if let Some(log_max_files) = self.log_max_files {
+            if log_max_files == 0 {
+                return Err(miette!("Field log_max_files must be at least 1."));
+            }
+
+            if self.log_rotation == LogRotation::Never {
+                return Err(miette!(
+                    "Field log_max_files has no effect when log_rotation is \"never\"."
+                ));
+            }
+        }
+
+
         let log_file_output_directory = replace_placeholders_in_path(
             self.log_file_output_directory,
             context.placeholders_map(),
-        );
+        )
+        .wrap_err("Failed to resolve field log_file_output_directory.")?;
 
 
         Ok(Self::Resolved {
             console_output_level_filter: self.console_output_level_filter,
             log_file_output_level_filter: self.log_file_output_level_filter,
             log_file_output_directory,
+            log_file_format: self.log_file_format,
+            log_rotation: self.log_rotation,
+            log_max_files: self.log_max_files,
         })
     }
 }
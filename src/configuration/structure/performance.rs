@@ -0,0 +1,51 @@
+use miette::Result;
+use serde::Deserialize;
+
+use crate::configuration::traits::ResolvableConfiguration;
+
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub(super) struct UnresolvedPerformanceConfiguration {
+    /// Number of threads to use for parallel work (parsing, projection, sorting and compositing).
+    /// `0` means "all cores". If unspecified, rayon's default is used (also all cores,
+    /// unless overridden with the `RAYON_NUM_THREADS` environment variable).
+    #[serde(default)]
+    threads: Option<usize>,
+}
+
+
+#[derive(Clone, Debug)]
+pub struct PerformanceConfiguration {
+    pub threads: Option<usize>,
+}
+
+impl ResolvableConfiguration for UnresolvedPerformanceConfiguration {
+    type Resolved = PerformanceConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        Ok(Self::Resolved {
+            threads: self.threads,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::Configuration;
+
+    #[test]
+    fn thread_count_defaults_to_unspecified() {
+        assert_eq!(
+            Configuration::load_for_tests("").performance.threads,
+            None
+        );
+    }
+
+    #[test]
+    fn thread_count_is_read_from_the_performance_table() {
+        let configuration = Configuration::load_for_tests("[performance]\nthreads = 2\n");
+
+        assert_eq!(configuration.performance.threads, Some(2));
+    }
+}
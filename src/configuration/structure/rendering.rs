@@ -0,0 +1,759 @@
+use miette::{miette, Context, IntoDiagnostic, Result};
+use serde::Deserialize;
+
+use crate::configuration::traits::ResolvableConfiguration;
+
+
+/// How the size of billboards depends on the distance `d` of the splat from the camera.
+///
+/// Square billboards (used for splats without a usable rotation) use the model directly.
+/// Elliptical billboards are projected with perspective (which already matches `1 / d`),
+/// so the other models scale them by their ratio to `1 / d`.
+#[derive(
+    Deserialize,
+    clap::ValueEnum,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Default
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum BillboardSizeModel {
+    /// Size is proportional to `1 / d` (the default).
+    #[default]
+    InverseDistance,
+
+    /// Size is proportional to `1 / d²`, shrinking distant splats faster.
+    InverseSquareDistance,
+
+    /// Size decreases linearly with `d`, reaching zero at the far clipping plane.
+    ClampedLinear,
+}
+
+
+/// Shape of square billboards (used for splats without a usable rotation),
+/// inscribed in the billboard square. The Gaussian falloff follows the shape as well.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BillboardShape {
+    /// The whole square is covered (the default).
+    #[default]
+    Square,
+
+    /// Only the circle inscribed in the square is covered.
+    Circle,
+
+    /// Only the diamond (the square rotated by 45 degrees) inscribed in the square is covered.
+    Diamond,
+}
+
+
+/// How splats are drawn onto the canvas.
+#[derive(
+    Deserialize,
+    clap::ValueEnum,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Default
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum RenderMode {
+    /// Splats are alpha-composited as Gaussian billboards (the default).
+    #[default]
+    Billboards,
+
+    /// Debugging aid: each splat is drawn as a single opaque pixel at its projected center,
+    /// ignoring its billboard size and alpha.
+    Points,
+}
+
+
+/// Color space the (RGB) colors of the input splats are encoded in.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InputColorSpace {
+    /// Colors are sRGB-encoded (the default).
+    #[default]
+    Srgb,
+
+    /// Colors are linear. Splats are blended in linear color space and
+    /// the result is sRGB-encoded (regardless of `linear_blending`).
+    Linear,
+}
+
+
+/// How the alpha of the input splat colors relates to their (RGB) color.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AlphaMode {
+    /// Colors are not multiplied by alpha (the default).
+    #[default]
+    Straight,
+
+    /// Colors are already multiplied by alpha, so they are not multiplied again when blending.
+    Premultiplied,
+}
+
+
+/// Tone mapping operator applied to the composited frame (see `tone_mapping` in the configuration).
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ToneMapping {
+    /// Colors are left as composited (the default).
+    #[default]
+    None,
+
+    /// The Reinhard operator `c / (1 + c)`, applied to the linear colors multiplied by the exposure.
+    /// Bright colors are compressed smoothly instead of clipping to white.
+    Reinhard,
+}
+
+
+/// The order splats are composited in (the first splat is drawn first).
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// Back to front, by distance from the camera (the default).
+    #[default]
+    Depth,
+
+    /// By ascending alpha, so the most opaque splats are drawn last.
+    /// Splats with equal alpha are drawn back to front.
+    Alpha,
+
+    /// By descending billboard size (on screen), so the smallest splats are drawn last.
+    /// Splats of equal size are drawn back to front.
+    Size,
+}
+
+
+/// What the canvas is filled with before the splats are drawn.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Backdrop {
+    /// The background color (the default).
+    #[default]
+    Solid,
+
+    /// A two-tone gray checkerboard, which makes (semi-)transparent areas of the splats easy to spot.
+    Checker,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub(super) struct UnresolvedRenderingConfiguration {
+    /// Standard deviation of the Gaussian falloff, relative to the billboard extent.
+    /// Defaults to `0.5`, meaning the edge of each billboard lies at two standard deviations.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_gaussian_falloff_sigma")]
+    gaussian_falloff_sigma: f32,
+
+    /// Whether to depth-test splats against a per-pixel depth buffer.
+    /// Fully opaque splats write their distance into the buffer, and pixels of splats
+    /// that are further away than the stored distance are not drawn. Defaults to `false`.
+    #[serde(default)]
+    depth_test: bool,
+
+    /// Color the canvas is cleared to before drawing the splats.
+    /// Either a hex string (`#RRGGBB` or `#RRGGBBAA`) or a list of
+    /// components (`r,g,b` or `r,g,b,a`). Defaults to opaque black.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_background_color")]
+    background_color: String,
+
+    /// What the canvas is filled with before drawing the splats: `solid` (the background color)
+    /// or `checker` (a gray checkerboard, for inspecting transparency). Defaults to `solid`.
+    #[serde(default)]
+    backdrop: Backdrop,
+
+    /// Size of the checkerboard tiles (in output pixels) when `backdrop` is `checker`. Defaults to `16`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_checker_tile_size")]
+    checker_tile_size: u32,
+
+    /// Whether to alpha-blend splats in linear color space (converting the sRGB colors
+    /// to linear before blending and back to sRGB afterwards). Defaults to `false`,
+    /// which blends the sRGB values directly.
+    #[serde(default)]
+    linear_blending: bool,
+
+    /// Color space of the input splat colors: `srgb` or `linear`. Defaults to `srgb`.
+    #[serde(default)]
+    input_color_space: InputColorSpace,
+
+    /// Whether the input splat colors use `straight` or `premultiplied` alpha.
+    /// Defaults to `straight`.
+    #[serde(default)]
+    alpha_mode: AlphaMode,
+
+    /// Tone mapping applied to the composited frame (in linear color space): `none` or `reinhard`.
+    /// Defaults to `none`.
+    #[serde(default)]
+    tone_mapping: ToneMapping,
+
+    /// Multiplier applied to the linear colors before tone mapping (only used by the `reinhard`
+    /// operator). Must be a positive number, defaults to `1.0`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_exposure")]
+    exposure: f32,
+
+    /// How splats are drawn: `billboards` or `points` (a single opaque pixel per splat,
+    /// for diagnosing splat placement). Defaults to `billboards`.
+    #[serde(default)]
+    render_mode: RenderMode,
+
+    /// The order splats are drawn in: `depth` (back to front), `alpha` (most opaque last)
+    /// or `size` (largest billboards first). Anything other than `depth` is meant for
+    /// visualizations, since the result is no longer a correct alpha-composited image.
+    /// Defaults to `depth`.
+    #[serde(default)]
+    sort_key: SortKey,
+
+    /// Whether to initially draw the world axes (X in red, Y in green, Z in blue)
+    /// from the origin over the splats. Can be toggled at runtime. Defaults to `false`.
+    #[serde(default)]
+    show_axes: bool,
+
+    /// How the size of billboards depends on the distance from the camera:
+    /// `inverse-distance`, `inverse-square-distance` or `clamped-linear`.
+    /// Defaults to `inverse-distance`.
+    #[serde(default)]
+    billboard_size_model: BillboardSizeModel,
+
+    /// Shape of square billboards: `square`, `circle` or `diamond` (inscribed in the square).
+    /// Elliptical billboards of splats with a usable rotation are not affected. Defaults to `square`.
+    #[serde(default)]
+    billboard_shape: BillboardShape,
+
+    /// Largest allowed billboard size (in pixels). Larger billboards (e.g. of splats
+    /// very close to the camera) are shrunk to this size. Defaults to `256`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_max_billboard_size_pixels")]
+    max_billboard_size_pixels: u32,
+
+    /// Smallest allowed billboard size (in pixels), so distant splats don't vanish.
+    /// Defaults to `1`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_min_billboard_size_pixels")]
+    min_billboard_size_pixels: u32,
+
+    /// Supersampling anti-aliasing factor: splats are rendered at this multiple of the output
+    /// resolution (in each dimension) and then downsampled. Between 1 and 4, defaults to `1` (off).
+    #[serde(default = "UnresolvedRenderingConfiguration::default_ssaa_factor")]
+    ssaa_factor: u32,
+
+    /// Debugging aid: instead of culling splats whose centers project outside the viewport,
+    /// clamp them to the nearest border pixel (and log how many were clamped). Defaults to `false`.
+    #[serde(default)]
+    clamp_offscreen: bool,
+
+    /// Optional interactive frame budget (in milliseconds). If a full render takes longer,
+    /// only a subset of the splats is drawn while the camera is moving,
+    /// and the full scene is rendered once it stops. Screenshots are always rendered in full.
+    /// Unspecified by default (every frame is rendered in full).
+    #[serde(default)]
+    frame_budget_milliseconds: Option<f32>,
+
+    /// Largest allowed render width and height (in pixels). Guards against accidentally
+    /// allocating huge frame buffers (e.g. because of a typo in `--render-width`).
+    /// Defaults to `8192`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_max_render_dimension")]
+    max_render_dimension: u32,
+
+    /// Whether to enable distance-based level of detail: the splats are partitioned into
+    /// a spatial grid at load, and cells further away from the camera only contribute
+    /// a (representative) subset of their splats to each render, screenshots included.
+    /// Meant for very large scenes. Defaults to `false`.
+    #[serde(default)]
+    lod_enabled: bool,
+
+    /// If the scene contains more splats than this and level of detail is not enabled,
+    /// a warning suggesting `lod_enabled` is emitted at startup. Defaults to `10000000`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_splat_count_warning_threshold")]
+    splat_count_warning_threshold: usize,
+
+    /// Distance of the near clipping plane from the camera. Defaults to `0.1`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_near_plane")]
+    near_plane: f32,
+
+    /// Distance of the far clipping plane from the camera. Defaults to `100.0`.
+    #[serde(default = "UnresolvedRenderingConfiguration::default_far_plane")]
+    far_plane: f32,
+}
+
+impl UnresolvedRenderingConfiguration {
+    fn default_gaussian_falloff_sigma() -> f32 {
+        0.5
+    }
+
+    fn default_background_color() -> String {
+        "#000000FF".to_string()
+    }
+
+    fn default_checker_tile_size() -> u32 {
+        16
+    }
+
+    fn default_exposure() -> f32 {
+        1.0
+    }
+
+    fn default_max_billboard_size_pixels() -> u32 {
+        256
+    }
+
+    fn default_min_billboard_size_pixels() -> u32 {
+        1
+    }
+
+    fn default_ssaa_factor() -> u32 {
+        1
+    }
+
+    fn default_max_render_dimension() -> u32 {
+        8192
+    }
+
+    fn default_splat_count_warning_threshold() -> usize {
+        10_000_000
+    }
+
+    fn default_near_plane() -> f32 {
+        0.1
+    }
+
+    fn default_far_plane() -> f32 {
+        100.0
+    }
+}
+
+impl Default for UnresolvedRenderingConfiguration {
+    fn default() -> Self {
+        Self {
+            gaussian_falloff_sigma: Self::default_gaussian_falloff_sigma(),
+            depth_test: false,
+            background_color: Self::default_background_color(),
+            backdrop: Backdrop::default(),
+            checker_tile_size: Self::default_checker_tile_size(),
+            linear_blending: false,
+            input_color_space: InputColorSpace::default(),
+            alpha_mode: AlphaMode::default(),
+            tone_mapping: ToneMapping::default(),
+            exposure: Self::default_exposure(),
+            render_mode: RenderMode::default(),
+            sort_key: SortKey::default(),
+            show_axes: false,
+            billboard_size_model: BillboardSizeModel::default(),
+            billboard_shape: BillboardShape::default(),
+            max_billboard_size_pixels: Self::default_max_billboard_size_pixels(),
+            min_billboard_size_pixels: Self::default_min_billboard_size_pixels(),
+            ssaa_factor: Self::default_ssaa_factor(),
+            clamp_offscreen: false,
+            frame_budget_milliseconds: None,
+            max_render_dimension: Self::default_max_render_dimension(),
+            lod_enabled: false,
+            splat_count_warning_threshold: Self::default_splat_count_warning_threshold(),
+            near_plane: Self::default_near_plane(),
+            far_plane: Self::default_far_plane(),
+        }
+    }
+}
+
+
+#[derive(Clone, Debug)]
+pub struct RenderingConfiguration {
+    pub gaussian_falloff_sigma: f32,
+
+    pub depth_test: bool,
+
+    /// RGBA background color.
+    pub background_color: [u8; 4],
+
+    /// What the canvas is filled with before drawing the splats.
+    pub backdrop: Backdrop,
+
+    /// Size of the checkerboard tiles (in output pixels).
+    pub checker_tile_size: u32,
+
+    /// Whether to alpha-blend in linear color space.
+    pub linear_blending: bool,
+
+    /// Color space of the input splat colors.
+    pub input_color_space: InputColorSpace,
+
+    /// Whether the input splat colors use straight or premultiplied alpha.
+    pub alpha_mode: AlphaMode,
+
+    /// Tone mapping applied to the composited frame.
+    pub tone_mapping: ToneMapping,
+
+    /// Multiplier applied to the linear colors before tone mapping.
+    pub exposure: f32,
+
+    /// How splats are drawn.
+    pub render_mode: RenderMode,
+
+    /// The order splats are drawn in.
+    pub sort_key: SortKey,
+
+    /// Whether to initially draw the world axes over the splats.
+    pub show_axes: bool,
+
+    /// How the size of square billboards depends on the distance from the camera.
+    pub billboard_size_model: BillboardSizeModel,
+
+    /// Shape of square billboards.
+    pub billboard_shape: BillboardShape,
+
+    /// Largest allowed billboard size (in pixels).
+    pub max_billboard_size_pixels: u32,
+
+    /// Smallest allowed billboard size (in pixels).
+    pub min_billboard_size_pixels: u32,
+
+    /// Supersampling anti-aliasing factor (`1` means no supersampling).
+    pub ssaa_factor: u32,
+
+    /// Whether to clamp off-screen splats to the viewport border instead of culling them.
+    pub clamp_offscreen: bool,
+
+    /// Interactive frame budget (in milliseconds), if any.
+    pub frame_budget_milliseconds: Option<f32>,
+
+    /// Largest allowed render width and height (in pixels).
+    pub max_render_dimension: u32,
+
+    /// Whether distance-based level of detail is enabled.
+    pub lod_enabled: bool,
+
+    /// Splat count above which a warning is emitted if level of detail is not enabled.
+    pub splat_count_warning_threshold: usize,
+
+    /// Distance of the near clipping plane from the camera.
+    pub near_plane: f32,
+
+    /// Distance of the far clipping plane from the camera.
+    pub far_plane: f32,
+}
+
+impl RenderingConfiguration {
+    /// Returns whether splats are blended in linear color space, either because
+    /// linear blending was requested or because the input colors are linear.
+    pub fn blends_in_linear_color_space(&self) -> bool {
+        self.linear_blending || self.input_color_space == InputColorSpace::Linear
+    }
+
+    /// Ensures that the supersampling factor is between 1 and 4.
+    pub fn validate_ssaa_factor(ssaa_factor: u32) -> Result<()> {
+        if !(1..=4).contains(&ssaa_factor) {
+            return Err(miette!(
+                "Supersampling factor must be between 1 and 4, got {}.",
+                ssaa_factor
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that both render dimensions are between 1 and `max_render_dimension` (inclusive).
+    pub fn validate_render_dimensions(
+        render_width: u32,
+        render_height: u32,
+        max_render_dimension: u32,
+    ) -> Result<()> {
+        for (dimension_name, dimension) in [("width", render_width), ("height", render_height)] {
+            if dimension == 0 || dimension > max_render_dimension {
+                return Err(miette!(
+                    "Render {} must be between 1 and {} pixels, got {}.",
+                    dimension_name,
+                    max_render_dimension,
+                    dimension
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that `0 < near_plane < far_plane` (and that both are finite).
+    pub fn validate_clip_planes(near_plane: f32, far_plane: f32) -> Result<()> {
+        if !near_plane.is_finite() || !far_plane.is_finite() {
+            return Err(miette!(
+                "Clipping planes must be finite, got near {} and far {}.",
+                near_plane,
+                far_plane
+            ));
+        }
+
+        if near_plane <= 0.0 || near_plane >= far_plane {
+            return Err(miette!(
+                "Clipping planes must satisfy 0 < near < far, got near {} and far {}.",
+                near_plane,
+                far_plane
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ResolvableConfiguration for UnresolvedRenderingConfiguration {
+    type Resolved = RenderingConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        if !self.gaussian_falloff_sigma.is_finite() || self.gaussian_falloff_sigma <= 0.0 {
+            return Err(miette!(
+                "Field gaussian_falloff_sigma must be a positive number, got {}.",
+                self.gaussian_falloff_sigma
+            ));
+        }
+
+
+        let background_color = parse_rgba_color(&self.background_color)
+            .wrap_err("Failed to parse field background_color.")?;
+
+        if self.checker_tile_size == 0 {
+            return Err(miette!(
+                "Field checker_tile_size must be a positive number, got {}.",
+                self.checker_tile_size
+            ));
+        }
+
+
+        if !self.exposure.is_finite() || self.exposure <= 0.0 {
+            return Err(miette!(
+                "Field exposure must be a positive number, got {}.",
+                self.exposure
+            ));
+        }
+
+
+        if self.max_billboard_size_pixels == 0
+            || self.min_billboard_size_pixels > self.max_billboard_size_pixels
+        {
+            return Err(miette!(
+                "Fields min_billboard_size_pixels and max_billboard_size_pixels must satisfy \
+                 min <= max and max > 0, got min {} and max {}.",
+                self.min_billboard_size_pixels,
+                self.max_billboard_size_pixels
+            ));
+        }
+
+
+        RenderingConfiguration::validate_ssaa_factor(self.ssaa_factor)
+            .wrap_err("Invalid field ssaa_factor.")?;
+
+
+        if let Some(frame_budget_milliseconds) = self.frame_budget_milliseconds {
+            if !frame_budget_milliseconds.is_finite() || frame_budget_milliseconds <= 0.0 {
+                return Err(miette!(
+                    "Field frame_budget_milliseconds must be a positive number, got {}.",
+                    frame_budget_milliseconds
+                ));
+            }
+        }
+
+
+        if self.max_render_dimension == 0 {
+            return Err(miette!(
+                "Field max_render_dimension must be a positive number, got {}.",
+                self.max_render_dimension
+            ));
+        }
+
+
+        RenderingConfiguration::validate_clip_planes(self.near_plane, self.far_plane)
+            .wrap_err("Invalid fields near_plane and far_plane.")?;
+
+
+        Ok(Self::Resolved {
+            gaussian_falloff_sigma: self.gaussian_falloff_sigma,
+            depth_test: self.depth_test,
+            background_color,
+            backdrop: self.backdrop,
+            checker_tile_size: self.checker_tile_size,
+            linear_blending: self.linear_blending,
+            input_color_space: self.input_color_space,
+            alpha_mode: self.alpha_mode,
+            tone_mapping: self.tone_mapping,
+            exposure: self.exposure,
+            render_mode: self.render_mode,
+            sort_key: self.sort_key,
+            show_axes: self.show_axes,
+            billboard_size_model: self.billboard_size_model,
+            billboard_shape: self.billboard_shape,
+            max_billboard_size_pixels: self.max_billboard_size_pixels,
+            min_billboard_size_pixels: self.min_billboard_size_pixels,
+            ssaa_factor: self.ssaa_factor,
+            clamp_offscreen: self.clamp_offscreen,
+            frame_budget_milliseconds: self.frame_budget_milliseconds,
+            max_render_dimension: self.max_render_dimension,
+            lod_enabled: self.lod_enabled,
+            splat_count_warning_threshold: self.splat_count_warning_threshold,
+            near_plane: self.near_plane,
+            far_plane: self.far_plane,
+        })
+    }
+}
+
+
+/// Parses a color of the format `#RRGGBB`, `#RRGGBBAA`, `r,g,b` or `r,g,b,a`.
+/// If the alpha component is omitted, it defaults to 255 (opaque).
+fn parse_rgba_color(value: &str) -> Result<[u8; 4]> {
+    let value = value.trim();
+
+    if let Some(hex_digits) = value.strip_prefix('#') {
+        if !matches!(hex_digits.len(), 6 | 8) || !hex_digits.is_ascii() {
+            return Err(miette!(
+                "Expected hex color in the format #RRGGBB or #RRGGBBAA, got {}.",
+                value
+            ));
+        }
+
+        let mut color = [0, 0, 0, u8::MAX];
+        for (component_index, component) in color.iter_mut().enumerate().take(hex_digits.len() / 2) {
+            let component_digits = &hex_digits[component_index * 2..component_index * 2 + 2];
+
+            *component = u8::from_str_radix(component_digits, 16)
+                .into_diagnostic()
+                .wrap_err_with(|| {
+                    miette!(
+                        "Invalid hex color component: {}.",
+                        component_digits
+                    )
+                })?;
+        }
+
+        return Ok(color);
+    }
+
+
+    let components = value.split(',').map(str::trim).collect::<Vec<_>>();
+    if !matches!(components.len(), 3 | 4) {
+        return Err(miette!(
+            "Expected color in the format r,g,b or r,g,b,a, got {}.",
+            value
+        ));
+    }
+
+    let mut color = [0, 0, 0, u8::MAX];
+    for (component, component_string) in color.iter_mut().zip(components) {
+        *component = component_string
+            .parse::<u8>()
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                miette!(
+                    "Invalid color component (expected 0-255): {}.",
+                    component_string
+                )
+            })?;
+    }
+
+    Ok(color)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::Configuration;
+
+    #[test]
+    fn hex_colors_are_parsed() {
+        assert_eq!(
+            parse_rgba_color("#3366cc").unwrap(),
+            [0x33, 0x66, 0xCC, 255]
+        );
+        assert_eq!(
+            parse_rgba_color("#3366CC80").unwrap(),
+            [0x33, 0x66, 0xCC, 0x80]
+        );
+    }
+
+    #[test]
+    fn component_colors_are_parsed() {
+        assert_eq!(
+            parse_rgba_color("10, 20, 30").unwrap(),
+            [10, 20, 30, 255]
+        );
+        assert_eq!(
+            parse_rgba_color("10,20,30,40").unwrap(),
+            [10, 20, 30, 40]
+        );
+    }
+
+    #[test]
+    fn invalid_colors_are_rejected() {
+        assert!(parse_rgba_color("#12345").is_err());
+        assert!(parse_rgba_color("#GG0000").is_err());
+        assert!(parse_rgba_color("10,20").is_err());
+        assert!(parse_rgba_color("10,20,300").is_err());
+    }
+
+    #[test]
+    fn background_color_defaults_to_black() {
+        assert_eq!(
+            Configuration::load_for_tests("").rendering.background_color,
+            [0, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn clip_planes_must_satisfy_near_below_far() {
+        let resolve_with_clip_planes = |near_plane, far_plane| {
+            UnresolvedRenderingConfiguration {
+                near_plane,
+                far_plane,
+                ..Default::default()
+            }
+            .resolve()
+        };
+
+        let rendering_configuration = resolve_with_clip_planes(0.5, 500.0).unwrap();
+        assert_eq!(rendering_configuration.near_plane, 0.5);
+        assert_eq!(rendering_configuration.far_plane, 500.0);
+
+        assert!(resolve_with_clip_planes(10.0, 10.0).is_err());
+        assert!(resolve_with_clip_planes(20.0, 10.0).is_err());
+        assert!(resolve_with_clip_planes(0.0, 10.0).is_err());
+        assert!(resolve_with_clip_planes(0.1, f32::INFINITY).is_err());
+    }
+
+
+    #[test]
+    fn too_large_render_dimensions_are_rejected() {
+        let error = RenderingConfiguration::validate_render_dimensions(100_000, 720, 8192)
+            .expect_err("too large width should be rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains("Render width must be between 1 and 8192 pixels, got 100000"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn zero_render_dimensions_are_rejected() {
+        let error = RenderingConfiguration::validate_render_dimensions(1280, 0, 8192)
+            .expect_err("zero height should be rejected");
+
+        assert!(
+            error.to_string().contains("Render height"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn render_dimensions_up_to_the_maximum_are_accepted() {
+        assert!(RenderingConfiguration::validate_render_dimensions(1, 8192, 8192).is_ok());
+    }
+
+    #[test]
+    fn maximum_render_dimension_is_configurable() {
+        let configuration =
+            Configuration::load_for_tests("[rendering]\nmax_render_dimension = 512\n");
+
+        assert_eq!(configuration.rendering.max_render_dimension, 512);
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use miette::{miette, Context, IntoDiagnostic, Result};
 use serde::Deserialize;
@@ -10,14 +10,101 @@ use crate::configuration::{
 };
 
 
+/// Image format screenshots are saved in.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotFormat {
+    /// 8-bit RGBA PNG (the default).
+    #[default]
+    Png,
+
+    /// Radiance HDR with 32-bit float RGB components.
+    Hdr,
+
+    /// OpenEXR with 32-bit float RGB components.
+    Exr,
+}
+
+impl ScreenshotFormat {
+    /// Returns the file extension (without the leading dot) for this format.
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "png",
+            ScreenshotFormat::Hdr => "hdr",
+            ScreenshotFormat::Exr => "exr",
+        }
+    }
+
+    /// Returns `true` if this format stores floating-point (high dynamic range) colors.
+    pub fn is_floating_point(&self) -> bool {
+        matches!(
+            self,
+            ScreenshotFormat::Hdr | ScreenshotFormat::Exr
+        )
+    }
+}
+
+
+/// Checks that files can be created in `directory_path` by creating (and removing) an empty file.
+fn verify_directory_is_writable(directory_path: &Path) -> Result<()> {
+    let test_file_path = directory_path.join(".nrg-dn1-write-test");
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&test_file_path)
+        .into_diagnostic()?;
+
+    std::fs::remove_file(&test_file_path).into_diagnostic()?;
+
+    Ok(())
+}
+
+
 #[derive(Deserialize, Clone, Debug)]
 pub(super) struct UnresolvedScreenshotConfiguration {
     screenshot_directory_path: String,
+
+    /// Format of saved screenshots: `png`, `hdr` or `exr`. Defaults to `png`.
+    #[serde(default)]
+    screenshot_format: ScreenshotFormat,
+
+    /// Bits per channel of PNG screenshots: `8` or `16`. 16-bit screenshots are converted
+    /// from the unrounded compositing result, so they keep more precision. Defaults to `8`.
+    #[serde(default = "UnresolvedScreenshotConfiguration::default_screenshot_bit_depth")]
+    screenshot_bit_depth: u8,
+
+    /// Whether PNG screenshots keep the accumulated per-pixel alpha instead of being saved
+    /// fully opaque. Only useful with a translucent `background_color`. Defaults to `false`.
+    #[serde(default)]
+    preserve_alpha: bool,
+
+    /// Fixed file name (without directory) to save screenshots as, overwriting any previous one.
+    /// The extension is optional. If unspecified, screenshots are named by their timestamp.
+    #[serde(default)]
+    screenshot_name: Option<String>,
+}
+
+impl UnresolvedScreenshotConfiguration {
+    fn default_screenshot_bit_depth() -> u8 {
+        8
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct ScreenshotConfiguration {
     pub screenshot_directory_path: PathBuf,
+
+    pub screenshot_format: ScreenshotFormat,
+
+    /// Bits per channel of PNG screenshots (8 or 16).
+    pub screenshot_bit_depth: u8,
+
+    pub preserve_alpha: bool,
+
+    /// Fixed screenshot file name (if `None`, screenshots are named by their timestamp).
+    pub screenshot_name: Option<String>,
 }
 
 
@@ -29,26 +116,86 @@ impl ResolvableConfigurationWithContext for UnresolvedScreenshotConfiguration {
         let screenshot_directory_path = replace_placeholders_in_path(
             self.screenshot_directory_path,
             context.placeholders_map(),
-        );
+        )
+        .wrap_err("Failed to resolve field screenshot_directory_path.")?;
+
+
+        if screenshot_directory_path.exists() && !screenshot_directory_path.is_dir() {
+            return Err(miette!(
+                "Screenshot directory path {} exists, but is not a directory!",
+                screenshot_directory_path.display()
+            ));
+        }
+
+        if !screenshot_directory_path.is_dir() {
+            std::fs::create_dir_all(&screenshot_directory_path)
+                .into_diagnostic()
+                .wrap_err_with(|| {
+                    miette!(
+                        "Failed to create missing screenshot directory at {}.",
+                        screenshot_directory_path.display()
+                    )
+                })?;
+        }
+
+        verify_directory_is_writable(&screenshot_directory_path).wrap_err_with(|| {
+            miette!(
+                "Screenshot directory {} is not writable.",
+                screenshot_directory_path.display()
+            )
+        })?;
+
+
+        if let Some(screenshot_name) = self.screenshot_name.as_deref() {
+            ScreenshotConfiguration::validate_screenshot_name(screenshot_name)
+                .wrap_err("Invalid field screenshot_name.")?;
+        }
+
+
+        if !matches!(self.screenshot_bit_depth, 8 | 16) {
+            return Err(miette!(
+                "Field screenshot_bit_depth must be 8 or 16, got {}.",
+                self.screenshot_bit_depth
+            ));
+        }
 
 
         Ok(Self::Resolved {
             screenshot_directory_path,
+            screenshot_format: self.screenshot_format,
+            screenshot_bit_depth: self.screenshot_bit_depth,
+            preserve_alpha: self.preserve_alpha,
+            screenshot_name: self.screenshot_name,
         })
     }
 }
 
 impl ScreenshotConfiguration {
-    /// Creates the screenshot directory if it does not already exist.
-    pub fn create_screenshot_directory_if_not_exists(&self) -> Result<()> {
-        std::fs::create_dir_all(&self.screenshot_directory_path)
-            .into_diagnostic()
-            .wrap_err_with(|| {
-                miette!(
-                    "Failed to create missing screenshot directory at {}.",
-                    self.screenshot_directory_path.display()
-                )
-            })
+    /// Ensures that `screenshot_name` is a plain, non-empty file name (without any directories).
+    pub fn validate_screenshot_name(screenshot_name: &str) -> Result<()> {
+        let is_plain_file_name = Path::new(screenshot_name)
+            .file_name()
+            .is_some_and(|file_name| file_name == screenshot_name);
+
+        if screenshot_name.trim().is_empty() || !is_plain_file_name {
+            return Err(miette!(
+                "Screenshot name must be a plain file name without directories, got \"{}\".",
+                screenshot_name
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if screenshots are saved as 16-bit PNG.
+    pub fn is_16_bit_png(&self) -> bool {
+        self.screenshot_format == ScreenshotFormat::Png && self.screenshot_bit_depth == 16
+    }
+
+    /// Returns `true` if screenshots are converted from the full-precision (floating-point)
+    /// compositing result instead of the 8-bit frame.
+    pub fn needs_full_precision_frame(&self) -> bool {
+        self.screenshot_format.is_floating_point() || self.is_16_bit_png()
     }
 
     /// Returns a full screenshot path by joining the screenshot directory
@@ -57,3 +204,119 @@ impl ScreenshotConfiguration {
         self.screenshot_directory_path.join(screenshot_file_name)
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a fresh (empty) temporary directory named `name`.
+    fn get_temporary_directory(name: &str) -> PathBuf {
+        let directory_path = std::env::temp_dir().join("nrg-dn1-tests").join(name);
+
+        if directory_path.exists() {
+            std::fs::remove_dir_all(&directory_path)
+                .expect("temporary directory should be removable");
+        }
+
+        std::fs::create_dir_all(&directory_path).expect("temporary directory should be creatable");
+
+        directory_path
+    }
+
+    fn resolve_screenshot_directory(
+        screenshot_directory_path: &Path,
+    ) -> Result<ScreenshotConfiguration> {
+        UnresolvedScreenshotConfiguration {
+            screenshot_directory_path: screenshot_directory_path.to_string_lossy().to_string(),
+            screenshot_format: ScreenshotFormat::Png,
+            screenshot_bit_depth: 8,
+            preserve_alpha: false,
+            screenshot_name: None,
+        }
+        .resolve(BasePathsConfiguration {
+            base_data_directory_path: std::env::temp_dir(),
+        })
+    }
+
+
+    #[test]
+    fn writable_screenshot_directory_is_accepted() {
+        let directory_path = get_temporary_directory("screenshot-writable");
+
+        let configuration =
+            resolve_screenshot_directory(&directory_path).expect("directory should be accepted");
+
+        assert_eq!(
+            configuration.screenshot_directory_path,
+            directory_path
+        );
+        assert!(!directory_path.join(".nrg-dn1-write-test").exists());
+    }
+
+    #[test]
+    fn screenshot_directory_without_write_access_is_rejected() {
+        let directory_path = get_temporary_directory("screenshot-not-writable");
+
+        // The write test file cannot be created where a directory of the same name exists.
+        // Unlike read-only permissions, this also holds for privileged users (e.g. root).
+        std::fs::create_dir(directory_path.join(".nrg-dn1-write-test"))
+            .expect("temporary directory should be creatable");
+
+        let error = resolve_screenshot_directory(&directory_path)
+            .expect_err("directory without write access should be rejected");
+
+        assert!(
+            error.to_string().contains("is not writable"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn directory_under_a_regular_file_is_not_writable() {
+        let directory_path = get_temporary_directory("screenshot-under-file");
+
+        let file_path = directory_path.join("regular-file");
+        std::fs::write(&file_path, b"").expect("temporary file should be writable");
+
+        assert!(verify_directory_is_writable(&file_path.join("screenshots")).is_err());
+        assert!(verify_directory_is_writable(&directory_path).is_ok());
+    }
+
+    #[test]
+    fn screenshot_directory_that_cannot_be_created_is_rejected() {
+        let directory_path = get_temporary_directory("screenshot-uncreatable");
+
+        let file_path = directory_path.join("regular-file");
+        std::fs::write(&file_path, b"").expect("temporary file should be writable");
+
+        let error = resolve_screenshot_directory(&file_path.join("screenshots"))
+            .expect_err("directory inside a file should be rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains("Failed to create missing screenshot directory"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn screenshot_directory_path_pointing_at_a_file_is_rejected() {
+        let directory_path = get_temporary_directory("screenshot-file");
+
+        let file_path = directory_path.join("regular-file");
+        std::fs::write(&file_path, b"").expect("temporary file should be writable");
+
+        let error =
+            resolve_screenshot_directory(&file_path).expect_err("file path should be rejected");
+
+        assert!(
+            error.to_string().contains("is not a directory"),
+            "unexpected error: {}",
+            error
+        );
+    }
+}
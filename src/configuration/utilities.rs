@@ -18,20 +18,143 @@ pub fn get_default_configuration_file_path() -> Result<PathBuf> {
     Ok(configuration_filepath)
 }
 
-#[must_use = "function returns the modified path"]
-#[allow(dead_code)]
+/// Returns `true` if `character` may appear in an environment variable name.
+fn is_environment_variable_name_character(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+fn get_environment_variable(name: &str) -> Result<String> {
+    std::env::var(name).map_err(|_| {
+        miette!(
+            "Environment variable {} is referenced, but is not set (or is not valid unicode).",
+            name
+        )
+    })
+}
+
+/// Expands environment variables in `value`. Both `$NAME` and `${NAME}` are supported,
+/// as well as `%NAME%` on Windows. A `$` that isn't followed by a variable name is kept as-is.
+///
+/// Returns an error if a referenced variable is not set.
+pub fn expand_environment_variables(value: &str) -> Result<String> {
+    let mut expanded_value = String::with_capacity(value.len());
+    let mut remaining_value = value;
+
+    while let Some(variable_start_index) =
+        remaining_value.find(|character| character == '$' || (cfg!(windows) && character == '%'))
+    {
+        expanded_value.push_str(&remaining_value[..variable_start_index]);
+
+        let start_character = &remaining_value[variable_start_index..variable_start_index + 1];
+        let after_start = &remaining_value[variable_start_index + 1..];
+
+        // Find the variable name and the rest of the string after the reference.
+        let variable_reference = if start_character == "%" {
+            after_start.split_once('%').filter(|(name, _)| {
+                !name.is_empty() && name.chars().all(is_environment_variable_name_character)
+            })
+        } else if let Some(after_brace) = after_start.strip_prefix('{') {
+            Some(after_brace.split_once('}').ok_or_else(|| {
+                miette!(
+                    "Unterminated environment variable reference in {}.",
+                    value
+                )
+            })?)
+        } else {
+            let name_length = after_start
+                .find(|character: char| !is_environment_variable_name_character(character))
+                .unwrap_or(after_start.len());
+
+            (name_length > 0).then(|| after_start.split_at(name_length))
+        };
+
+
+        match variable_reference {
+            Some((variable_name, rest)) => {
+                expanded_value.push_str(&get_environment_variable(variable_name)?);
+                remaining_value = rest;
+            }
+            None => {
+                expanded_value.push_str(start_character);
+                remaining_value = after_start;
+            }
+        }
+    }
+
+    expanded_value.push_str(remaining_value);
+
+    Ok(expanded_value)
+}
+
+/// Expands environment variables in `original_path` (see [`expand_environment_variables`]),
+/// then replaces the given `placeholders` (e.g. `{BASE_DATA_DIRECTORY}`) with their values.
 pub fn replace_placeholders_in_path<S>(
     original_path: S,
     placeholders: HashMap<&'static str, String>,
-) -> PathBuf
+) -> Result<PathBuf>
 where
     S: Into<String>,
 {
-    let mut path_string: String = original_path.into();
+    let original_path: String = original_path.into();
+
+    let mut path_string = expand_environment_variables(&original_path).wrap_err_with(|| {
+        miette!(
+            "Failed to expand environment variables in path {}.",
+            original_path
+        )
+    })?;
 
     for (key, value) in placeholders.into_iter() {
         path_string = path_string.replace(key, &value);
     }
 
-    PathBuf::from(path_string)
+    Ok(PathBuf::from(path_string))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_environment_variables_are_expanded() {
+        std::env::set_var("NRG_DN1_TEST_SET_VARIABLE", "/home/user");
+
+        let path = replace_placeholders_in_path(
+            "${NRG_DN1_TEST_SET_VARIABLE}/screenshots/$NRG_DN1_TEST_SET_VARIABLE-{SUFFIX}",
+            HashMap::from([("{SUFFIX}", "png".to_string())]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            path,
+            PathBuf::from("/home/user/screenshots//home/user-png")
+        );
+    }
+
+    #[test]
+    fn unset_environment_variables_are_reported() {
+        std::env::remove_var("NRG_DN1_TEST_UNSET_VARIABLE");
+
+        let error = expand_environment_variables("${NRG_DN1_TEST_UNSET_VARIABLE}/logs").unwrap_err();
+
+        assert!(
+            error.to_string().contains("NRG_DN1_TEST_UNSET_VARIABLE"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn dollar_signs_without_a_variable_name_are_kept() {
+        assert_eq!(
+            expand_environment_variables("a $ b/$").unwrap(),
+            "a $ b/$"
+        );
+    }
+
+    #[test]
+    fn unterminated_references_are_rejected() {
+        assert!(expand_environment_variables("${HOME/logs").is_err());
+    }
 }
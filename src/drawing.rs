@@ -1,21 +1,36 @@
-use miette::{Context, IntoDiagnostic, Result};
+#[cfg(feature = "egui")]
+mod control_panel;
+
+
+use std::{
+    thread::{self, ScopedJoinHandle},
+    time::Instant,
+};
+
+use miette::{Context, IntoDiagnostic, Report, Result};
 use pixels::{Pixels, SurfaceTexture};
 use tracing::{error, info, trace};
 use winit::{
-    dpi::LogicalSize,
-    event::{Event, KeyEvent, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    keyboard::Key,
+    dpi::{LogicalSize, PhysicalSize},
+    event::{ElementState, Event, KeyEvent, MouseButton, StartCause, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopProxy},
     window::{Window, WindowBuilder},
 };
 
-use crate::renderer::{InteractiveRenderer, PixelSurfaceRenderer};
+#[cfg(feature = "egui")]
+use self::control_panel::ControlPanel;
+use crate::renderer::{
+    keybindings::{get_key_name, KeyAction, Keymap},
+    InteractiveRenderer,
+    PixelSurfaceRenderer,
+};
 
 
 /// A high-level action to perform inside the render loop.
 ///
 /// This enum is returned from [`handle_keyboard_input`] to
 /// indicate the user requesting the program to stop, for example.
+#[derive(Debug, PartialEq, Eq)]
 enum Action {
     Nothing,
     Quit,
@@ -25,22 +40,44 @@ enum Action {
 /// Handles the [`KeyEvent`] on the window.
 ///
 /// # Shortcuts
-/// - `q` — closes the window and quits the program.
-fn handle_keyboard_input(event: &KeyEvent) -> Result<Action> {
+/// - [`KeyAction::Quit`] (`q` by default) — closes the window and quits the program.
+fn handle_keyboard_input(
+    event: &KeyEvent,
+    keymap: &Keymap,
+    control_key_pressed: bool,
+) -> Result<Action> {
     trace!("Keyboard input event: {:?}", event);
 
-    let Key::Character(input_key) = &event.logical_key else {
+    let Some(input_key) = get_key_name(&event.logical_key) else {
         return Ok(Action::Nothing);
     };
 
 
-    if input_key == "q" {
-        info!("User pressed q, quitting.");
-        return Ok(Action::Quit);
+    Ok(get_keyboard_action(
+        &input_key,
+        control_key_pressed,
+        keymap,
+    ))
+}
+
+/// Maps the pressed key onto an [`Action`] using the given keymap.
+///
+/// While Ctrl is held only `Ctrl+` shortcuts apply (like in the renderer),
+/// so e.g. `Ctrl+q` does not quit unless the quit key is bound to it.
+fn get_keyboard_action(input_key: &str, control_key_pressed: bool, keymap: &Keymap) -> Action {
+    let key_name = if control_key_pressed {
+        format!("Ctrl+{}", input_key)
+    } else {
+        input_key.to_string()
+    };
+
+    if keymap.find_action(&key_name) == Some(KeyAction::Quit) {
+        info!("User pressed {}, quitting.", key_name);
+        return Action::Quit;
     }
 
 
-    Ok(Action::Nothing)
+    Action::Nothing
 }
 
 /// Handles the [`WindowEvent::RedrawRequested`] on the window.
@@ -48,18 +85,135 @@ fn handle_keyboard_input(event: &KeyEvent) -> Result<Action> {
 /// Given a [`Pixels`] surface and a surface renderer, this function
 /// has the renderer draw pixels on the surface and, finally,
 /// output them to the `winit` window.
-fn handle_redraw_request<R>(surface: &mut Pixels, renderer: &R) -> Result<()>
+///
+/// With the `egui` feature, the control panel is drawn over the frame.
+fn handle_redraw_request<R>(
+    surface: &mut Pixels,
+    renderer: &R,
+    #[cfg(feature = "egui")] control_panel: &mut ControlPanel,
+    #[cfg(feature = "egui")] window: &Window,
+) -> Result<()>
 where
     R: PixelSurfaceRenderer + InteractiveRenderer,
 {
     renderer.draw(surface.frame_mut());
 
+    #[cfg(feature = "egui")]
+    {
+        let frame_size = (
+            surface.texture().width(),
+            surface.texture().height(),
+        );
+
+        if control_panel.draw(window, renderer, surface.frame_mut(), frame_size) {
+            window.request_redraw();
+        }
+    }
+
     surface
         .render()
         .into_diagnostic()
         .wrap_err("Failed to render to window: {:?}")
 }
 
+/// Starts rendering a new frame on a background thread (spawned in `scope`) if the renderer
+/// needs one and no background render is already in progress.
+///
+/// Once the render completes, a user event is sent through `event_loop_proxy`
+/// so that the event loop can draw the new frame.
+fn start_background_render_if_needed<'scope, 'environment, R>(
+    scope: &'scope thread::Scope<'scope, 'environment>,
+    background_render: &mut Option<ScopedJoinHandle<'scope, ()>>,
+    renderer: &'environment R,
+    event_loop_proxy: &EventLoopProxy<()>,
+) where
+    R: PixelSurfaceRenderer + Sync,
+{
+    if background_render
+        .as_ref()
+        .is_some_and(|render_handle| !render_handle.is_finished())
+    {
+        return;
+    }
+
+    if let Some(finished_render_handle) = background_render.take() {
+        if finished_render_handle.join().is_err() {
+            error!("Background render thread panicked.");
+        }
+    }
+
+    if !renderer.needs_render() {
+        return;
+    }
+
+
+    let event_loop_proxy = event_loop_proxy.clone();
+
+    *background_render = Some(scope.spawn(move || {
+        renderer.render();
+
+        // This only fails if the event loop has already exited, in which case
+        // there is nothing left to draw the frame to.
+        let _ = event_loop_proxy.send_event(());
+    }));
+}
+
+/// Handles the [`WindowEvent::Resized`] on the window.
+///
+/// The pixel surface is resized to cover the whole window, while the
+/// render resolution (the pixel buffer and the renderer) follows the
+/// logical size of the window, up to the limit of the renderer.
+/// Minimized windows (zero size) are ignored.
+fn handle_resize<R>(
+    surface: &mut Pixels,
+    renderer: &R,
+    window: &Window,
+    new_physical_size: PhysicalSize<u32>,
+) -> Result<()>
+where
+    R: PixelSurfaceRenderer + InteractiveRenderer,
+{
+    if new_physical_size.width == 0 || new_physical_size.height == 0 {
+        return Ok(());
+    }
+
+    let new_logical_size = new_physical_size.to_logical::<u32>(window.scale_factor());
+
+    // The renderer may limit the render resolution (see `max_render_dimension`),
+    // in which case the pixel buffer is scaled up to cover the window.
+    let (render_width, render_height) = renderer.resize(
+        new_logical_size.width.max(1),
+        new_logical_size.height.max(1),
+    );
+
+    info!(
+        "Window resized, new render resolution is {}x{}.",
+        render_width, render_height
+    );
+
+    surface
+        .resize_surface(new_physical_size.width, new_physical_size.height)
+        .into_diagnostic()
+        .wrap_err("Failed to resize pixel surface.")?;
+
+    surface
+        .resize_buffer(render_width, render_height)
+        .into_diagnostic()
+        .wrap_err("Failed to resize pixel buffer.")?;
+
+    Ok(())
+}
+
+
+/// Error returned by [`WindowManager::new`] when the window or its pixel surface
+/// could not be created (e.g. on a headless machine without a display or a GPU).
+///
+/// Gives the renderer back, so it can still be used without a window (e.g. to save a screenshot).
+pub struct WindowInitializationError<R> {
+    pub error: Report,
+    pub renderer: R,
+}
+
 
 /// A graphical window manager.
 ///  Takes care of window initialization and its render loop.
@@ -78,16 +232,51 @@ where
 
     /// A surface renderer implementation (generic).
     renderer: R,
+
+    /// Control panel drawn over the frame.
+    #[cfg(feature = "egui")]
+    control_panel: ControlPanel,
 }
 
 
 impl<R> WindowManager<R>
 where
-    R: PixelSurfaceRenderer + InteractiveRenderer,
+    R: PixelSurfaceRenderer + InteractiveRenderer + Sync,
 {
     /// Initialize a new window. THe render loop will not be automatically
     /// executed, run [`Self::run`] afterwards.
-    pub fn new(render_width: u32, render_height: u32, renderer: R) -> Result<Self> {
+    ///
+    /// If the window can not be created, the `renderer` is returned along with the error.
+    pub fn new(
+        render_width: u32,
+        render_height: u32,
+        renderer: R,
+    ) -> std::result::Result<Self, WindowInitializationError<R>> {
+        let (event_loop, window, window_surface) =
+            match Self::initialize_window_and_surface(render_width, render_height) {
+                Ok(window_and_surface) => window_and_surface,
+                Err(error) => return Err(WindowInitializationError { error, renderer }),
+            };
+
+        #[cfg(feature = "egui")]
+        let control_panel = ControlPanel::new(&window);
+
+
+        Ok(Self {
+            event_loop,
+            window,
+            window_surface,
+            renderer,
+            #[cfg(feature = "egui")]
+            control_panel,
+        })
+    }
+
+    /// Creates the [`winit`] event loop and window, and the [`pixels`] surface on the window.
+    fn initialize_window_and_surface(
+        render_width: u32,
+        render_height: u32,
+    ) -> Result<(EventLoop<()>, Window, Pixels)> {
         let event_loop: EventLoop<()> = EventLoop::new()
             .into_diagnostic()
             .wrap_err("Failed to initialize winit event loop.")?;
@@ -99,8 +288,6 @@ where
 
             WindowBuilder::new()
                 .with_inner_size(logical_window_size)
-                .with_min_inner_size(logical_window_size)
-                .with_max_inner_size(logical_window_size)
                 .with_title("NRG: DN1")
                 .build(&event_loop)
                 .into_diagnostic()
@@ -118,39 +305,153 @@ where
                 .wrap_err("Failed to initialize pixel surface.")?
         };
 
+        Ok((event_loop, window, window_surface))
+    }
 
-        Ok(Self {
+    /// A blocking function that consumes the window manager and runs the window
+    /// render loop as long as required (e.g. until the user presses "q").
+    ///
+    /// Frames are rendered on a background thread, so window events keep being handled
+    /// (and the last completed frame drawn) while a render is in progress.
+    pub fn run(self) -> Result<()> {
+        let Self {
             event_loop,
             window,
-            window_surface,
+            mut window_surface,
             renderer,
-        })
-    }
+            #[cfg(feature = "egui")]
+            mut control_panel,
+        } = self;
 
-    /// A blocking function that consumes the window manager and runs the window
-    /// render loop as long as required (e.g. until the user presses "q").
-    pub fn run(mut self) -> Result<()> {
-        self.event_loop
-            .run(move |event, target| {
-                // Ignore non-window-related events.
+        let event_loop_proxy = event_loop.create_proxy();
+        let renderer = &renderer;
+
+        thread::scope(|scope| {
+            let mut background_render: Option<ScopedJoinHandle<()>> = None;
+            let mut showing_rendering_title = false;
+
+            // Last known cursor position inside the window (in physical pixels).
+            let mut last_cursor_position: Option<(f32, f32)> = None;
+
+            // Whether the Ctrl modifier is currently held (for `Ctrl+` quit shortcuts).
+            let mut control_key_pressed = false;
+
+            // When time-based animations (e.g. the turntable) were last advanced.
+            let mut last_animation_update = Instant::now();
+
+            event_loop.run(move |event, target| {
+                // Redraw once the wait for refining a reduced-quality frame is over.
+                if let Event::NewEvents(StartCause::ResumeTimeReached { .. }) = event {
+                    target.set_control_flow(ControlFlow::Wait);
+                    window.request_redraw();
+                    return;
+                }
+
+                // A background render has completed, draw the new frame.
+                if let Event::UserEvent(()) = event {
+                    window.request_redraw();
+                    return;
+                }
+
+
+                // Ignore other non-window-related events.
 
                 let Event::WindowEvent { event, .. } = event else {
                     return;
                 };
 
 
+                // Input meant for the control panel (e.g. dragging its sliders)
+                // is not handled any further.
+                #[cfg(feature = "egui")]
+                {
+                    let control_panel_response = control_panel.handle_window_event(&window, &event);
+
+                    if control_panel_response.repaint {
+                        window.request_redraw();
+                    }
+
+                    if control_panel_response.consumed {
+                        return;
+                    }
+                }
+
+
                 // Handle redraw requests and keyboard input.
                 // The renderer may also provide its own `handle_window_event`.
 
                 if event == WindowEvent::RedrawRequested {
-                    let render_result =
-                        handle_redraw_request(&mut self.window_surface, &self.renderer);
+                    let now = Instant::now();
+                    let is_animating = renderer.advance_animation(now - last_animation_update);
+                    last_animation_update = now;
+
+                    start_background_render_if_needed(
+                        scope,
+                        &mut background_render,
+                        renderer,
+                        &event_loop_proxy,
+                    );
+
+                    let is_rendering = background_render
+                        .as_ref()
+                        .is_some_and(|render_handle| !render_handle.is_finished());
+
+                    if is_rendering != showing_rendering_title {
+                        window.set_title(
+                            if is_rendering {
+                                "NRG: DN1 (rendering...)"
+                            } else {
+                                "NRG: DN1"
+                            },
+                        );
+
+                        showing_rendering_title = is_rendering;
+                    }
+
+
+                    let render_result = handle_redraw_request(
+                        &mut window_surface,
+                        renderer,
+                        #[cfg(feature = "egui")]
+                        &mut control_panel,
+                        #[cfg(feature = "egui")]
+                        &window,
+                    );
                     if let Err(render_error) = render_result {
                         error!("{:?}", render_error);
                         return;
                     };
+
+                    // If the renderer only drew a reduced-quality frame, wake up
+                    // to draw the full-quality one later (if nothing else happens until then).
+                    if let Some(refinement_delay) = renderer.pending_refinement_delay() {
+                        target.set_control_flow(ControlFlow::WaitUntil(
+                            Instant::now() + refinement_delay,
+                        ));
+                    }
+
+                    // Don't request another redraw: that would redraw (and poll) continuously,
+                    // which is only wanted while animating.
+                    if is_animating {
+                        target.set_control_flow(ControlFlow::Poll);
+                        window.request_redraw();
+                    }
+
+                    return;
+                } else if let WindowEvent::Resized(new_physical_size) = &event {
+                    let resize_result = handle_resize(
+                        &mut window_surface,
+                        renderer,
+                        &window,
+                        *new_physical_size,
+                    );
+                    if let Err(resize_error) = resize_result {
+                        error!("{:?}", resize_error);
+                        return;
+                    };
                 } else if let WindowEvent::KeyboardInput { event, .. } = &event {
-                    let input_result = handle_keyboard_input(event);
+                    let input_result =
+                        handle_keyboard_input(event, renderer.keymap(), control_key_pressed);
 
                     match input_result {
                         Ok(action) => match action {
@@ -165,9 +466,28 @@ where
                             return;
                         }
                     }
+                } else if let WindowEvent::ModifiersChanged(modifiers) = &event {
+                    control_key_pressed = modifiers.state().control_key();
+                } else if let WindowEvent::CursorMoved { position, .. } = &event {
+                    last_cursor_position = Some((position.x as f32, position.y as f32));
+                } else if let WindowEvent::CursorLeft { .. } = &event {
+                    last_cursor_position = None;
+                } else if let WindowEvent::MouseInput {
+                    state: ElementState::Released,
+                    button: MouseButton::Right,
+                    ..
+                } = &event
+                {
+                    // The pixel surface may be scaled relative to the window,
+                    // so let it map the cursor position onto a pixel of the frame.
+                    if let Some(Ok((pixel_x, pixel_y))) = last_cursor_position
+                        .map(|cursor_position| window_surface.window_pos_to_pixel(cursor_position))
+                    {
+                        renderer.handle_pixel_click((pixel_x as u32, pixel_y as u32));
+                    }
                 }
 
-                let renderer_input_handle_result = self.renderer.handle_window_event(&event);
+                let renderer_input_handle_result = renderer.handle_window_event(&event);
                 if let Err(renderer_error) = renderer_input_handle_result {
                     error!(
                         "Renderer failed while processing window input: {:?}",
@@ -177,11 +497,71 @@ where
                 }
 
 
-                self.window.request_redraw();
+                window.request_redraw();
             })
-            .into_diagnostic()
-            .wrap_err("Failed to run winit event loop to completion.")?;
+        })
+        .into_diagnostic()
+        .wrap_err("Failed to run winit event loop to completion.")?;
 
         Ok(())
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn get_keymap_with_quit_key(quit_key: &str) -> Keymap {
+        Keymap::new(&HashMap::from([(
+            "quit".to_string(),
+            quit_key.to_string(),
+        )]))
+        .unwrap()
+    }
+
+
+    #[test]
+    fn default_quit_key_does_not_quit_while_ctrl_is_held() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            get_keyboard_action("q", false, &keymap),
+            Action::Quit
+        );
+        assert_eq!(
+            get_keyboard_action("q", true, &keymap),
+            Action::Nothing
+        );
+    }
+
+    #[test]
+    fn remapped_quit_key_quits() {
+        let keymap = get_keymap_with_quit_key("z");
+
+        assert_eq!(
+            get_keyboard_action("z", false, &keymap),
+            Action::Quit
+        );
+        assert_eq!(
+            get_keyboard_action("q", false, &keymap),
+            Action::Nothing
+        );
+    }
+
+    #[test]
+    fn quit_key_bound_to_a_ctrl_chord_quits_only_with_ctrl() {
+        let keymap = get_keymap_with_quit_key("Ctrl+q");
+
+        assert_eq!(
+            get_keyboard_action("q", true, &keymap),
+            Action::Quit
+        );
+        assert_eq!(
+            get_keyboard_action("q", false, &keymap),
+            Action::Nothing
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! An optional [`egui`] control panel, drawn over the rendered frame (see the `egui` feature).
+//!
+//! The `pixels` surface renders with an older version of `wgpu` than any `egui` backend supports,
+//! so the panel is tessellated by `egui` and its triangles are then rasterized on the CPU,
+//! directly into the frame buffer (like the other overlays).
+
+use std::collections::HashMap;
+
+use egui::{
+    epaint::{Primitive, Vertex},
+    ClippedPrimitive,
+    Color32,
+    Context,
+    ImageData,
+    TextureId,
+    TexturesDelta,
+    ViewportId,
+};
+use egui_winit::EventResponse;
+use winit::{event::WindowEvent, window::Window};
+
+use crate::renderer::{ControlPanelState, InteractiveRenderer, RenderSettings};
+
+
+/// A texture (e.g. the font atlas) uploaded by `egui`, with premultiplied sRGBA pixels.
+struct SoftwareTexture {
+    /// Width and height of the texture in pixels.
+    size: [usize; 2],
+
+    pixels: Vec<Color32>,
+}
+
+impl SoftwareTexture {
+    /// Returns the texel nearest to the given texture coordinates.
+    #[inline]
+    fn sample(&self, u: f32, v: f32) -> Color32 {
+        let [width, height] = self.size;
+        if width == 0 || height == 0 {
+            return Color32::WHITE;
+        }
+
+        let x = ((u * width as f32) as usize).min(width - 1);
+        let y = ((v * height as f32) as usize).min(height - 1);
+
+        self.pixels[y * width + x]
+    }
+}
+
+
+/// Pixel-aligned clipping rectangle (minimum inclusive, maximum exclusive).
+struct PixelClipRect {
+    min_x: usize,
+    min_y: usize,
+    max_x: usize,
+    max_y: usize,
+}
+
+
+/// The control panel: adjusts the [`RenderSettings`] and shows the camera position and render time.
+pub struct ControlPanel {
+    egui_context: Context,
+
+    egui_state: egui_winit::State,
+
+    textures: HashMap<TextureId, SoftwareTexture>,
+}
+
+impl ControlPanel {
+    pub fn new(window: &Window) -> Self {
+        let egui_context = Context::default();
+
+        let egui_state = egui_winit::State::new(
+            egui_context.clone(),
+            ViewportId::ROOT,
+            window,
+            Some(window.scale_factor() as f32),
+            None,
+        );
+
+        Self {
+            egui_context,
+            egui_state,
+            textures: HashMap::new(),
+        }
+    }
+
+    /// Passes a window event on to `egui`. If the returned response is `consumed`,
+    /// the event was meant for the panel (e.g. dragging a slider) and should not be handled further.
+    pub fn handle_window_event(&mut self, window: &Window, event: &WindowEvent) -> EventResponse {
+        self.egui_state.on_window_event(window, event)
+    }
+
+    /// Runs the panel and draws it onto `frame` (RGBA, `frame_size` pixels), then applies
+    /// any settings the user has changed to the `renderer`.
+    ///
+    /// Returns `true` if the panel needs to be redrawn again (e.g. while it is being interacted with).
+    pub fn draw<R>(
+        &mut self,
+        window: &Window,
+        renderer: &R,
+        frame: &mut [u8],
+        frame_size: (u32, u32),
+    ) -> bool
+    where
+        R: InteractiveRenderer,
+    {
+        let control_panel_state = renderer.control_panel_state();
+        let mut render_settings = control_panel_state.render_settings;
+
+        let raw_input = self.egui_state.take_egui_input(window);
+        let full_output = self.egui_context.run(raw_input, |context| {
+            show_control_panel(
+                context,
+                &mut render_settings,
+                &control_panel_state,
+            );
+        });
+
+        self.egui_state
+            .handle_platform_output(window, full_output.platform_output);
+
+
+        // The frame buffer follows the logical size of the window,
+        // so it usually has exactly one pixel per point.
+        let screen_width_in_points = self.egui_context.screen_rect().width();
+        let pixels_per_point = if screen_width_in_points > 0.0 {
+            frame_size.0 as f32 / screen_width_in_points
+        } else {
+            1.0
+        };
+
+        self.apply_textures_delta_set(&full_output.textures_delta);
+
+        let clipped_primitives = self
+            .egui_context
+            .tessellate(full_output.shapes, pixels_per_point);
+
+        for clipped_primitive in &clipped_primitives {
+            self.paint_clipped_primitive(
+                clipped_primitive,
+                pixels_per_point,
+                frame,
+                frame_size,
+            );
+        }
+
+        for freed_texture_id in &full_output.textures_delta.free {
+            self.textures.remove(freed_texture_id);
+        }
+
+
+        let settings_changed = render_settings != control_panel_state.render_settings;
+        if settings_changed {
+            renderer.apply_render_settings(render_settings);
+        }
+
+        let needs_repaint = full_output
+            .viewport_output
+            .get(&ViewportId::ROOT)
+            .is_some_and(|viewport_output| viewport_output.repaint_delay.is_zero());
+
+        settings_changed || needs_repaint
+    }
+
+    /// Creates or updates the textures `egui` has uploaded.
+    fn apply_textures_delta_set(&mut self, textures_delta: &TexturesDelta) {
+        for (texture_id, image_delta) in &textures_delta.set {
+            let (size, pixels) = match &image_delta.image {
+                ImageData::Color(color_image) => (color_image.size, color_image.pixels.clone()),
+                ImageData::Font(font_image) => (
+                    font_image.size,
+                    font_image.srgba_pixels(None).collect::<Vec<_>>(),
+                ),
+            };
+
+            let Some([patch_x, patch_y]) = image_delta.pos else {
+                self.textures
+                    .insert(*texture_id, SoftwareTexture { size, pixels });
+                continue;
+            };
+
+            let Some(texture) = self.textures.get_mut(texture_id) else {
+                continue;
+            };
+
+            let [texture_width, texture_height] = texture.size;
+            let [patch_width, patch_height] = size;
+
+            for row in 0..patch_height.min(texture_height.saturating_sub(patch_y)) {
+                let copied_width = patch_width.min(texture_width.saturating_sub(patch_x));
+                let texture_row_start = (patch_y + row) * texture_width + patch_x;
+                let patch_row_start = row * patch_width;
+
+                texture.pixels[texture_row_start..texture_row_start + copied_width]
+                    .copy_from_slice(&pixels[patch_row_start..patch_row_start + copied_width]);
+            }
+        }
+    }
+
+    /// Rasterizes a tessellated mesh onto the frame.
+    fn paint_clipped_primitive(
+        &self,
+        clipped_primitive: &ClippedPrimitive,
+        pixels_per_point: f32,
+        frame: &mut [u8],
+        frame_size: (u32, u32),
+    ) {
+        let Primitive::Mesh(mesh) = &clipped_primitive.primitive else {
+            // Paint callbacks are only used by GPU backends.
+            return;
+        };
+
+        let Some(texture) = self.textures.get(&mesh.texture_id) else {
+            return;
+        };
+
+
+        let clip_rect = clipped_primitive.clip_rect;
+        let clip_rect = PixelClipRect {
+            min_x: (clip_rect.min.x * pixels_per_point).max(0.0) as usize,
+            min_y: (clip_rect.min.y * pixels_per_point).max(0.0) as usize,
+            max_x: ((clip_rect.max.x * pixels_per_point).ceil().max(0.0) as usize)
+                .min(frame_size.0 as usize),
+            max_y: ((clip_rect.max.y * pixels_per_point).ceil().max(0.0) as usize)
+                .min(frame_size.1 as usize),
+        };
+
+        if clip_rect.min_x >= clip_rect.max_x || clip_rect.min_y >= clip_rect.max_y {
+            return;
+        }
+
+
+        for triangle_indices in mesh.indices.chunks_exact(3) {
+            let vertices = [
+                &mesh.vertices[triangle_indices[0] as usize],
+                &mesh.vertices[triangle_indices[1] as usize],
+                &mesh.vertices[triangle_indices[2] as usize],
+            ];
+
+            rasterize_triangle(
+                vertices,
+                texture,
+                pixels_per_point,
+                &clip_rect,
+                frame,
+                frame_size.0 as usize,
+            );
+        }
+    }
+}
+
+
+/// Builds the control panel's window.
+fn show_control_panel(
+    context: &Context,
+    render_settings: &mut RenderSettings,
+    control_panel_state: &ControlPanelState,
+) {
+    egui::Window::new("Controls")
+        .anchor(egui::Align2::LEFT_BOTTOM, [8.0, -8.0])
+        .resizable(false)
+        .show(context, |ui| {
+            ui.add(
+                egui::Slider::new(
+                    &mut render_settings.splat_scaling_factor,
+                    0.01..=100.0,
+                )
+                .logarithmic(true)
+                .text("Splat scale"),
+            );
+
+            ui.add(
+                egui::Slider::new(
+                    &mut render_settings.vertical_field_of_view_in_degrees,
+                    1.0..=179.0,
+                )
+                .text("Vertical FOV (°)"),
+            );
+
+            // Keep the near plane in front of the far one.
+            let far_plane = render_settings.far_plane;
+            ui.add(
+                egui::Slider::new(
+                    &mut render_settings.near_plane,
+                    0.001..=far_plane * 0.999,
+                )
+                .logarithmic(true)
+                .text("Near plane"),
+            );
+
+            let near_plane = render_settings.near_plane;
+            ui.add(
+                egui::Slider::new(
+                    &mut render_settings.far_plane,
+                    near_plane * 1.001..=100_000.0,
+                )
+                .logarithmic(true)
+                .text("Far plane"),
+            );
+
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba_unmultiplied(&mut render_settings.background_color);
+                ui.label("Background");
+            });
+
+
+            ui.separator();
+
+            let camera_position = control_panel_state.camera_position;
+            ui.label(format!(
+                "Camera position: ({:.3}, {:.3}, {:.3})",
+                camera_position.x, camera_position.y, camera_position.z
+            ));
+
+            ui.label(match control_panel_state.last_render_duration {
+                Some(render_duration) => format!(
+                    "Last render: {:.1} ms",
+                    render_duration.as_secs_f64() * 1000.0
+                ),
+                None => "Last render: -".to_string(),
+            });
+        });
+}
+
+
+/// Twice the signed area of the triangle `(a, b, point)`.
+#[inline]
+fn edge_function(a: (f32, f32), b: (f32, f32), point: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (point.1 - a.1) - (b.1 - a.1) * (point.0 - a.0)
+}
+
+/// Rasterizes a single textured triangle onto the (RGBA) `frame`, blending its premultiplied colors
+/// over the existing pixels. Texture coordinates and colors are interpolated barycentrically.
+fn rasterize_triangle(
+    vertices: [&Vertex; 3],
+    texture: &SoftwareTexture,
+    pixels_per_point: f32,
+    clip_rect: &PixelClipRect,
+    frame: &mut [u8],
+    frame_width: usize,
+) {
+    let positions = vertices.map(|vertex| {
+        (
+            vertex.pos.x * pixels_per_point,
+            vertex.pos.y * pixels_per_point,
+        )
+    });
+
+    let doubled_area = edge_function(positions[0], positions[1], positions[2]);
+    if doubled_area.abs() <= f32::EPSILON {
+        return;
+    }
+
+
+    let min_x = positions
+        .iter()
+        .map(|position| position.0)
+        .fold(f32::MAX, f32::min);
+    let max_x = positions
+        .iter()
+        .map(|position| position.0)
+        .fold(f32::MIN, f32::max);
+    let min_y = positions
+        .iter()
+        .map(|position| position.1)
+        .fold(f32::MAX, f32::min);
+    let max_y = positions
+        .iter()
+        .map(|position| position.1)
+        .fold(f32::MIN, f32::max);
+
+    let start_x = (min_x.floor().max(0.0) as usize).max(clip_rect.min_x);
+    let end_x = (max_x.ceil().max(0.0) as usize).min(clip_rect.max_x);
+    let start_y = (min_y.floor().max(0.0) as usize).max(clip_rect.min_y);
+    let end_y = (max_y.ceil().max(0.0) as usize).min(clip_rect.max_y);
+
+    let colors = vertices.map(|vertex| vertex.color.to_array().map(f32::from));
+
+
+    for y in start_y..end_y {
+        for x in start_x..end_x {
+            let pixel_center = (x as f32 + 0.5, y as f32 + 0.5);
+
+            let first_weight =
+                edge_function(positions[1], positions[2], pixel_center) / doubled_area;
+            let second_weight =
+                edge_function(positions[2], positions[0], pixel_center) / doubled_area;
+            let third_weight = 1.0 - first_weight - second_weight;
+
+            if first_weight < 0.0 || second_weight < 0.0 || third_weight < 0.0 {
+                continue;
+            }
+
+            let weights = [first_weight, second_weight, third_weight];
+            let interpolate = |values: [f32; 3]| -> f32 {
+                values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
+            };
+
+            let texel = texture
+                .sample(
+                    interpolate(vertices.map(|vertex| vertex.uv.x)),
+                    interpolate(vertices.map(|vertex| vertex.uv.y)),
+                )
+                .to_array();
+
+            // Both the vertex colors and the texels are premultiplied.
+            let source_color: [f32; 4] = std::array::from_fn(|channel| {
+                interpolate(colors.map(|color| color[channel])) * f32::from(texel[channel]) / 255.0
+            });
+
+            let source_alpha = source_color[3] / 255.0;
+            if source_alpha <= 0.0 && source_color[..3].iter().all(|channel| *channel <= 0.0) {
+                continue;
+            }
+
+
+            let pixel_offset = (y * frame_width + x) * 4;
+            let frame_pixel = &mut frame[pixel_offset..pixel_offset + 4];
+
+            for (channel, frame_channel) in frame_pixel.iter_mut().enumerate() {
+                let blended =
+                    source_color[channel] + f32::from(*frame_channel) * (1.0 - source_alpha);
+
+                *frame_channel = blended.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
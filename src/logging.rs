@@ -2,23 +2,82 @@
 
 use std::path::Path;
 
-use miette::Result;
-use tracing_appender::non_blocking::WorkerGuard;
+use miette::{Context, IntoDiagnostic, Result};
+use tracing::Subscriber;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
 use tracing_subscriber::{
+    fmt::MakeWriter,
     prelude::__tracing_subscriber_SubscriberExt,
+    registry::LookupSpan,
     util::SubscriberInitExt,
     EnvFilter,
     Layer,
 };
 
+use crate::configuration::{LogFileFormat, LogRotation};
+
+
+/// Picks the console level filter, in order of precedence:
+/// - `console_level_filter_override` (from the `-v` or `-q` command-line flags), if specified,
+/// - the `RUST_LOG` environment variable, if set,
+/// - `configured_console_level_filter` (from the configuration file).
+pub fn resolve_console_level_filter(
+    configured_console_level_filter: EnvFilter,
+    console_level_filter_override: Option<EnvFilter>,
+) -> EnvFilter {
+    if let Some(console_level_filter_override) = console_level_filter_override {
+        console_level_filter_override
+    } else if std::env::var("RUST_LOG").is_err() {
+        // If RUST_LOG is unset, use the configuration default.
+        configured_console_level_filter
+    } else {
+        EnvFilter::from_default_env()
+    }
+}
+
+
+/// Creates the (unfiltered) log file layer, which writes events into `writer`
+/// in the given `log_file_format`.
+fn create_file_layer<S, W>(
+    writer: W,
+    log_file_format: LogFileFormat,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let file_tracing_format = tracing_subscriber::fmt::format()
+        .with_ansi(false)
+        .with_target(true)
+        .with_level(true);
+
+    let file_layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .log_internal_errors(true);
+
+    match log_file_format {
+        LogFileFormat::Pretty => file_layer.event_format(file_tracing_format).boxed(),
+        LogFileFormat::Json => file_layer
+            .event_format(file_tracing_format.json())
+            .fmt_fields(tracing_subscriber::fmt::format::JsonFields::new())
+            .boxed(),
+    }
+}
+
 
 /// Initialize console and file logging via [`tracing`](../../tracing/index.html).
 ///
 /// The `console_level_filter` and `log_file_level_filter` specify the logging levels for
-/// the console and log file, respectively.
+/// the console and log file, respectively (see [`resolve_console_level_filter`]).
 ///
 /// The `log_file_directory_path` should point to a directory in which the log files should be stored.
-/// The log files themselves will automatically roll over daily.
+/// The log files roll over according to `log_file_rotation`, and at most `log_file_max_files`
+/// of them are kept (if specified).
+/// The `log_file_format` selects between human-readable and JSON lines output
+/// for the log file (console output is always human-readable).
 ///
 /// # Return value obligations
 /// **The caller must ensure that the returned [`WorkerGuard`]
@@ -29,6 +88,9 @@ pub fn initialize_tracing<P, S>(
     log_file_level_filter: EnvFilter,
     log_file_directory_path: P,
     log_file_name_prefix: S,
+    log_file_format: LogFileFormat,
+    log_file_rotation: LogRotation,
+    log_file_max_files: Option<usize>,
 ) -> Result<WorkerGuard>
 where
     P: AsRef<Path>,
@@ -44,34 +106,35 @@ where
             .log_internal_errors(true)
             .event_format(console_tracing_format);
 
-        let level_filter = if std::env::var("RUST_LOG").is_err() {
-            // If RUST_LOG is unset, use the configuration default.
-            console_level_filter
-        } else {
-            EnvFilter::from_default_env()
-        };
-
-        console_layer.with_filter(level_filter)
+        console_layer.with_filter(console_level_filter)
     };
 
     let (file_layer, file_guard) = {
-        let file_tracing_format = tracing_subscriber::fmt::format()
-            .with_ansi(false)
-            .with_target(true)
-            .with_level(true);
+        let rotation = match log_file_rotation {
+            LogRotation::Never => Rotation::NEVER,
+            LogRotation::Minutely => Rotation::MINUTELY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Weekly => Rotation::WEEKLY,
+        };
 
-        let (appender, guard) = tracing_appender::non_blocking(tracing_appender::rolling::daily(
-            log_file_directory_path,
-            log_file_name_prefix.as_ref(),
-        ));
+        let mut file_appender_builder = RollingFileAppender::builder()
+            .rotation(rotation)
+            .filename_prefix(log_file_name_prefix.as_ref());
 
-        let file_subscriber = tracing_subscriber::fmt::layer()
-            .with_writer(appender)
-            .log_internal_errors(true)
-            .event_format(file_tracing_format);
+        if let Some(log_file_max_files) = log_file_max_files {
+            file_appender_builder = file_appender_builder.max_log_files(log_file_max_files);
+        }
+
+        let file_appender = file_appender_builder
+            .build(log_file_directory_path)
+            .into_diagnostic()
+            .wrap_err("Failed to initialize log file appender.")?;
+
+        let (appender, guard) = tracing_appender::non_blocking(file_appender);
 
         (
-            file_subscriber.with_filter(log_file_level_filter),
+            create_file_layer(appender, log_file_format).with_filter(log_file_level_filter),
             guard,
         )
     };
@@ -84,3 +147,82 @@ where
 
     Ok(file_guard)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::info;
+
+    use super::*;
+
+    /// A writer that appends everything into a shared buffer.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Logs a single event through the file layer in `log_file_format` and returns the output.
+    fn log_event_to_file_layer(log_file_format: LogFileFormat) -> String {
+        let buffer = SharedBuffer::default();
+
+        let writer_buffer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(create_file_layer(
+            move || writer_buffer.clone(),
+            log_file_format,
+        ));
+
+        tracing::subscriber::with_default(subscriber, || {
+            info!(splat_count = 5, "Loaded \"scene.splat\".");
+        });
+
+        let output = buffer.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn json_log_lines_are_valid_json() {
+        let output = log_event_to_file_layer(LogFileFormat::Json);
+        let log_line = output.lines().next().expect("an event should be logged");
+
+        let log_entry: serde_json::Value = serde_json::from_str(log_line).unwrap();
+
+        assert_eq!(log_entry["level"], "INFO");
+        assert_eq!(
+            log_entry["fields"]["message"],
+            "Loaded \"scene.splat\"."
+        );
+        assert_eq!(log_entry["fields"]["splat_count"], 5);
+    }
+
+    #[test]
+    fn pretty_log_lines_are_not_json() {
+        let output = log_event_to_file_layer(LogFileFormat::Pretty);
+
+        assert!(output.contains("Loaded \"scene.splat\"."));
+        assert!(serde_json::from_str::<serde_json::Value>(output.trim()).is_err());
+    }
+
+
+    #[test]
+    fn command_line_override_takes_precedence_over_the_configured_level() {
+        let console_level_filter = resolve_console_level_filter(
+            EnvFilter::new("info"),
+            Some(EnvFilter::new("debug")),
+        );
+
+        assert_eq!(console_level_filter.to_string(), "debug");
+    }
+}
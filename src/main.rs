@@ -1,16 +1,32 @@
+use std::io::IsTerminal;
+
 use clap::Parser;
-use miette::{miette, Context, Result};
-use nalgebra::{Vector3, Vector4};
+use miette::{miette, Context, IntoDiagnostic, Result};
+use nalgebra::Point3;
 use tracing::info;
+use tracing_subscriber::EnvFilter;
 
 use crate::{
-    cli::{parse_str_as_point3, parse_str_as_vector3, CLIArgs},
-    configuration::Configuration,
-    logging::initialize_tracing,
-    renderer::SplatRenderer,
-    splat_decoder::{Splat, Splats},
+    benchmark::run_render_benchmark,
+    cli::{
+        parse_camera_path_file,
+        parse_str_as_clip_plane,
+        parse_str_as_point3,
+        parse_str_as_vector3,
+        CLIArgs,
+    },
+    configuration::{
+        CameraConfiguration,
+        Configuration,
+        RenderingConfiguration,
+        ScreenshotConfiguration,
+    },
+    logging::{initialize_tracing, resolve_console_level_filter},
+    renderer::{get_camera_pose_fitting_splats, keybindings::Keymap, ClipPlane, SplatRenderer},
+    splat_decoder::{get_testing_splat_scene, DecodeOptions, DownsampleStrategy, Splats},
 };
 
+mod benchmark;
 mod cli;
 mod configuration;
 mod logging;
@@ -20,6 +36,9 @@ mod splat_decoder;
 #[cfg(feature = "ui")]
 mod drawing;
 
+#[cfg(test)]
+mod regression;
+
 
 /// Splats are parsed from raw data in parallel, resulting in a vector of splats that is non-deterministic.
 /// If you wish to manually reorder the splats back to their file order, specify this to be true.
@@ -27,6 +46,8 @@ mod drawing;
 /// If this is `false`, the program will not process the file deterministically.
 ///
 /// **This is enabled on debug builds and disabled in release builds.**
+/// Release builds can still opt in at runtime with the `--deterministic` flag
+/// or the `deterministic` option in the `[loading]` configuration table.
 #[cfg(debug_assertions)]
 pub const REORDER_SPLATS_TO_FILE_ORDER: bool = true;
 
@@ -48,52 +69,178 @@ pub const DEFAULT_WINDOW_HEIGHT: u32 = 720;
 
 
 
-/// Construct and return [`Splats`] containing a simple 5-point splatting testing scene.
-pub fn get_testing_splat_scene() -> Splats {
-    Splats::from_entries(vec![
-        Splat::new(
-            Vector3::new(0.0, 0.0, 0.0),
-            Vector3::new(1.0, 1.0, 1.0),
-            Vector4::new(244, 130, 80, 220),
-            Vector4::new(0.0, 0.0, 0.0, 0.0),
-        ),
-        Splat::new(
-            Vector3::new(0.1, 0.0, 0.0),
-            Vector3::new(1.0, 1.0, 1.0),
-            Vector4::new(200, 22, 1, 123),
-            Vector4::new(0.0, 0.0, 0.0, 0.0),
-        ),
-        Splat::new(
-            Vector3::new(0.0, 0.1, 0.0),
-            Vector3::new(1.0, 1.0, 1.0),
-            Vector4::new(200, 255, 255, 22),
-            Vector4::new(0.0, 0.0, 0.0, 0.0),
-        ),
-        Splat::new(
-            Vector3::new(0.0, 0.0, 0.1),
-            Vector3::new(1.0, 1.0, 1.0),
-            Vector4::new(22, 255, 255, 90),
-            Vector4::new(0.0, 0.0, 0.0, 0.0),
-        ),
-        Splat::new(
-            Vector3::new(0.0, -0.1, 0.0),
-            Vector3::new(1.0, 1.0, 1.0),
-            Vector4::new(22, 2, 255, 100),
-            Vector4::new(0.0, 0.0, 0.0, 0.0),
-        ),
-    ])
+/// Prints splat parsing progress to standard error as a single, continuously updated line.
+/// Nothing is printed if standard error is not a terminal (e.g. when redirected to a file).
+fn print_loading_progress(parsed_splats: usize, total_splats: usize) {
+    if !std::io::stderr().is_terminal() {
+        return;
+    }
+
+    let percentage = (parsed_splats * 100)
+        .checked_div(total_splats)
+        .unwrap_or(100);
+
+    eprint!(
+        "\rParsing splats: {}/{} ({}%)",
+        parsed_splats, total_splats, percentage
+    );
+
+    if parsed_splats >= total_splats {
+        eprintln!();
+    }
+}
+
+
+/// Formats the splat count, axis-aligned bounding box and centroid for `--print-bounds`,
+/// one `key=value` pair per line. Coordinates are formatted as `x,y,z`,
+/// or as `none` if there are no splats.
+fn format_splat_bounds(splats: &Splats) -> String {
+    let statistics = splats.statistics();
+
+    let format_point = |point: Option<Point3<f32>>| match point {
+        Some(point) => format!("{},{},{}", point.x, point.y, point.z),
+        None => "none".to_string(),
+    };
+
+    format!(
+        "splat_count={}\nbounds_min={}\nbounds_max={}\ncentroid={}",
+        statistics.splat_count,
+        format_point(statistics.bounding_box.map(|(minimum, _)| minimum)),
+        format_point(statistics.bounding_box.map(|(_, maximum)| maximum)),
+        format_point(statistics.centroid)
+    )
+}
+
+
+/// Returns the initial camera position and look target given on the command line, falling back
+/// to the ones in the `[camera]` configuration table for each of them that was not given.
+fn get_camera_pose_with_configured_fallback(
+    initial_camera_position: Option<Point3<f32>>,
+    initial_camera_look_target: Option<Point3<f32>>,
+    camera_configuration: &CameraConfiguration,
+) -> (Option<Point3<f32>>, Option<Point3<f32>>) {
+    (
+        initial_camera_position.or(camera_configuration.initial_position),
+        initial_camera_look_target.or(camera_configuration.initial_look_target),
+    )
+}
+
+
+/// Returns a builder for a thread pool with `thread_count` threads (`0` meaning all cores).
+fn get_thread_pool_builder(thread_count: usize) -> rayon::ThreadPoolBuilder {
+    rayon::ThreadPoolBuilder::new().num_threads(thread_count)
+}
+
+
+/// Loads the splats from standard input or the input files given on the command line
+/// (merging multiple files into a single scene), falling back to the testing scene without any input.
+fn load_input_splats(cli_args: &CLIArgs, decode_options: DecodeOptions) -> Result<Splats> {
+    if cli_args.read_from_stdin {
+        return Splats::load_from_reader(std::io::stdin().lock(), decode_options)
+            .wrap_err("Failed to load splats from standard input.");
+    }
+
+    let mut merged_splat_data: Option<Splats> = None;
+
+    for splat_file_path in &cli_args.input_file_paths {
+        let file_splat_data = Splats::load_from_file_with_progress(
+            splat_file_path,
+            decode_options,
+            print_loading_progress,
+        )
+        .wrap_err_with(|| {
+            miette!(
+                "Failed to load splat input file: {}",
+                splat_file_path.display()
+            )
+        })?;
+
+        match merged_splat_data.as_mut() {
+            Some(merged_splat_data) => merged_splat_data.merge(file_splat_data),
+            None => merged_splat_data = Some(file_splat_data),
+        }
+    }
+
+    if cli_args.input_file_paths.len() > 1 {
+        info!(
+            "Merged {} input files into a scene of {} splats.",
+            cli_args.input_file_paths.len(),
+            merged_splat_data
+                .as_ref()
+                .map_or(0, |merged_splat_data| merged_splat_data
+                    .splats
+                    .len())
+        );
+    }
+
+    Ok(merged_splat_data.unwrap_or_else(get_testing_splat_scene))
+}
+
+
+/// Saves the exported screenshot, rendering it at `screenshot_dimensions`
+/// if they are given (and at the render resolution otherwise).
+fn save_exported_screenshot(
+    splat_renderer: &SplatRenderer,
+    screenshot_dimensions: Option<(u32, u32)>,
+) {
+    match screenshot_dimensions {
+        Some((screenshot_width, screenshot_height)) => {
+            splat_renderer.save_screenshot_to_disk_at(screenshot_width, screenshot_height)
+        }
+        None => splat_renderer.save_screenshot_to_disk(),
+    }
 }
 
 
+/// Runs the render loop of the window if it could be created. Otherwise degrades to what
+/// the build without the `ui` feature does (e.g. on a headless machine) instead of failing,
+/// and saves a screenshot.
+#[cfg(feature = "ui")]
+fn run_window_or_save_screenshot(
+    window_manager: std::result::Result<
+        drawing::WindowManager<SplatRenderer>,
+        drawing::WindowInitializationError<SplatRenderer>,
+    >,
+    screenshot_dimensions: Option<(u32, u32)>,
+) -> Result<()> {
+    match window_manager {
+        Ok(window_manager) => window_manager.run(),
+        Err(drawing::WindowInitializationError { error, renderer }) => {
+            tracing::warn!(
+                "Failed to initialize window manager, saving a screenshot instead: {:?}",
+                error
+            );
+
+            save_exported_screenshot(&renderer, screenshot_dimensions);
+            Ok(())
+        }
+    }
+}
 
 
 fn main() -> Result<()> {
     // Parse command-line arguments.
     let cli_args = CLIArgs::parse();
 
+    if cli_args.list_keys {
+        // Only an explicitly given configuration file is loaded (for its `[keymap]` table),
+        // so the default shortcuts can be listed without any configuration file.
+        let keymap = match cli_args.configuration_file_path.as_ref() {
+            Some(path) => {
+                Configuration::load_from_path(path)
+                    .wrap_err("Failed to load configuration file.")?
+                    .keymap
+            }
+            None => Keymap::default(),
+        };
+
+        println!("{}", keymap.format());
+        return Ok(());
+    }
+
 
     // Parse configuration file.
-    let configuration = match cli_args.configuration_file_path.as_ref() {
+    let mut configuration = match cli_args.configuration_file_path.as_ref() {
         Some(path) => {
             println!("Loading configuration: {}", path.display());
             Configuration::load_from_path(path)
@@ -110,36 +257,136 @@ fn main() -> Result<()> {
         configuration.file_path.display()
     );
 
-    configuration
-        .screenshot
-        .create_screenshot_directory_if_not_exists()?;
-
 
     let logging_raii_guard = initialize_tracing(
-        configuration.logging.console_output_level_filter(),
+        resolve_console_level_filter(
+            configuration.logging.console_output_level_filter(),
+            cli_args.console_level_filter_override().map(EnvFilter::new),
+        ),
         configuration.logging.log_file_output_level_filter(),
         &configuration.logging.log_file_output_directory,
         "nrg-dn1.log",
+        configuration.logging.log_file_format,
+        configuration.logging.log_rotation,
+        configuration.logging.log_max_files,
     )
     .wrap_err("Failed to initialize tracing.")?;
 
     info!("Tracing initialized.");
 
 
+    // Configure the global thread pool before any parallel work is done.
+    if let Some(thread_count) = cli_args.threads.or(configuration.performance.threads) {
+        get_thread_pool_builder(thread_count)
+            .build_global()
+            .into_diagnostic()
+            .wrap_err("Failed to initialize the thread pool.")?;
+    }
 
-    // Load splat data from file if provided, otherwise use the testing scene.
-    let splat_data = match cli_args.input_file_path.as_ref() {
-        Some(splat_file_path) => Splats::load_from_file(splat_file_path).wrap_err_with(|| {
-            miette!(
-                "Failed to load splat input file: {}",
-                splat_file_path.display()
-            )
-        })?,
-        None => get_testing_splat_scene(),
+    info!(
+        "Using {} threads for parallel work.",
+        rayon::current_num_threads()
+    );
+
+
+
+    // The seed from the command line overrides the one in the configuration file,
+    // so the renderer sees the same seed as the loading-time operations below.
+    if let Some(seed) = cli_args.seed {
+        configuration.loading.seed = seed;
+    }
+
+    // Random downsampling and deduplication depend on the order of the splats,
+    // so they (and an explicitly requested seed) require them to be in file order.
+    let requires_file_order = cli_args.seed.is_some()
+        || cli_args.deduplicate
+        || (cli_args.max_splats.is_some()
+            && cli_args.downsample_strategy == DownsampleStrategy::Random);
+
+    // Load splat data from standard input or a file if provided, otherwise use the testing scene.
+    let decode_options = DecodeOptions {
+        strict: cli_args.strict_decoding,
+        skip_transparent_splats: cli_args.skip_transparent_splats,
+        deterministic: cli_args.deterministic
+            || configuration.loading.deterministic
+            || requires_file_order
+            || REORDER_SPLATS_TO_FILE_ORDER,
     };
 
+    let mut splat_data = load_input_splats(&cli_args, decode_options)?;
+
+    // Mirror the splats first: this changes the handedness of the data, not its placement.
+    for flipped_axis in &cli_args.flip_axes {
+        info!(
+            "Flipping splats along the {:?} axis.",
+            flipped_axis
+        );
+        splat_data.flip_axis(*flipped_axis);
+    }
+
+    // Move the splats into the world frame if a transform was requested.
+    if let Some(scene_transform) = cli_args.scene_transform()? {
+        info!("Transforming splats by {:?}.", scene_transform);
+        splat_data.apply_transform(&scene_transform);
+    }
+
+    // If only the bounds were requested, print them and exit before rendering anything.
+    if cli_args.print_bounds {
+        println!("{}", format_splat_bounds(&splat_data));
+
+        drop(logging_raii_guard);
+        return Ok(());
+    }
+
+    if cli_args.alpha_threshold > 0 {
+        let total_splats = splat_data.len();
+        let dropped_splats = splat_data.filter_by_alpha(cli_args.alpha_threshold);
 
-    // Parse initial rendering parameters from the command-line parameters.
+        info!(
+            "Discarded {} of {} splats with an opacity below {}.",
+            dropped_splats, total_splats, cli_args.alpha_threshold
+        );
+    }
+
+    if cli_args.deduplicate {
+        if !cli_args.deduplication_epsilon.is_finite() || cli_args.deduplication_epsilon <= 0.0 {
+            return Err(miette!(
+                "Deduplication epsilon must be a positive number, got {}.",
+                cli_args.deduplication_epsilon
+            ));
+        }
+
+        let total_splats = splat_data.len();
+        let merged_splats = splat_data.deduplicate(cli_args.deduplication_epsilon);
+
+        info!(
+            "Merged {} of {} splats into a coincident splat (within {} world units).",
+            merged_splats, total_splats, cli_args.deduplication_epsilon
+        );
+    }
+
+    if let Some(max_splats) = cli_args.max_splats {
+        let total_splats = splat_data.len();
+        let dropped_splats = splat_data.downsample(
+            max_splats,
+            cli_args.downsample_strategy,
+            configuration.loading.seed,
+        );
+
+        if dropped_splats > 0 {
+            info!(
+                "Downsampled splats ({:?} strategy): dropped {} of {}, keeping {}.",
+                cli_args.downsample_strategy,
+                dropped_splats,
+                total_splats,
+                splat_data.len()
+            );
+        }
+    }
+
+
+    // Parse initial rendering parameters from the command-line parameters
+    // (the camera parameters fall back to the ones in the configuration file, if any).
     let initial_camera_position = match cli_args.camera_position.as_ref() {
         Some(position_as_string) => Some(parse_str_as_point3(position_as_string)?),
         None => None,
@@ -152,13 +399,154 @@ fn main() -> Result<()> {
 
     let initial_up_vector = match cli_args.initial_up_vector.as_ref() {
         Some(vector_as_string) => Some(parse_str_as_vector3(vector_as_string)?),
+        None => configuration.camera.initial_up_vector,
+    };
+
+    let clip_planes = cli_args
+        .clip_planes
+        .iter()
+        .map(|clip_plane_as_string| {
+            let (normal, offset) = parse_str_as_clip_plane(clip_plane_as_string)?;
+            Ok(ClipPlane::new(normal, offset))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+
+    let camera_poses = match cli_args.camera_path.as_ref() {
+        Some(camera_path_file_path) => Some(parse_camera_path_file(camera_path_file_path)?),
         None => None,
     };
 
 
+    if let Some(vertical_field_of_view) = cli_args.vertical_field_of_view {
+        if !vertical_field_of_view.is_finite()
+            || vertical_field_of_view <= 0.0
+            || vertical_field_of_view >= 180.0
+        {
+            return Err(miette!(
+                "Field of view must be between 0 and 180 degrees (exclusive), got {}.",
+                vertical_field_of_view
+            ));
+        }
+    }
+
+    if let Some(turntable_degrees_per_second) = cli_args.turntable_degrees_per_second {
+        if !turntable_degrees_per_second.is_finite() {
+            return Err(miette!(
+                "Turntable speed must be a finite number, got {}.",
+                turntable_degrees_per_second
+            ));
+        }
+    }
+
+
+    if let Some(screenshot_name) = cli_args.screenshot_name.as_ref() {
+        ScreenshotConfiguration::validate_screenshot_name(screenshot_name)
+            .wrap_err("Invalid screenshot name.")?;
+
+        configuration.screenshot.screenshot_name = Some(screenshot_name.clone());
+    }
+
+    if let Some(near_plane) = cli_args.near_plane {
+        configuration.rendering.near_plane = near_plane;
+    }
+
+    if let Some(far_plane) = cli_args.far_plane {
+        configuration.rendering.far_plane = far_plane;
+    }
+
+    if let Some(billboard_size_model) = cli_args.billboard_size_model {
+        configuration.rendering.billboard_size_model = billboard_size_model;
+    }
+
+    if let Some(render_mode) = cli_args.render_mode {
+        configuration.rendering.render_mode = render_mode;
+    }
+
+    if let Some(ssaa_factor) = cli_args.ssaa_factor {
+        RenderingConfiguration::validate_ssaa_factor(ssaa_factor)
+            .wrap_err("Invalid supersampling factor.")?;
+
+        configuration.rendering.ssaa_factor = ssaa_factor;
+    }
+
+    if cli_args.clamp_offscreen {
+        configuration.rendering.clamp_offscreen = true;
+    }
+
+    RenderingConfiguration::validate_clip_planes(
+        configuration.rendering.near_plane,
+        configuration.rendering.far_plane,
+    )
+    .wrap_err("Invalid clipping planes.")?;
+
+
     let render_width = cli_args.render_width.unwrap_or(DEFAULT_WINDOW_WIDTH);
     let render_height = cli_args.render_height.unwrap_or(DEFAULT_WINDOW_HEIGHT);
 
+    RenderingConfiguration::validate_render_dimensions(
+        render_width,
+        render_height,
+        configuration.rendering.max_render_dimension,
+    )
+    .wrap_err("Invalid render dimensions.")?;
+
+
+    // Both are required together (enforced by the argument parser).
+    let screenshot_dimensions = cli_args.screenshot_width.zip(cli_args.screenshot_height);
+
+    if let Some((screenshot_width, screenshot_height)) = screenshot_dimensions {
+        RenderingConfiguration::validate_render_dimensions(
+            screenshot_width,
+            screenshot_height,
+            configuration.rendering.max_render_dimension,
+        )
+        .wrap_err("Invalid screenshot dimensions.")?;
+    }
+
+
+    // Frame the entire scene if requested (and no camera position was given on the command line).
+    let (initial_camera_position, initial_camera_look_target) =
+        if cli_args.fit_camera_to_scene && initial_camera_position.is_none() {
+            let (fitted_camera_position, fitted_camera_look_target) = get_camera_pose_fitting_splats(
+                &splat_data,
+                cli_args
+                    .vertical_field_of_view
+                    .unwrap_or(configuration.camera.vertical_field_of_view_in_degrees),
+                render_width,
+                render_height,
+                configuration.rendering.near_plane,
+            );
+
+            info!(
+                "Fitted camera to scene: position {:?}, look target {:?}.",
+                fitted_camera_position, fitted_camera_look_target
+            );
+
+            (
+                Some(fitted_camera_position),
+                initial_camera_look_target.or(Some(fitted_camera_look_target)),
+            )
+        } else {
+            get_camera_pose_with_configured_fallback(
+                initial_camera_position,
+                initial_camera_look_target,
+                &configuration.camera,
+            )
+        };
+
+
+    // Everything has been loaded and validated at this point. If this is a dry run, stop here.
+    if cli_args.dry_run {
+        println!(
+            "Dry run: configuration and {} splats loaded successfully, nothing was rendered.",
+            splat_data.len()
+        );
+
+        drop(logging_raii_guard);
+        return Ok(());
+    }
+
 
     // Initialize the splat rendered and drawing manager.
     let splat_renderer = SplatRenderer::new(
@@ -167,25 +555,92 @@ fn main() -> Result<()> {
         render_height,
         splat_data,
         cli_args.splat_scaling_factor,
+        cli_args.camera_movement_speed,
+        cli_args.vertical_field_of_view,
         initial_camera_position,
         initial_camera_look_target,
         initial_up_vector,
     );
 
-    splat_renderer.render_in_place();
+    if !clip_planes.is_empty() {
+        info!(
+            "Clipping splats to {} plane(s).",
+            clip_planes.len()
+        );
+        splat_renderer.set_clip_planes(clip_planes);
+    }
+
+    if let Some(projection_dump_path) = cli_args.dump_projection.as_ref() {
+        splat_renderer
+            .dump_projection_to_csv(projection_dump_path)
+            .wrap_err("Failed to dump splat projection.")?;
+    }
+
+    // If benchmarking was requested, render the scene repeatedly and exit.
+    if let Some(benchmark_run_count) = cli_args.benchmark {
+        run_render_benchmark(&splat_renderer, benchmark_run_count as usize);
+
+        drop(logging_raii_guard);
+        return Ok(());
+    }
+
+    // If a camera path was provided, render each pose and exit.
+    if let Some(camera_poses) = camera_poses {
+        let index_width = camera_poses.len().to_string().len().max(4);
+
+        for (pose_index, (camera_position, camera_look_target)) in
+            camera_poses.into_iter().enumerate()
+        {
+            info!(
+                "Rendering camera pose {}: position {:?}, look target {:?}.",
+                pose_index, camera_position, camera_look_target
+            );
+
+            splat_renderer.set_camera_pose(camera_position, camera_look_target);
+            splat_renderer.save_screenshot_to_disk_as(&format!(
+                "nrg-screenshot_{:0width$}",
+                pose_index,
+                width = index_width
+            ));
+        }
+
+        drop(logging_raii_guard);
+        return Ok(());
+    }
+
+
+    // Screenshots of a different size are rendered separately.
+    if screenshot_dimensions.is_none() {
+        let render_stats = splat_renderer.render_in_place();
+
+        info!(
+            "Initial render drew {} splats ({} culled) in {} milliseconds.",
+            render_stats.visible_splats,
+            render_stats.culled_splats,
+            (render_stats.timings.total.as_secs_f64() * 1000.0).round() as u32
+        );
+    }
 
 
     #[cfg(feature = "ui")]
     {
         if cli_args.export_screenshot_and_exit {
-            splat_renderer.save_screenshot_to_disk();
+            save_exported_screenshot(&splat_renderer, screenshot_dimensions);
         } else {
             use crate::drawing::WindowManager;
 
-            let drawing_manager = WindowManager::new(render_width, render_height, splat_renderer)
-                .wrap_err("Failed to initialize window manager.")?;
-
-            drawing_manager.run()?;
+            if let Some(turntable_degrees_per_second) = cli_args.turntable_degrees_per_second {
+                info!(
+                    "Turntable mode enabled, orbiting at {} degrees per second.",
+                    turntable_degrees_per_second
+                );
+                splat_renderer.set_turntable_speed(Some(turntable_degrees_per_second));
+            }
+
+            run_window_or_save_screenshot(
+                WindowManager::new(render_width, render_height, splat_renderer),
+                screenshot_dimensions,
+            )?;
         }
     }
 
@@ -193,7 +648,11 @@ fn main() -> Result<()> {
     {
         // Since all graphical window dependencies are not present,
         // just save a screenshot to disk and exit.
-        splat_renderer.save_screenshot_to_disk();
+        if cli_args.turntable_degrees_per_second.is_some() {
+            tracing::warn!("Turntable mode requires the interactive window, ignoring --turntable.");
+        }
+
+        save_exported_screenshot(&splat_renderer, screenshot_dimensions);
     }
 
 
@@ -201,3 +660,194 @@ fn main() -> Result<()> {
     drop(logging_raii_guard);
     Ok(())
 }
+
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Vector3, Vector4};
+
+    use super::*;
+    use crate::splat_decoder::Splat;
+
+    #[test]
+    fn splat_bounds_are_formatted_as_key_value_lines() {
+        let get_splat = |x: f32, y: f32, z: f32| {
+            Splat::new(
+                Vector3::new(x, y, z),
+                Vector3::repeat(0.1),
+                Vector4::new(255, 255, 255, 255),
+                Vector4::new(1.0, 0.0, 0.0, 0.0),
+            )
+        };
+
+        let splats = Splats::from_entries(vec![
+            get_splat(-1.0, 0.0, 2.0),
+            get_splat(1.0, 4.0, -2.0),
+        ]);
+
+        assert_eq!(
+            format_splat_bounds(&splats),
+            "splat_count=2\n\
+             bounds_min=-1,0,-2\n\
+             bounds_max=1,4,2\n\
+             centroid=0,2,0"
+        );
+    }
+
+    #[test]
+    fn configured_camera_position_is_used_without_the_command_line_flag() {
+        let configuration = Configuration::load_for_tests(
+            "[camera]\nposition = \"1,2,3\"\nlook_target = [0.5, 0.0, 0.0]\n",
+        );
+
+        assert_eq!(
+            get_camera_pose_with_configured_fallback(None, None, &configuration.camera),
+            (
+                Some(Point3::new(1.0, 2.0, 3.0)),
+                Some(Point3::new(0.5, 0.0, 0.0))
+            )
+        );
+
+        // Camera parameters given on the command line take precedence.
+        assert_eq!(
+            get_camera_pose_with_configured_fallback(
+                Some(Point3::new(4.0, 5.0, 6.0)),
+                None,
+                &configuration.camera
+            ),
+            (
+                Some(Point3::new(4.0, 5.0, 6.0)),
+                Some(Point3::new(0.5, 0.0, 0.0))
+            )
+        );
+    }
+
+    #[test]
+    fn camera_pose_is_unspecified_without_flags_or_configuration() {
+        let configuration = Configuration::load_for_tests("");
+
+        assert_eq!(
+            get_camera_pose_with_configured_fallback(None, None, &configuration.camera),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn thread_pool_has_the_requested_number_of_threads() {
+        let thread_pool = get_thread_pool_builder(3)
+            .build()
+            .expect("thread pool should build");
+
+        assert_eq!(thread_pool.current_num_threads(), 3);
+        assert_eq!(thread_pool.install(rayon::current_num_threads), 3);
+    }
+
+    #[test]
+    fn thread_pool_with_zero_threads_uses_all_cores() {
+        let thread_pool = get_thread_pool_builder(0)
+            .build()
+            .expect("thread pool should build");
+
+        assert_eq!(
+            thread_pool.current_num_threads(),
+            rayon::ThreadPoolBuilder::new()
+                .build()
+                .expect("thread pool should build")
+                .current_num_threads()
+        );
+    }
+
+    #[test]
+    fn bounds_of_an_empty_scene_are_none() {
+        assert_eq!(
+            format_splat_bounds(&Splats::from_entries(Vec::new())),
+            "splat_count=0\nbounds_min=none\nbounds_max=none\ncentroid=none"
+        );
+    }
+
+
+    /// Decoding options used to load the files in these tests.
+    const TEST_DECODE_OPTIONS: DecodeOptions = DecodeOptions {
+        strict: true,
+        skip_transparent_splats: false,
+        deterministic: true,
+    };
+
+    #[test]
+    fn dry_run_loads_a_valid_fixture() {
+        let fixture_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/references/fixtures/two-splats.json"
+        );
+        let cli_args = CLIArgs::try_parse_from(["nrg-dn1", "--dry-run", "-i", fixture_path])
+            .expect("flags should parse");
+
+        assert!(cli_args.dry_run);
+
+        let splats =
+            load_input_splats(&cli_args, TEST_DECODE_OPTIONS).expect("valid fixture should load");
+        assert_eq!(splats.len(), 2);
+    }
+
+    #[test]
+    fn dry_run_fails_on_a_corrupt_file() {
+        let corrupt_file_path = std::env::temp_dir().join("nrg-dn1-tests-corrupt-dry-run.splat");
+        // Not a multiple of the 32-byte record size of the .splat format.
+        std::fs::write(&corrupt_file_path, [0u8; 45]).expect("corrupt file should be writable");
+
+        let cli_args = CLIArgs::try_parse_from([
+            "nrg-dn1".as_ref(),
+            "--dry-run".as_ref(),
+            "-i".as_ref(),
+            corrupt_file_path.as_os_str(),
+        ])
+        .expect("flags should parse");
+
+        let error = load_input_splats(&cli_args, TEST_DECODE_OPTIONS)
+            .expect_err("corrupt file should not load");
+        assert!(
+            error
+                .to_string()
+                .contains("Failed to load splat input file"),
+            "unexpected error: {}",
+            error
+        );
+
+        std::fs::remove_file(&corrupt_file_path).ok();
+    }
+
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn failing_to_create_the_window_saves_a_screenshot_instead() {
+        const CONFIGURATION_TABLES: &str = "screenshot_name = \"window-fallback-test.png\"\n";
+
+        let screenshot_path = Configuration::load_for_tests(CONFIGURATION_TABLES)
+            .screenshot
+            .screenshot_path("window-fallback-test.png");
+        let _ = std::fs::remove_file(&screenshot_path);
+
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (32, 32),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            CONFIGURATION_TABLES,
+        );
+
+        run_window_or_save_screenshot(
+            Err(drawing::WindowInitializationError {
+                error: miette!("No display available."),
+                renderer,
+            }),
+            None,
+        )
+        .expect("window fallback should not fail");
+
+        let screenshot = image::open(&screenshot_path).expect("screenshot should be saved");
+        assert_eq!(
+            (screenshot.width(), screenshot.height()),
+            (32, 32)
+        );
+    }
+}
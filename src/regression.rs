@@ -0,0 +1,215 @@
+//! Render regression tests: the testing scene and the fixtures in `references/fixtures` are rendered
+//! headlessly and compared with the reference images in `references`.
+//!
+//! After an intended change to the rendered output, re-render the reference images by running
+//! the tests with the `UPDATE_REFERENCES` environment variable set (e.g. `UPDATE_REFERENCES=1 cargo test`).
+
+use std::path::PathBuf;
+
+use image::RgbaImage;
+use nalgebra::Point3;
+
+use crate::{
+    renderer::SplatRenderer,
+    splat_decoder::{get_testing_splat_scene, DecodeOptions, Splats},
+};
+
+
+/// Resolution the reference images are rendered at.
+const REFERENCE_RENDER_DIMENSIONS: (u32, u32) = (256, 256);
+
+/// Largest difference (0-255) of any pixel component that is still considered equal.
+const REFERENCE_TOLERANCE: u8 = 2;
+
+
+/// Per-component differences between a rendered image and its reference.
+struct ImageDifference {
+    /// Largest difference of any (RGBA) component.
+    maximum: u8,
+
+    /// Mean difference over all components.
+    mean: f64,
+
+    /// Number of pixels with at least one component differing by more than the tolerance.
+    mismatched_pixels: usize,
+}
+
+/// Compares the `rendered` image with the `reference` image (both must be of the same size),
+/// counting pixels with a component that differs by more than `tolerance` as mismatched.
+fn compare_images(rendered: &RgbaImage, reference: &RgbaImage, tolerance: u8) -> ImageDifference {
+    let mut maximum = 0;
+    let mut difference_sum = 0u64;
+    let mut mismatched_pixels = 0;
+
+    for (rendered_pixel, reference_pixel) in rendered.pixels().zip(reference.pixels()) {
+        let pixel_maximum = rendered_pixel
+            .0
+            .iter()
+            .zip(reference_pixel.0.iter())
+            .map(|(rendered_component, reference_component)| {
+                let difference = rendered_component.abs_diff(*reference_component);
+                difference_sum += difference as u64;
+                difference
+            })
+            .max()
+            .unwrap_or(0);
+
+        maximum = maximum.max(pixel_maximum);
+        if pixel_maximum > tolerance {
+            mismatched_pixels += 1;
+        }
+    }
+
+    let component_count = rendered.as_raw().len().max(1);
+
+    ImageDifference {
+        maximum,
+        mean: difference_sum as f64 / component_count as f64,
+        mismatched_pixels,
+    }
+}
+
+
+/// Returns the path of a file in the `references` directory.
+fn get_reference_path(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("references")
+        .join(file_name)
+}
+
+/// Renders `splats` at [`REFERENCE_RENDER_DIMENSIONS`] from `camera_position` (looking at the origin),
+/// with the default settings plus `additional_configuration_tables`.
+fn render_reference_scene(
+    splats: Splats,
+    camera_position: Point3<f32>,
+    additional_configuration_tables: &str,
+) -> RgbaImage {
+    SplatRenderer::new_for_tests(
+        splats,
+        REFERENCE_RENDER_DIMENSIONS,
+        camera_position,
+        Point3::origin(),
+        additional_configuration_tables,
+    )
+    .render_to_image()
+}
+
+/// Compares the `rendered` image with the reference image `reference_file_name`, panicking
+/// (with the maximum and mean difference) if any component differs by more than [`REFERENCE_TOLERANCE`].
+///
+/// If the `UPDATE_REFERENCES` environment variable is set, the reference image is overwritten instead.
+fn assert_matches_reference(rendered: &RgbaImage, reference_file_name: &str) {
+    let reference_path = get_reference_path(reference_file_name);
+
+    if std::env::var_os("UPDATE_REFERENCES").is_some() {
+        rendered
+            .save(&reference_path)
+            .expect("reference image should be writable");
+        return;
+    }
+
+
+    let reference = image::open(&reference_path)
+        .unwrap_or_else(|error| {
+            panic!(
+                "failed to load reference image {}: {}",
+                reference_path.display(),
+                error
+            )
+        })
+        .to_rgba8();
+
+    assert_eq!(
+        reference.dimensions(),
+        rendered.dimensions(),
+        "reference image {} has a different size than the render",
+        reference_file_name
+    );
+
+    let difference = compare_images(rendered, &reference, REFERENCE_TOLERANCE);
+
+    assert_eq!(
+        difference.mismatched_pixels,
+        0,
+        "render differs from reference {}: {} pixels differ by more than {} \
+         (maximum difference {}, mean difference {:.4})",
+        reference_file_name,
+        difference.mismatched_pixels,
+        REFERENCE_TOLERANCE,
+        difference.maximum,
+        difference.mean
+    );
+}
+
+/// Loads the translucent grid fixture (27 translucent splats on a 3x3x3 grid).
+fn load_translucent_grid_fixture() -> Splats {
+    Splats::load_from_file(
+        get_reference_path("fixtures/translucent-grid.json"),
+        DecodeOptions {
+            strict: true,
+            skip_transparent_splats: false,
+            deterministic: true,
+        },
+    )
+    .expect("fixture should load")
+}
+
+
+#[test]
+fn compare_images_counts_pixels_beyond_the_tolerance() {
+    let reference = RgbaImage::from_pixel(2, 2, image::Rgba([100, 100, 100, 255]));
+
+    let mut rendered = reference.clone();
+    rendered.put_pixel(0, 0, image::Rgba([102, 100, 100, 255]));
+    rendered.put_pixel(1, 1, image::Rgba([100, 90, 100, 255]));
+
+    let difference = compare_images(&rendered, &reference, 2);
+
+    assert_eq!(difference.mismatched_pixels, 1);
+    assert_eq!(difference.maximum, 10);
+    assert!((difference.mean - 12.0 / 16.0).abs() < 1e-9);
+}
+
+#[test]
+fn testing_scene_matches_reference() {
+    let rendered = render_reference_scene(
+        get_testing_splat_scene(),
+        Point3::new(0.3, 0.24, 0.4),
+        "",
+    );
+
+    assert_matches_reference(&rendered, "testing-scene.png");
+}
+
+#[test]
+fn testing_scene_from_the_side_matches_reference() {
+    let rendered = render_reference_scene(
+        get_testing_splat_scene(),
+        Point3::new(0.45, 0.2, 0.1),
+        "",
+    );
+
+    assert_matches_reference(&rendered, "testing-scene-side.png");
+}
+
+#[test]
+fn translucent_grid_matches_reference() {
+    let rendered = render_reference_scene(
+        load_translucent_grid_fixture(),
+        Point3::new(0.35, 0.28, 0.45),
+        "",
+    );
+
+    assert_matches_reference(&rendered, "translucent-grid.png");
+}
+
+#[test]
+fn translucent_grid_points_match_reference() {
+    let rendered = render_reference_scene(
+        load_translucent_grid_fixture(),
+        Point3::new(0.35, 0.28, 0.45),
+        "[rendering]\nrender_mode = \"points\"\n",
+    );
+
+    assert_matches_reference(&rendered, "translucent-grid-points.png");
+}
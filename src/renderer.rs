@@ -1,35 +1,148 @@
-use std::{iter::FusedIterator, time::Instant};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    iter::FusedIterator,
+    ops::Range,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
 
 use chrono::Local;
-use image::{ImageFormat, RgbaImage};
-use miette::Result;
-use nalgebra::{Matrix4, Perspective3, Point3, Vector3, Vector4};
-use parking_lot::RwLock;
+use image::{ImageBuffer, ImageFormat, Rgb32FImage, Rgba, RgbaImage};
+use miette::{Context, IntoDiagnostic, Result};
+use nalgebra::{
+    Matrix2,
+    Matrix2x3,
+    Matrix3,
+    Matrix4,
+    Perspective3,
+    Point3,
+    Vector2,
+    Vector3,
+    Vector4,
+};
+#[cfg(feature = "ui")]
+use nalgebra::{Unit, UnitQuaternion};
+use parking_lot::{Mutex, RwLock};
 use rayon::{
-    iter::{IntoParallelRefIterator, ParallelIterator},
+    iter::{
+        IndexedParallelIterator,
+        IntoParallelIterator,
+        IntoParallelRefIterator,
+        ParallelIterator,
+    },
     slice::ParallelSliceMut,
 };
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 #[cfg(feature = "ui")]
 use winit::{
-    event::{ElementState, MouseButton, WindowEvent},
+    event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
     keyboard::{Key, NamedKey},
 };
 
+use self::keybindings::Keymap;
+#[cfg(feature = "ui")]
+use self::keybindings::{get_key_name, KeyAction};
+use self::lod::LodGrid;
 use crate::{
-    configuration::Configuration,
+    configuration::{
+        AlphaMode,
+        Backdrop,
+        BillboardShape,
+        BillboardSizeModel,
+        Configuration,
+        InputColorSpace,
+        RenderMode,
+        ScreenshotFormat,
+        SortKey,
+        ToneMapping,
+    },
     splat_decoder::{Splat, Splats},
 };
 
+pub mod keybindings;
+mod lod;
+mod overlay;
+
 
 pub trait PixelSurfaceRenderer {
+    /// Copies the most recently completed frame onto `frame`. This must not render
+    /// (see [`Self::render`]), so that drawing stays responsive while a render is in progress.
     fn draw(&self, frame: &mut [u8]);
+
+    /// Returns whether the scene changed since the last completed render,
+    /// i.e. whether [`Self::render`] should be called.
+    fn needs_render(&self) -> bool;
+
+    /// Renders a new frame. This is called on a background thread,
+    /// concurrently with [`Self::draw`] and the handling of window events.
+    fn render(&self);
+
+    /// Called when the size of the surface (in pixels) changes. Returns the size of
+    /// subsequently rendered frames, which may be smaller than the requested one.
+    fn resize(&self, width: u32, height: u32) -> (u32, u32);
+
+    /// If the last rendered frame was a reduced-quality preview, returns how long to wait
+    /// before calling [`Self::render`] again to replace it with a full-quality frame.
+    fn pending_refinement_delay(&self) -> Option<Duration> {
+        None
+    }
 }
 
 
 #[cfg(feature = "ui")]
 pub trait InteractiveRenderer {
-    fn handle_window_event(&mut self, window_event: &WindowEvent) -> Result<()>;
+    fn handle_window_event(&self, window_event: &WindowEvent) -> Result<()>;
+
+    /// Returns the keyboard shortcuts of the interactive window.
+    fn keymap(&self) -> &Keymap;
+
+    /// Called when the user clicks (with the right mouse button) onto the given pixel of the frame.
+    fn handle_pixel_click(&self, _pixel: (u32, u32)) {}
+
+    /// Advances time-based camera animations (e.g. the turntable) by `elapsed` (real time).
+    /// Returns `true` if the renderer is animating and the window should keep redrawing.
+    fn advance_animation(&self, _elapsed: Duration) -> bool {
+        false
+    }
+
+    /// Returns the current adjustable settings and readouts shown in the control panel.
+    #[cfg(feature = "egui")]
+    fn control_panel_state(&self) -> ControlPanelState;
+
+    /// Applies the settings adjusted in the control panel, scheduling a rerender if they changed.
+    #[cfg(feature = "egui")]
+    fn apply_render_settings(&self, render_settings: RenderSettings);
+}
+
+
+/// Renderer settings that can be adjusted at runtime in the control panel.
+#[cfg(feature = "egui")]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RenderSettings {
+    pub splat_scaling_factor: f32,
+
+    /// RGBA color the canvas is cleared to.
+    pub background_color: [u8; 4],
+
+    pub vertical_field_of_view_in_degrees: f32,
+
+    pub near_plane: f32,
+
+    pub far_plane: f32,
+}
+
+/// Everything the control panel shows: the adjustable settings and some read-only values.
+#[cfg(feature = "egui")]
+#[derive(Clone, Copy, Debug)]
+pub struct ControlPanelState {
+    pub render_settings: RenderSettings,
+
+    pub camera_position: Point3<f32>,
+
+    /// How long the last render took, if there was one.
+    pub last_render_duration: Option<Duration>,
 }
 
 
@@ -46,6 +159,143 @@ pub struct PixelPosition {
 }
 
 
+/// Screen-space shape of a splat's billboard.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BillboardFootprint {
+    /// An axis-aligned square with the given side length (in pixels).
+    /// Only the pixels inside the `shape` inscribed in the square are covered.
+    Square {
+        size_in_pixels: u32,
+        shape: BillboardShape,
+    },
+
+    /// An ellipse, described by its 2D covariance matrix (in pixels squared).
+    /// The edge of the ellipse lies at one standard deviation.
+    Ellipse {
+        covariance: Matrix2<f32>,
+        inverse_covariance: Matrix2<f32>,
+    },
+}
+
+impl BillboardFootprint {
+    /// Variance (in pixels squared) that is added to the diagonal of each 2D covariance matrix.
+    /// This ensures that the matrix is invertible and that very thin splats
+    /// still cover at least around one pixel.
+    const COVARIANCE_LOW_PASS_VARIANCE: f32 = 0.3;
+
+    /// Construct an elliptical footprint from a 2D covariance matrix (in pixels squared).
+    ///
+    /// Returns `None` if the matrix contains non-finite values or is not invertible.
+    pub fn from_covariance(covariance: Matrix2<f32>) -> Option<Self> {
+        if !covariance.iter().all(|value| value.is_finite()) {
+            return None;
+        }
+
+        let covariance =
+            covariance + Matrix2::from_diagonal_element(Self::COVARIANCE_LOW_PASS_VARIANCE);
+        let inverse_covariance = covariance.try_inverse()?;
+
+        Some(Self::Ellipse {
+            covariance,
+            inverse_covariance,
+        })
+    }
+
+    /// Returns this footprint, uniformly scaled by `factor`.
+    pub fn scaled(&self, factor: f32) -> Self {
+        match self {
+            Self::Square {
+                size_in_pixels,
+                shape,
+            } => Self::Square {
+                size_in_pixels: (*size_in_pixels as f32 * factor).round() as u32,
+                shape: *shape,
+            },
+            Self::Ellipse {
+                covariance,
+                inverse_covariance,
+            } => Self::Ellipse {
+                covariance: covariance * (factor * factor),
+                inverse_covariance: inverse_covariance / (factor * factor),
+            },
+        }
+    }
+
+    /// Returns this footprint, uniformly scaled so that the larger side of its bounding box
+    /// (the side length of squares, or the 1-sigma extent of ellipses) lies
+    /// between `minimum_size_in_pixels` and `maximum_size_in_pixels`.
+    pub fn clamped_to_size(&self, minimum_size_in_pixels: u32, maximum_size_in_pixels: u32) -> Self {
+        match self {
+            Self::Square {
+                size_in_pixels,
+                shape,
+            } => Self::Square {
+                size_in_pixels: (*size_in_pixels)
+                    .clamp(minimum_size_in_pixels, maximum_size_in_pixels),
+                shape: *shape,
+            },
+            Self::Ellipse { covariance, .. } => {
+                let size_in_pixels = 2.0 * covariance.m11.max(covariance.m22).sqrt();
+
+                if size_in_pixels > maximum_size_in_pixels as f32 {
+                    self.scaled(maximum_size_in_pixels as f32 / size_in_pixels)
+                } else if size_in_pixels < minimum_size_in_pixels as f32 && size_in_pixels > 0.0 {
+                    self.scaled(minimum_size_in_pixels as f32 / size_in_pixels)
+                } else {
+                    *self
+                }
+            }
+        }
+    }
+
+    /// Returns the half-width and half-height (in pixels) of the footprint's bounding box.
+    pub fn half_extents(&self) -> (u32, u32) {
+        match self {
+            Self::Square { size_in_pixels, .. } => {
+                let linear_distance = (*size_in_pixels).max(1).div_ceil(2);
+                (linear_distance, linear_distance)
+            }
+            Self::Ellipse { covariance, .. } => {
+                // The bounding box of a 1-sigma ellipse extends
+                // sqrt(variance) pixels along each axis.
+                (
+                    covariance.m11.sqrt().ceil() as u32,
+                    covariance.m22.sqrt().ceil() as u32,
+                )
+            }
+        }
+    }
+
+    /// Returns the squared distance of `pixel_offset` (relative to the billboard center),
+    /// normalized so that the edge of the footprint lies at `1.0`.
+    ///
+    /// For elliptical footprints this is the squared Mahalanobis distance. For square footprints
+    /// the distance is measured so that its level sets have the footprint's [`BillboardShape`].
+    #[inline]
+    pub fn normalized_distance_squared(&self, pixel_offset: Vector2<f32>) -> f32 {
+        match self {
+            Self::Square {
+                size_in_pixels,
+                shape,
+            } => {
+                let half_size = (*size_in_pixels).max(1) as f32 / 2.0;
+
+                let distance = match shape {
+                    BillboardShape::Square => pixel_offset.x.abs().max(pixel_offset.y.abs()),
+                    BillboardShape::Circle => pixel_offset.norm(),
+                    BillboardShape::Diamond => pixel_offset.x.abs() + pixel_offset.y.abs(),
+                };
+
+                (distance * distance) / (half_size * half_size)
+            }
+            Self::Ellipse {
+                inverse_covariance, ..
+            } => pixel_offset.dot(&(inverse_covariance * pixel_offset)),
+        }
+    }
+}
+
+
 pub struct BillboardCoordinatesIterator {
     x_start: u32,
     x_stop: u32,
@@ -57,24 +307,61 @@ pub struct BillboardCoordinatesIterator {
     next_x: u32,
     next_y: u32,
 
+    /// Subpixel center and footprint, unless the footprint covers its entire bounding box
+    /// (i.e. is a filled square). Pixels outside of the footprint's shape are skipped.
+    shaped_footprint: Option<((f32, f32), BillboardFootprint)>,
+
     finished: bool,
 }
 
 impl BillboardCoordinatesIterator {
-    pub fn from_center_and_size(
+    /// Creates an iterator over the pixels covered by a `footprint` centered at the given
+    /// subpixel coordinates (the bounding box is not snapped to the nearest pixel first).
+    pub fn from_center_and_footprint(
         viewport_size: (u32, u32),
-        center_pixel: (u32, u32),
-        billboard_size_pixels: u32,
+        center: (f32, f32),
+        footprint: BillboardFootprint,
     ) -> Self {
-        let (viewport_width, viewport_height) = viewport_size;
-        let (center_x, center_y) = center_pixel;
-        let linear_distance = billboard_size_pixels.max(1).div_ceil(2);
+        let shaped_footprint = match footprint {
+            BillboardFootprint::Square {
+                shape: BillboardShape::Square,
+                ..
+            } => None,
+            _ => Some(footprint),
+        };
+
+        Self::from_center_and_half_extents(
+            viewport_size,
+            center,
+            footprint.half_extents(),
+            shaped_footprint,
+        )
+    }
+
+    /// Restricts the iterator to only yield pixels in the given (half-open) range of rows.
+    pub fn restricted_to_rows(mut self, rows: Range<u32>) -> Self {
+        self.next_y = self.next_y.max(rows.start);
+        self.y_stop = self.y_stop.min(rows.end.saturating_sub(1));
 
-        let x_start = center_x.saturating_sub(linear_distance);
-        let x_stop = center_x.saturating_add(linear_distance);
+        if rows.is_empty() || self.next_y > self.y_stop {
+            self.finished = true;
+        }
+
+        self
+    }
+
+    fn from_center_and_half_extents(
+        viewport_size: (u32, u32),
+        center: (f32, f32),
+        half_extents: (u32, u32),
+        shaped_footprint: Option<BillboardFootprint>,
+    ) -> Self {
+        let (viewport_width, viewport_height) = viewport_size;
+        let (center_x, center_y) = center;
+        let (half_extent_x, half_extent_y) = half_extents;
 
-        let y_start = center_y.saturating_sub(linear_distance);
-        let y_stop = center_y.saturating_add(linear_distance);
+        let (x_start, x_stop) = get_covered_pixel_range(center_x, half_extent_x);
+        let (y_start, y_stop) = get_covered_pixel_range(center_y, half_extent_y);
 
 
         Self {
@@ -85,702 +372,6243 @@ impl BillboardCoordinatesIterator {
             y_max: viewport_height - 1,
             next_x: x_start,
             next_y: y_start,
-            finished: false,
+            shaped_footprint: shaped_footprint.map(|footprint| (center, footprint)),
+            // A zero half-extent around a fractional center covers no pixel centers at all.
+            finished: x_start > x_stop || y_start > y_stop,
         }
     }
+
+    #[inline]
+    fn is_inside_footprint(&self, x: u32, y: u32) -> bool {
+        let Some(((center_x, center_y), footprint)) = &self.shaped_footprint else {
+            return true;
+        };
+
+        let offset = Vector2::new(x as f32 - center_x, y as f32 - center_y);
+
+        footprint.normalized_distance_squared(offset) <= 1.0
+    }
 }
 
 impl Iterator for BillboardCoordinatesIterator {
     type Item = PixelPosition;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.finished {
-            return None;
-        }
+        loop {
+            if self.finished {
+                return None;
+            }
 
-        let (current_x, current_y) = (self.next_x, self.next_y);
+            let (current_x, current_y) = (self.next_x, self.next_y);
 
 
-        self.next_x += 1;
+            self.next_x += 1;
 
-        if self.next_x > self.x_stop || self.next_x > self.x_max {
-            self.next_x = self.x_start;
-            self.next_y += 1;
-        }
+            if self.next_x > self.x_stop || self.next_x > self.x_max {
+                self.next_x = self.x_start;
+                self.next_y += 1;
+            }
 
-        if self.next_y > self.y_stop || self.next_y > self.y_max {
-            self.finished = true;
-        }
+            if self.next_y > self.y_stop || self.next_y > self.y_max {
+                self.finished = true;
+            }
 
-        Some(PixelPosition {
-            x: current_x,
-            y: current_y,
-        })
+            if self.is_inside_footprint(current_x, current_y) {
+                return Some(PixelPosition {
+                    x: current_x,
+                    y: current_y,
+                });
+            }
+        }
     }
 }
 
 impl FusedIterator for BillboardCoordinatesIterator {}
 
 
+/// Returns the first and last pixel (inclusive, along one axis) whose centers lie within
+/// `half_extent` pixels of the subpixel `center`. Pixels before the start of the viewport are skipped.
 #[inline]
-fn get_pixel_coordinates_from_projected_coordinates(
-    projected_position: Vector4<f32>,
-    render_width: u32,
-    render_height: u32,
-) -> Option<(u32, u32)> {
-    // debug!("Before processing: {:?}", projected_position);
-
-    let mut projected_x = *projected_position.get(0).unwrap();
-    let mut projected_y = *projected_position.get(1).unwrap();
-    let mut projected_z = *projected_position.get(2).unwrap();
-    let projected_w = *projected_position.get(3).unwrap();
-
-    projected_x *= 1f32 / projected_w;
-    projected_y *= 1f32 / projected_w;
-    projected_z *= 1f32 / projected_w;
-
-    // debug!(
-    //     "After /= with w: {},{},{},{}",
-    //     projected_x, projected_y, projected_z, projected_w
-    // );
+fn get_covered_pixel_range(center: f32, half_extent: u32) -> (u32, u32) {
+    (
+        (center - half_extent as f32).ceil().max(0.0) as u32,
+        (center + half_extent as f32).floor().max(0.0) as u32,
+    )
+}
 
-    projected_x *= 1f32 / projected_z;
-    projected_y *= 1f32 / projected_z;
 
+/// Number of standard deviations after which the Gaussian falloff is cut off
+/// (pixels further away are not drawn at all).
+const GAUSSIAN_FALLOFF_CUTOFF_IN_SIGMAS: f32 = 3.0;
 
-    if !(-1.0..=1.0).contains(&projected_x) {
-        return None;
-    }
+/// Smallest effective alpha (the splat's opacity times its falloff) at which a pixel
+/// writes the splat's depth into the depth buffer (when the depth test is enabled).
+const DEPTH_WRITE_ALPHA_THRESHOLD: f32 = 0.99;
 
-    if !(-1.0..=1.0).contains(&projected_y) {
-        return None;
+/// Returns the Gaussian falloff multiplier (in `[0, 1]`) for a pixel
+/// that is `pixel_offset` pixels away from the billboard center.
+///
+/// The `falloff_sigma` is expressed relative to the billboard extent,
+/// i.e. with `0.5` the edge of the footprint lies at two standard deviations.
+/// Pixels further than [`GAUSSIAN_FALLOFF_CUTOFF_IN_SIGMAS`] standard deviations
+/// from the center get a multiplier of zero.
+#[inline]
+fn get_gaussian_falloff(
+    pixel_offset: Vector2<f32>,
+    footprint: &BillboardFootprint,
+    falloff_sigma: f32,
+) -> f32 {
+    let distance_in_sigmas_squared =
+        footprint.normalized_distance_squared(pixel_offset) / (falloff_sigma * falloff_sigma);
+
+    if distance_in_sigmas_squared
+        > GAUSSIAN_FALLOFF_CUTOFF_IN_SIGMAS * GAUSSIAN_FALLOFF_CUTOFF_IN_SIGMAS
+    {
+        return 0.0;
     }
 
+    (-0.5 * distance_in_sigmas_squared).exp()
+}
 
-    // x and y are now guaranteed to be between -1 and 1,
-    // so the next step is to remap them into u32 render coordinates.
-    let render_x: u32 = (((projected_x + 1.0) / 2.0) * (render_width as f32 - 1.0)).round() as u32;
-    let render_y: u32 = (((projected_y + 1.0) / 2.0) * (render_height as f32 - 1.0)).round() as u32;
 
+/// Converts an sRGB-encoded color component (in `[0, 1]`) to linear light.
+#[inline]
+fn srgb_to_linear(component: f32) -> f32 {
+    if component <= 0.04045 {
+        component / 12.92
+    } else {
+        ((component + 0.055) / 1.055).powf(2.4)
+    }
+}
 
-    #[cfg(debug_assertions)]
-    {
-        if render_x >= render_width {
-            panic!(
-                "render_x is larger than render width: {}",
-                render_x
-            );
-        }
+/// Converts a linear color component (in `[0, 1]`) to its sRGB encoding.
+#[inline]
+fn linear_to_srgb(component: f32) -> f32 {
+    if component <= 0.0031308 {
+        component * 12.92
+    } else {
+        1.055 * component.powf(1.0 / 2.4) - 0.055
+    }
+}
 
-        if render_y >= render_height {
-            panic!(
-                "render_y is larger than render height: {}",
-                render_y
-            );
+/// Applies the `tone_mapping` operator to a linear color component.
+/// The `exposure` is only used by [`ToneMapping::Reinhard`].
+#[inline]
+fn get_tone_mapped_component(component: f32, tone_mapping: ToneMapping, exposure: f32) -> f32 {
+    match tone_mapping {
+        ToneMapping::None => component,
+        ToneMapping::Reinhard => {
+            let exposed_component = component * exposure;
+            exposed_component / (1.0 + exposed_component)
         }
     }
-
-    Some((render_x, render_y))
 }
 
-fn get_average_splat_coordinates(splats: &[Splat]) -> Point3<f32> {
-    let average_splat_position: Point3<f32> = {
-        let mut total_position = Point3::new(0f32, 0f32, 0f32);
-
-        for splat in splats {
-            for (index, value) in total_position.iter_mut().enumerate() {
-                if index == 0 {
-                    *value += *splat.position.get(0).unwrap();
-                } else if index == 1 {
-                    *value += *splat.position.get(1).unwrap();
-                } else if index == 2 {
-                    *value += *splat.position.get(2).unwrap();
-                } else {
-                    panic!("BUG: Point3 should only have three components.");
-                }
-            }
-        }
+/// Tone maps the linear RGB `hdr_frame` in place and re-quantizes the RGB components of
+/// the RGBA `frame` from the tone-mapped values (leaving its alpha untouched).
+///
+/// This runs on the full-precision frame, so colors that were over-composited beyond 1.0
+/// are compressed instead of being clipped during the 8-bit conversion. Note that the
+/// full-precision frame is always blended in linear space, so (without linear blending)
+/// the resulting 8-bit colors can differ slightly from an untone-mapped render.
+fn apply_tone_mapping(
+    frame: &mut [u8],
+    hdr_frame: &mut [f32],
+    tone_mapping: ToneMapping,
+    exposure: f32,
+) {
+    if tone_mapping == ToneMapping::None {
+        return;
+    }
 
-        total_position / (splats.len() as f32)
-    };
+    frame
+        .par_chunks_exact_mut(4)
+        .zip(hdr_frame.par_chunks_exact_mut(3))
+        .for_each(|(pixel, hdr_pixel)| {
+            for (component, hdr_component) in pixel[..3].iter_mut().zip(hdr_pixel.iter_mut()) {
+                *hdr_component = get_tone_mapped_component(*hdr_component, tone_mapping, exposure);
 
-    average_splat_position
+                *component =
+                    (linear_to_srgb(hdr_component.clamp(0.0, 1.0)) * u8::MAX as f32).round() as u8;
+            }
+        });
 }
 
+/// Whether renders need the full-precision (linear, floating-point) frame: either because
+/// the screenshot format keeps more than 8 bits, or because tone mapping is enabled
+/// (see [`apply_tone_mapping`]).
+fn needs_full_precision_frame(configuration: &Configuration) -> bool {
+    configuration.screenshot.needs_full_precision_frame()
+        || configuration.rendering.tone_mapping != ToneMapping::None
+}
 
-struct SplatRendererInner {
-    pending_rerender: bool,
-
-    camera_position: Point3<f32>,
 
-    camera_look_target: Point3<f32>,
+/// Converts the (linear) full-precision `hdr_image` into a 16-bit sRGB image, taking alpha
+/// from the 8-bit `image` of the same render (which is opaque, unless alpha is preserved).
+fn get_16_bit_image(hdr_image: &Rgb32FImage, image: &RgbaImage) -> ImageBuffer<Rgba<u16>, Vec<u16>> {
+    let (frame_width, frame_height) = hdr_image.dimensions();
+    let frame_alpha = (image.dimensions() == (frame_width, frame_height)).then_some(image);
 
-    forward_vector: Vector3<f32>,
+    let to_u16 =
+        |component: f32| -> u16 { (component.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16 };
 
-    side_vector: Vector3<f32>,
+    let output_frame = hdr_image
+        .enumerate_pixels()
+        .flat_map(|(x, y, pixel)| {
+            // The full-precision frame holds linear colors, PNG expects sRGB.
+            let [red, green, blue] = pixel.0.map(|component| to_u16(linear_to_srgb(component)));
 
-    up_vector: Vector3<f32>,
+            let alpha = match frame_alpha {
+                Some(image) => u16::from(image.get_pixel(x, y).0[3]) * 257,
+                None => u16::MAX,
+            };
 
-    /// RGBA (u8 each) for each pixel.
-    frame: Vec<u8>,
-}
+            [red, green, blue, alpha]
+        })
+        .collect::<Vec<_>>();
 
-struct SplatRendererUserControlState {
-    left_mouse_pressed: bool,
-    control_key_pressed: bool,
+    // PANIC SAFETY: The output frame has four components for each pixel of the HDR image.
+    ImageBuffer::from_vec(frame_width, frame_height, output_frame)
+        .expect("BUG: 16-bit frame should match the render dimensions.")
 }
 
-pub struct SplatRenderer {
-    configuration: Configuration,
-
-    render_width: u32,
 
-    render_height: u32,
+/// Early frustum cull performed on clip-space coordinates, before the perspective divide.
+///
+/// Rejects positions behind the camera (non-positive `w`, where the divide would flip or
+/// blow up the coordinates) and positions outside the near and far clipping planes.
+#[inline]
+fn is_clip_space_position_inside_depth_frustum(clip_space_position: &Vector4<f32>) -> bool {
+    let projected_z = clip_space_position.z;
+    let projected_w = clip_space_position.w;
 
-    splat_file: Splats,
+    if !projected_z.is_finite() || !projected_w.is_finite() || projected_w <= 0.0 {
+        return false;
+    }
 
-    splat_scaling_factor: f32,
+    (-projected_w..=projected_w).contains(&projected_z)
+}
 
-    user_control: SplatRendererUserControlState,
 
-    inner: RwLock<SplatRendererInner>,
-}
+/// Projected `w` or `z` components with an absolute value smaller than this
+/// are considered degenerate (the perspective divide would produce infinities).
+const PROJECTED_COORDINATE_EPSILON: f32 = 1e-6;
 
-impl SplatRenderer {
-    #[allow(clippy::too_many_arguments)]
-    pub fn new(
-        configuration: Configuration,
-        render_width: u32,
-        render_height: u32,
-        splat_file: Splats,
-        splat_scaling_factor: Option<f32>,
-        initial_camera_position: Option<Point3<f32>>,
-        initial_camera_look_target: Option<Point3<f32>>,
-        initial_camera_up_vector: Option<Vector3<f32>>,
-    ) -> Self {
-        let splat_scaling_factor = splat_scaling_factor.unwrap_or(2.0);
-        debug!("Splat scaling factor: {}", splat_scaling_factor);
 
-        let camera_position = initial_camera_position.unwrap_or_else(|| Point3::new(3.0, 3.0, 3.0));
-        debug!("Starting camera position: {:?}", camera_position);
+/// Performs the perspective divide on clip-space coordinates and returns the
+/// normalized device `x` and `y` coordinates (visible between -1 and 1).
+///
+/// Only `w` is divided by: the projection matrix already encodes the perspective,
+/// so dividing by the normalized depth as well would distort the image
+/// (and mirror positions with a negative normalized depth across the screen center).
+///
+/// Returns `None` if the position lies behind the camera (non-positive `w`),
+/// or if the result is not finite.
+#[inline]
+fn get_normalized_device_coordinates(projected_position: Vector4<f32>) -> Option<Vector2<f32>> {
+    let projected_w = projected_position.w;
 
-        let camera_look_target = initial_camera_look_target
-            .unwrap_or_else(|| get_average_splat_coordinates(&splat_file.splats));
-        debug!(
-            "Starting camera look target: {:?}",
-            camera_look_target
-        );
+    if projected_w < PROJECTED_COORDINATE_EPSILON {
+        return None;
+    }
 
+    let projected_x = projected_position.x / projected_w;
+    let projected_y = projected_position.y / projected_w;
 
-        let initial_up_vector = initial_camera_up_vector
-            .unwrap_or_else(|| Vector3::new(0.0, 1.0, 0.0))
-            .normalize();
-        debug!(
-            "Starting camera up vector: {:?}",
-            initial_up_vector
-        );
+    if !projected_x.is_finite() || !projected_y.is_finite() {
+        return None;
+    }
 
+    Some(Vector2::new(projected_x, projected_y))
+}
 
-        let forward_vector = (camera_look_target - camera_position).normalize();
-        let side_vector = forward_vector.cross(&initial_up_vector).normalize();
-        let up_vector = side_vector.cross(&forward_vector).normalize();
+/// Returns the aspect ratio the perspective projection must use for
+/// [`get_viewport_coordinates_from_normalized_device_coordinates`] to map equal distances
+/// (in camera space) onto equal numbers of pixels horizontally and vertically.
+///
+/// The remap spans `render_width - 1` by `render_height - 1` pixels (from the center of the first
+/// pixel to the center of the last one), so using `render_width / render_height` instead
+/// would slightly stretch non-square renders.
+#[inline]
+fn get_viewport_aspect_ratio(render_width: u32, render_height: u32) -> f32 {
+    (render_width.max(2) - 1) as f32 / (render_height.max(2) - 1) as f32
+}
 
+/// Remaps normalized device coordinates (between -1 and 1) into (fractional) viewport pixel coordinates.
+#[inline]
+fn get_viewport_coordinates_from_normalized_device_coordinates(
+    normalized_device_coordinates: Vector2<f32>,
+    render_width: u32,
+    render_height: u32,
+) -> (f32, f32) {
+    (
+        ((normalized_device_coordinates.x + 1.0) / 2.0) * (render_width as f32 - 1.0),
+        ((normalized_device_coordinates.y + 1.0) / 2.0) * (render_height as f32 - 1.0),
+    )
+}
 
-        let frame = vec![0; render_width as usize * render_height as usize * 4];
+/// Where a projected splat center ended up in the viewport
+/// (see [`get_pixel_coordinates_from_projected_coordinates`]).
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ProjectedPixelCoordinates {
+    /// The position projects inside the viewport, onto the given (fractional) pixel coordinates.
+    Inside((f32, f32)),
 
-        let user_control = SplatRendererUserControlState {
-            left_mouse_pressed: false,
-            control_key_pressed: false,
-        };
+    /// The position projects outside the viewport and was clamped to the given (fractional)
+    /// coordinates on its border (only when clamping off-screen positions is enabled).
+    Clamped((f32, f32)),
 
-        let inner = RwLock::new(SplatRendererInner {
-            pending_rerender: true,
-            camera_position,
-            camera_look_target,
-            forward_vector,
-            side_vector,
-            up_vector,
-            frame,
-        });
+    /// The position projects outside the viewport (and clamping is disabled).
+    Outside,
 
+    /// The position can't be projected, e.g. because it lies behind the camera.
+    BehindCamera,
+}
 
-        Self {
-            configuration,
-            render_width,
-            render_height,
-            splat_file,
-            splat_scaling_factor,
-            user_control,
-            inner,
+impl ProjectedPixelCoordinates {
+    /// Returns the subpixel-accurate coordinates the position was projected (or clamped) onto, if any.
+    #[inline]
+    fn subpixel(self) -> Option<(f32, f32)> {
+        match self {
+            Self::Inside(subpixel) | Self::Clamped(subpixel) => Some(subpixel),
+            Self::Outside | Self::BehindCamera => None,
         }
     }
 
-    pub fn render_in_place(&self) {
-        let mut inner_locked = self.inner.write();
-
+    /// Returns the pixel the position was projected (or clamped) onto, if any.
+    #[inline]
+    fn pixel(self) -> Option<(u32, u32)> {
+        self.subpixel().map(get_nearest_pixel)
+    }
+}
 
-        // Transform the world coordinates of each splat to camera coordinates.
+/// Returns the pixel whose center is nearest to the given (non-negative) subpixel coordinates.
+#[inline]
+fn get_nearest_pixel(subpixel: (f32, f32)) -> (u32, u32) {
+    (
+        subpixel.0.round() as u32,
+        subpixel.1.round() as u32,
+    )
+}
 
-        let updated_forward_vector =
-            (inner_locked.camera_look_target - inner_locked.camera_position).normalize();
-        let updated_side_vector = updated_forward_vector
-            .cross(&inner_locked.up_vector)
-            .normalize();
-        let updated_up_vector = updated_side_vector
-            .cross(&updated_forward_vector)
-            .normalize();
+/// Projects clip-space coordinates onto the viewport, keeping their fractional (subpixel) part.
+///
+/// Positions outside the viewport are clamped to its border if `clamp_offscreen` is `true`,
+/// and are reported as [`ProjectedPixelCoordinates::Outside`] otherwise.
+#[inline]
+fn get_pixel_coordinates_from_projected_coordinates(
+    projected_position: Vector4<f32>,
+    render_width: u32,
+    render_height: u32,
+    clamp_offscreen: bool,
+) -> ProjectedPixelCoordinates {
+    let Some(mut normalized_device_coordinates) =
+        get_normalized_device_coordinates(projected_position)
+    else {
+        return ProjectedPixelCoordinates::BehindCamera;
+    };
 
-        inner_locked.forward_vector = updated_forward_vector;
-        inner_locked.side_vector = updated_side_vector;
-        inner_locked.up_vector = updated_up_vector;
+    let is_inside_viewport = (-1.0..=1.0).contains(&normalized_device_coordinates.x)
+        && (-1.0..=1.0).contains(&normalized_device_coordinates.y);
 
-        debug!(
-            "Performing render. Context:\n \
-             -> camera position: {:?}\n \
-             -> camera look target: {:?}\n \
-             -> forward vector: {:?}\n \
-             -> side vector: {:?}\n \
-             -> up vector: {:?}",
-            inner_locked.camera_position,
-            inner_locked.camera_look_target,
-            updated_forward_vector,
-            updated_side_vector,
-            updated_up_vector
-        );
+    if !is_inside_viewport {
+        if !clamp_offscreen {
+            return ProjectedPixelCoordinates::Outside;
+        }
 
+        normalized_device_coordinates.x = normalized_device_coordinates.x.clamp(-1.0, 1.0);
+        normalized_device_coordinates.y = normalized_device_coordinates.y.clamp(-1.0, 1.0);
+    }
 
-        let look_at_matrix = Matrix4::<f32>::look_at_rh(
-            &inner_locked.camera_position,
-            &inner_locked.camera_look_target,
-            &updated_up_vector,
-        );
 
-        let projection_matrix = Perspective3::<f32>::new(
-            self.render_width as f32 / self.render_height as f32,
-            // 0.6,
-            45f32,
-            0.1,
-            100.0,
-        );
+    // x and y are now guaranteed to be between -1 and 1,
+    // so the next step is to remap them into render coordinates.
+    // The `clamp` guards against any floating-point rounding pushing us outside the viewport.
+    let (viewport_x, viewport_y) = get_viewport_coordinates_from_normalized_device_coordinates(
+        normalized_device_coordinates,
+        render_width,
+        render_height,
+    );
+
+    let render_x = viewport_x.clamp(0.0, render_width.saturating_sub(1) as f32);
+    let render_y = viewport_y.clamp(0.0, render_height.saturating_sub(1) as f32);
+
+    if is_inside_viewport {
+        ProjectedPixelCoordinates::Inside((render_x, render_y))
+    } else {
+        ProjectedPixelCoordinates::Clamped((render_x, render_y))
+    }
+}
 
 
-        let joint_matrix = projection_matrix.as_matrix() * look_at_matrix;
+/// Clips a clip-space line segment to the part that lies in front of the camera,
+/// i.e. where both `w` and `z` are (comfortably) positive, so that the perspective
+/// divide is well-behaved along the entire segment.
+///
+/// Returns `None` if no part of the segment is in front of the camera.
+fn clip_segment_in_front_of_camera(
+    mut start: Vector4<f32>,
+    mut end: Vector4<f32>,
+) -> Option<(Vector4<f32>, Vector4<f32>)> {
+    // Each entry extracts the component that must be positive.
+    let plane_distance_functions: [fn(&Vector4<f32>) -> f32; 2] = [
+        |position| position.w - PROJECTED_COORDINATE_EPSILON * 16.0,
+        |position| position.z - PROJECTED_COORDINATE_EPSILON * 16.0,
+    ];
+
+    for plane_distance in plane_distance_functions {
+        let start_distance = plane_distance(&start);
+        let end_distance = plane_distance(&end);
+
+        if start_distance < 0.0 && end_distance < 0.0 {
+            return None;
+        }
 
+        if start_distance < 0.0 {
+            let t = start_distance / (start_distance - end_distance);
+            start = start.lerp(&end, t);
+        } else if end_distance < 0.0 {
+            let t = start_distance / (start_distance - end_distance);
+            end = start.lerp(&end, t);
+        }
+    }
 
-        // Project splats to camera space and order them back to front.
-        struct PreparedSplat {
-            pub distance_from_camera: f32,
-            pub center_pixel_in_viewport: (u32, u32),
-            pub billboard_size_in_pixels: u32,
+    Some((start, end))
+}
 
-            #[allow(dead_code)]
-            pub scale: Vector3<f32>,
+/// Colors of the two alternating tiles of the checkerboard backdrop
+/// (the top-left tile uses the first one).
+const CHECKER_TILE_COLORS: [[u8; 4]; 2] = [[204, 204, 204, 255], [153, 153, 153, 255]];
 
-            pub color: Vector4<u8>,
+/// Returns the color of the checkerboard backdrop at the given pixel.
+#[inline]
+fn get_checker_tile_color(pixel_x: u32, pixel_y: u32, tile_size: u32) -> [u8; 4] {
+    CHECKER_TILE_COLORS[((pixel_x / tile_size + pixel_y / tile_size) % 2) as usize]
+}
 
-            #[allow(dead_code)]
-            pub rotation: Vector4<f32>,
-        }
+/// Length (in world units) of each axis drawn by the world axes overlay.
+const WORLD_AXIS_LENGTH: f32 = 1.0;
 
+/// Largest scale the help overlay text is drawn at (it is drawn smaller if it does not fit).
+const HELP_OVERLAY_MAX_TEXT_SCALE: u32 = 2;
 
-        let time_prepare_splats_start = Instant::now();
+/// Returns the side length (in pixels, unrounded) of a square billboard at
+/// `distance_from_camera`, according to the chosen [`BillboardSizeModel`].
+///
+/// Projected (elliptical) footprints are scaled by the ratio of this size
+/// to the size of the [`BillboardSizeModel::InverseDistance`] model.
+fn billboard_size_for_distance(
+    size_model: BillboardSizeModel,
+    splat_scaling_factor: f32,
+    distance_from_camera: f32,
+    far_plane: f32,
+) -> f32 {
+    match size_model {
+        BillboardSizeModel::InverseDistance => 2.0 * splat_scaling_factor / distance_from_camera,
+        BillboardSizeModel::InverseSquareDistance => {
+            2.0 * splat_scaling_factor / (distance_from_camera * distance_from_camera)
+        }
+        BillboardSizeModel::ClampedLinear => {
+            2.0 * splat_scaling_factor * (1.0 - distance_from_camera / far_plane).clamp(0.0, 1.0)
+        }
+    }
+}
 
-        let mut prepared_splats = self
-            .splat_file
-            .splats
-            .as_slice()
-            .par_iter()
-            .filter_map(|splat| {
-                let position_in_world_space = Vector4::new(
-                    splat.position.x,
-                    splat.position.y,
-                    splat.position.z,
-                    1f32,
-                );
+/// Computes the screen-space footprint of a splat from its rotation and scale.
+///
+/// The 3D covariance of the splat (`R * S * S^T * R^T`) is rotated into camera space and then
+/// projected into pixel space using the Jacobian of the perspective projection
+/// at the splat's position (i.e. a local affine approximation of the projection).
+/// The `splat_scaling_factor` uniformly scales the resulting footprint.
+///
+/// Splats with a degenerate rotation quaternion (or an otherwise unusable covariance)
+/// fall back to an axis-aligned square of size `fallback_billboard_size_in_pixels`
+/// (covering the given `fallback_billboard_shape` inscribed in it).
+fn get_billboard_footprint(
+    splat: &Splat,
+    position_in_camera_space: &Vector3<f32>,
+    view_rotation: &Matrix3<f32>,
+    focal_lengths_in_pixels: (f32, f32),
+    splat_scaling_factor: f32,
+    fallback_billboard_size_in_pixels: u32,
+    fallback_billboard_shape: BillboardShape,
+) -> BillboardFootprint {
+    let square_footprint = BillboardFootprint::Square {
+        size_in_pixels: fallback_billboard_size_in_pixels,
+        shape: fallback_billboard_shape,
+    };
 
-                // let position_in_camera_space = look_at_matrix * position_in_world_space;
-                // let position_in_clip_space =
-                //     projection_matrix.as_matrix() * position_in_camera_space;
+    let Some(rotation_matrix) = splat.rotation_matrix() else {
+        return square_footprint;
+    };
 
-                let position_in_clip_space = joint_matrix * position_in_world_space;
+    // The camera looks down the negative z axis.
+    let depth = -position_in_camera_space.z;
+    if depth <= f32::EPSILON {
+        return square_footprint;
+    }
 
 
-                let distance_from_camera = get_splat_distance_from_camera(&position_in_clip_space);
-                let billboard_size =
-                    (2.0 * self.splat_scaling_factor / distance_from_camera).round() as u32;
+    let rotation_and_scale = rotation_matrix * Matrix3::from_diagonal(&splat.scale);
+    let covariance_in_camera_space = view_rotation
+        * rotation_and_scale
+        * rotation_and_scale.transpose()
+        * view_rotation.transpose();
 
+    let (focal_length_x, focal_length_y) = focal_lengths_in_pixels;
+    let projection_jacobian = Matrix2x3::new(
+        focal_length_x / depth,
+        0.0,
+        focal_length_x * position_in_camera_space.x / (depth * depth),
+        0.0,
+        focal_length_y / depth,
+        focal_length_y * position_in_camera_space.y / (depth * depth),
+    );
 
-                if let Some((render_center_x, render_center_y)) =
-                    get_pixel_coordinates_from_projected_coordinates(
-                        position_in_clip_space,
-                        self.render_width,
-                        self.render_height,
-                    )
-                {
-                    Some(PreparedSplat {
-                        distance_from_camera,
-                        center_pixel_in_viewport: (render_center_x, render_center_y),
-                        billboard_size_in_pixels: billboard_size,
-                        scale: splat.scale,
-                        color: splat.color,
-                        rotation: splat.rotation,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+    let covariance_in_pixels = projection_jacobian
+        * covariance_in_camera_space
+        * projection_jacobian.transpose()
+        * (splat_scaling_factor * splat_scaling_factor);
 
-        debug!(
-            "Preparing splats (projection + viewport filtering + distance calculation) took {} milliseconds.",
-            (time_prepare_splats_start.elapsed().as_secs_f64() * 1000.0).round() as u32
-        );
 
+    BillboardFootprint::from_covariance(covariance_in_pixels).unwrap_or(square_footprint)
+}
 
+/// Returns the average splat position (or the origin if there are no splats).
+fn get_average_splat_coordinates(splats: &Splats) -> Point3<f32> {
+    splats.statistics().centroid.unwrap_or_else(Point3::origin)
+}
 
-        let time_prepared_splat_sort_start = Instant::now();
 
-        prepared_splats
-            .as_mut_slice()
-            .par_sort_unstable_by(|first, second| {
-                first
-                    .distance_from_camera
-                    .total_cmp(&second.distance_from_camera)
-                    .reverse()
-            });
+/// Direction (from the centroid of the splats) along which [`get_camera_pose_fitting_splats`]
+/// places the camera. Matches the direction of the default camera position, `(3, 3, 3)`.
+const FIT_CAMERA_DIRECTION: Vector3<f32> = Vector3::new(1.0, 1.0, 1.0);
 
-        debug!(
-            "Sorting prepared splats by depth took {} milliseconds.",
-            (time_prepared_splat_sort_start.elapsed().as_secs_f64() * 1000.0).round() as u32
-        );
+/// How much further away than strictly necessary [`get_camera_pose_fitting_splats`]
+/// places the camera, so splats at the edge of the scene aren't drawn right at the border.
+const FIT_CAMERA_DISTANCE_MARGIN: f32 = 1.2;
+
+/// Returns a camera position and look target that frame all splats: the camera looks at their
+/// centroid from far enough away that the bounding sphere of all splat positions (around the centroid)
+/// fits into both the vertical and horizontal field of view.
+pub fn get_camera_pose_fitting_splats(
+    splats: &Splats,
+    vertical_field_of_view_in_degrees: f32,
+    render_width: u32,
+    render_height: u32,
+    near_plane: f32,
+) -> (Point3<f32>, Point3<f32>) {
+    let centroid = get_average_splat_coordinates(splats);
 
+    let bounding_sphere_radius = splats
+        .splats
+        .par_iter()
+        .map(|splat| (splat.position - centroid.coords).norm())
+        .reduce(|| 0.0, f32::max);
 
 
-        trace!(
-            "Distance of first ordered splat: {}",
-            prepared_splats.first().unwrap().distance_from_camera
-        );
-        trace!(
-            "Distance of last ordered splat: {}",
-            prepared_splats.last().unwrap().distance_from_camera
-        );
+    // The sphere fits into a field of view (angle) at distance `radius / sin(angle / 2)`.
+    let half_vertical_field_of_view = vertical_field_of_view_in_degrees.to_radians() / 2.0;
+    let half_horizontal_field_of_view = (half_vertical_field_of_view.tan()
+        * get_viewport_aspect_ratio(render_width, render_height))
+    .atan();
 
+    let limiting_half_field_of_view = half_vertical_field_of_view.min(half_horizontal_field_of_view);
 
+    // The nearest splat must also remain in front of the near clipping plane.
+    let camera_distance = (bounding_sphere_radius / limiting_half_field_of_view.sin()
+        * FIT_CAMERA_DISTANCE_MARGIN)
+        .max((bounding_sphere_radius + near_plane) * FIT_CAMERA_DISTANCE_MARGIN);
 
-        // Reset canvas.
-        let time_canvas_reset_start = Instant::now();
+    debug!(
+        "Fitting camera to splats: bounding sphere with center {:?} and radius {}, camera distance {}.",
+        centroid, bounding_sphere_radius, camera_distance
+    );
 
-        for pixel in inner_locked.frame.chunks_exact_mut(4) {
-            pixel.copy_from_slice(&[0, 0, 0, 255]);
-        }
 
-        debug!(
-            "Resetting the canvas took {} milliseconds.",
-            (time_canvas_reset_start.elapsed().as_secs_f64() * 1000.0).round() as u32
+    let camera_position = centroid + FIT_CAMERA_DIRECTION.normalize() * camera_distance;
+
+    (camera_position, centroid)
+}
+
+
+/// Multiplicative step by which the splat scaling factor is changed with the `[` and `]` keys.
+#[cfg(feature = "ui")]
+const SPLAT_SCALING_FACTOR_STEP: f32 = 1.1;
+
+/// Smallest allowed angle (in radians) between the orbiting camera and the world up axis.
+/// This prevents the camera from passing over the poles, where the view would flip.
+#[cfg(feature = "ui")]
+const ORBIT_MINIMUM_POLAR_ANGLE: f32 = 0.01;
+
+/// Orbits the `camera_position` around the `look_target` by the given yaw and pitch deltas
+/// (in radians), keeping the distance between them fixed.
+///
+/// Yaw rotates around the `world_up_vector`, while pitch changes the angle between the camera
+/// and the `world_up_vector` (clamped so the camera never passes over the poles).
+#[cfg(feature = "ui")]
+fn get_orbited_camera_position(
+    camera_position: Point3<f32>,
+    look_target: Point3<f32>,
+    world_up_vector: &Vector3<f32>,
+    yaw_delta: f32,
+    pitch_delta: f32,
+) -> Point3<f32> {
+    let offset_from_target = camera_position - look_target;
+    if offset_from_target.norm() <= f32::EPSILON {
+        return camera_position;
+    }
+
+    let world_up_axis = Unit::new_normalize(*world_up_vector);
+
+    let yawed_offset =
+        UnitQuaternion::from_axis_angle(&world_up_axis, yaw_delta) * offset_from_target;
+
+
+    let Some(pitch_axis) = Unit::try_new(world_up_vector.cross(&yawed_offset), f32::EPSILON) else {
+        return look_target + yawed_offset;
+    };
+
+    let current_polar_angle = yawed_offset.angle(world_up_vector);
+    let target_polar_angle = (current_polar_angle + pitch_delta).clamp(
+        ORBIT_MINIMUM_POLAR_ANGLE,
+        std::f32::consts::PI - ORBIT_MINIMUM_POLAR_ANGLE,
+    );
+
+    // Rotating around (up x offset) by a positive angle moves the offset away from the up axis.
+    let pitched_offset = UnitQuaternion::from_axis_angle(
+        &pitch_axis,
+        target_polar_angle - current_polar_angle,
+    ) * yawed_offset;
+
+
+    look_target + pitched_offset
+}
+
+
+/// The closest the camera can get to its look target by zooming with the mouse wheel.
+#[cfg(feature = "ui")]
+const MINIMUM_ZOOM_DISTANCE_FROM_TARGET: f32 = 0.05;
+
+/// Number of pixels that correspond to scrolling a single line
+/// (used for converting touchpad scroll deltas).
+#[cfg(feature = "ui")]
+const SCROLL_PIXELS_PER_LINE: f64 = 20.0;
+
+/// Moves the `camera_position` towards the `look_target` by `zoom_distance`
+/// (a negative distance moves it away from the target).
+///
+/// The camera never gets closer to the target than [`MINIMUM_ZOOM_DISTANCE_FROM_TARGET`],
+/// so zooming in can't move the camera through the target.
+#[cfg(feature = "ui")]
+fn get_zoomed_camera_position(
+    camera_position: Point3<f32>,
+    look_target: Point3<f32>,
+    zoom_distance: f32,
+) -> Point3<f32> {
+    let offset_from_target = camera_position - look_target;
+    let current_distance = offset_from_target.norm();
+
+    if current_distance <= f32::EPSILON {
+        return camera_position;
+    }
+
+    let target_distance = (current_distance - zoom_distance).max(MINIMUM_ZOOM_DISTANCE_FROM_TARGET);
+
+    look_target + offset_from_target * (target_distance / current_distance)
+}
+
+
+/// Angle (in degrees) by which the camera is rolled with the `,` and `.` keys.
+#[cfg(feature = "ui")]
+const CAMERA_ROLL_STEP_IN_DEGREES: f32 = 5.0;
+
+/// Angle (in degrees) by which the vertical field of view is changed with the `=` and `-` keys.
+#[cfg(feature = "ui")]
+const FIELD_OF_VIEW_STEP_IN_DEGREES: f32 = 5.0;
+
+/// Smallest vertical field of view (in degrees) that can be set with the keyboard.
+#[cfg(feature = "ui")]
+const MINIMUM_FIELD_OF_VIEW_IN_DEGREES: f32 = 10.0;
+
+/// Largest vertical field of view (in degrees) that can be set with the keyboard.
+#[cfg(feature = "ui")]
+const MAXIMUM_FIELD_OF_VIEW_IN_DEGREES: f32 = 120.0;
+
+/// Clamps the vertical field of view (in degrees) to the range that can be set with the keyboard.
+#[cfg(feature = "ui")]
+fn get_clamped_field_of_view(vertical_field_of_view_in_degrees: f32) -> f32 {
+    vertical_field_of_view_in_degrees.clamp(
+        MINIMUM_FIELD_OF_VIEW_IN_DEGREES,
+        MAXIMUM_FIELD_OF_VIEW_IN_DEGREES,
+    )
+}
+
+/// Rotates the `up_vector` around the (unit) `forward_vector` by `roll_angle` (in radians).
+/// Positive angles roll the camera clockwise (as seen by the viewer), i.e. to the right.
+///
+/// The rotated up vector stays orthogonal to the forward vector if it was orthogonal before.
+#[cfg(feature = "ui")]
+fn get_rolled_up_vector(
+    forward_vector: &Vector3<f32>,
+    up_vector: &Vector3<f32>,
+    roll_angle: f32,
+) -> Vector3<f32> {
+    let forward_axis = Unit::new_normalize(*forward_vector);
+
+    UnitQuaternion::from_axis_angle(&forward_axis, roll_angle) * up_vector
+}
+
+
+/// How long the camera must remain unchanged before a reduced-quality frame
+/// (drawn because of the frame budget) is replaced with a full render.
+const CAMERA_SETTLE_DELAY: Duration = Duration::from_millis(250);
+
+/// Names and directions (from the look target towards the camera) of the axis-aligned views
+/// selected with [`KeyAction::SnapToAxisAlignedView`]: `+X`, `-X`, `+Y`, `-Y`, `+Z` and `-Z`.
+#[cfg(feature = "ui")]
+const AXIS_ALIGNED_VIEWS: [(&str, Vector3<f32>); 6] = [
+    ("+X", Vector3::new(1.0, 0.0, 0.0)),
+    ("-X", Vector3::new(-1.0, 0.0, 0.0)),
+    ("+Y", Vector3::new(0.0, 1.0, 0.0)),
+    ("-Y", Vector3::new(0.0, -1.0, 0.0)),
+    ("+Z", Vector3::new(0.0, 0.0, 1.0)),
+    ("-Z", Vector3::new(0.0, 0.0, -1.0)),
+];
+
+/// Returns the camera position and up vector for looking at `look_target` from the (unit)
+/// `view_direction`, keeping the camera's current distance from the target.
+///
+/// The up vector is `world_up_vector`, unless it is (nearly) parallel to the view direction
+/// (e.g. for top-down views), in which case another world axis is used.
+#[cfg(feature = "ui")]
+fn get_axis_aligned_camera_pose(
+    camera_position: Point3<f32>,
+    look_target: Point3<f32>,
+    view_direction: &Vector3<f32>,
+    world_up_vector: &Vector3<f32>,
+) -> (Point3<f32>, Vector3<f32>) {
+    let distance_from_target = (camera_position - look_target)
+        .norm()
+        .max(MINIMUM_ZOOM_DISTANCE_FROM_TARGET);
+
+    let up_vector = if world_up_vector.dot(view_direction).abs() < 0.999 {
+        *world_up_vector
+    } else if view_direction.z.abs() < 0.999 {
+        Vector3::new(0.0, 0.0, -view_direction.y.signum())
+    } else {
+        Vector3::new(0.0, 1.0, 0.0)
+    };
+
+    (
+        look_target + view_direction * distance_from_target,
+        up_vector,
+    )
+}
+
+
+
+/// Minimum length of the cross product of the (unit) forward and up vectors
+/// (i.e. the sine of the angle between them) for the up vector to be usable.
+const MINIMUM_UP_VECTOR_CROSS_PRODUCT_LENGTH: f32 = 1e-4;
+
+/// Returns the side and up vectors that, together with the (unit) `forward_vector`,
+/// form the orthonormal basis of the camera, with the up vector as close to `up_vector_hint` as possible.
+///
+/// If the up vector hint is (nearly) parallel to the forward vector (e.g. when looking straight down
+/// with a vertical up vector), or is not a valid direction at all, the world axis that is
+/// the least aligned with the forward vector is used instead (and a warning is emitted),
+/// since the cross products would otherwise produce NaNs.
+fn get_camera_side_and_up_vectors(
+    forward_vector: &Vector3<f32>,
+    up_vector_hint: &Vector3<f32>,
+) -> (Vector3<f32>, Vector3<f32>) {
+    let mut side_vector = forward_vector.cross(&up_vector_hint.normalize());
+
+    let side_vector_length = side_vector.norm();
+    if side_vector_length.is_nan() || side_vector_length < MINIMUM_UP_VECTOR_CROSS_PRODUCT_LENGTH {
+        let least_aligned_axis_index = forward_vector.iamin();
+
+        let mut fallback_up_vector = Vector3::zeros();
+        fallback_up_vector[least_aligned_axis_index] = 1.0;
+
+        warn!(
+            "Camera up vector {:?} is (nearly) parallel to the forward vector {:?}, \
+             falling back to {:?}.",
+            up_vector_hint, forward_vector, fallback_up_vector
         );
 
+        side_vector = forward_vector.cross(&fallback_up_vector);
+    }
 
-        // Splats have been prepared and ordered back to front, render them.
-        let time_compositing_start = Instant::now();
+    let side_vector = side_vector.normalize();
+    let up_vector = side_vector.cross(forward_vector).normalize();
 
-        for prepared_splat in prepared_splats {
-            let billboard_pixel_iterator = BillboardCoordinatesIterator::from_center_and_size(
-                (self.render_width, self.render_height),
-                prepared_splat.center_pixel_in_viewport,
-                prepared_splat.billboard_size_in_pixels,
-            )
-            .fuse();
-
-            for pixel in billboard_pixel_iterator {
-                let pixel_index = ((pixel.y * self.render_width + pixel.x) * 4) as usize;
-
-                if pixel_index > (inner_locked.frame.len() - 1) {
-                    panic!(
-                        "Invalid pixel_index: got {} (from x={}, y={}), but array length is {}.\n\
-                        Context: render center ({},{}), billboard width {}",
-                        pixel_index,
-                        pixel.x,
-                        pixel.y,
-                        inner_locked.frame.len(),
-                        prepared_splat.center_pixel_in_viewport.0,
-                        prepared_splat.center_pixel_in_viewport.1,
-                        prepared_splat.billboard_size_in_pixels
-                    );
-                }
+    (side_vector, up_vector)
+}
 
-                let existing_pixel_r = inner_locked.frame[pixel_index];
-                let existing_pixel_g = inner_locked.frame[pixel_index + 1];
-                let existing_pixel_b = inner_locked.frame[pixel_index + 2];
 
+/// A splat that has been projected onto the screen and is ready to be composited.
+pub(crate) struct PreparedSplat {
+    /// Index of the splat in the original splat list
+    /// (used to break ties between splats at the same distance from the camera).
+    splat_index: usize,
 
-                let existing_rgb = Vector3::new(
-                    (existing_pixel_r as f32) / (u8::MAX as f32),
-                    (existing_pixel_g as f32) / (u8::MAX as f32),
-                    (existing_pixel_b as f32) / (u8::MAX as f32),
-                );
-                let splat_rgb = Vector3::new(
-                    (prepared_splat.color.x as f32) / (u8::MAX as f32),
-                    (prepared_splat.color.y as f32) / (u8::MAX as f32),
-                    (prepared_splat.color.z as f32) / (u8::MAX as f32),
-                );
+    distance_from_camera: f32,
 
+    /// Subpixel-accurate center of the billboard. Keeping the fractional part lets the Gaussian
+    /// falloff weight the edge pixels by their exact distance, so billboards don't snap
+    /// to whole pixels as the camera moves.
+    center_pixel_in_viewport: (f32, f32),
 
-                let splat_alpha = (prepared_splat.color.w as f32) / (u8::MAX as f32);
-                let splat_inverted_alpha = 1.0 - splat_alpha;
+    footprint: BillboardFootprint,
 
-                let final_rgb_f32 = splat_inverted_alpha * existing_rgb + splat_alpha * splat_rgb;
+    /// Color of the splat (its alpha component is superseded by `opacity`).
+    color: Vector4<u8>,
 
-                let final_rgb_u8 = [
-                    (final_rgb_f32.x * (u8::MAX as f32)).round() as u8,
-                    (final_rgb_f32.y * (u8::MAX as f32)).round() as u8,
-                    (final_rgb_f32.z * (u8::MAX as f32)).round() as u8,
-                ];
+    /// Opacity of the splat, in `[0, 1]` (see [`Splat::opacity`]).
+    opacity: f32,
+}
 
-                inner_locked.frame[pixel_index..pixel_index + 3].copy_from_slice(&final_rgb_u8);
-            }
+/// Compares two prepared splats by the order they should be composited in
+/// (`Less` means `first` is drawn before `second`), according to the `sort_key`.
+///
+/// Splats that compare equal by the sort key are ordered by their original index,
+/// so their relative order (and therefore the composited result) does not change between frames.
+fn compare_prepared_splats(
+    first: &PreparedSplat,
+    second: &PreparedSplat,
+    sort_key: SortKey,
+) -> std::cmp::Ordering {
+    let back_to_front_ordering = first
+        .distance_from_camera
+        .total_cmp(&second.distance_from_camera)
+        .reverse();
+
+    let sort_key_ordering = match sort_key {
+        SortKey::Depth => back_to_front_ordering,
+        SortKey::Alpha => first
+            .opacity
+            .total_cmp(&second.opacity)
+            .then(back_to_front_ordering),
+        SortKey::Size => {
+            let get_footprint_area = |prepared_splat: &PreparedSplat| {
+                let (half_width, half_height) = prepared_splat.footprint.half_extents();
+                half_width as u64 * half_height as u64
+            };
+
+            get_footprint_area(first)
+                .cmp(&get_footprint_area(second))
+                .reverse()
+                .then(back_to_front_ordering)
         }
+    };
 
-        debug!(
-            "Compositing the splats took {} milliseconds.",
-            (time_compositing_start.elapsed().as_secs_f64() * 1000.0).round() as u32
+    sort_key_ordering.then(first.splat_index.cmp(&second.splat_index))
+}
+
+
+/// Height (in rows) of the horizontal frame tiles that are composited in parallel.
+const COMPOSITING_TILE_HEIGHT_IN_ROWS: u32 = 16;
+
+/// A horizontal band of the frame that is composited independently of all other tiles.
+/// Each slice covers only the rows of this tile.
+struct FrameTile<'frame> {
+    rows: Range<u32>,
+
+    /// RGBA (u8 each) for each pixel in the tile.
+    frame: &'frame mut [u8],
+
+    /// Depth value for each pixel in the tile.
+    depth_buffer: &'frame mut [f32],
+
+    /// Linear RGB (f32 each) for each pixel in the tile, if a full-precision output is enabled.
+    hdr_frame: Option<&'frame mut [f32]>,
+}
+
+/// Parameters shared by all tiles when compositing.
+struct CompositingParameters {
+    viewport_size: (u32, u32),
+    render_mode: RenderMode,
+    falloff_sigma: f32,
+    depth_test_enabled: bool,
+
+    /// If `true`, colors are converted from sRGB to linear before blending (and back afterwards).
+    linear_blending: bool,
+
+    /// If `true`, splat colors are already linear and are not converted before (linear) blending.
+    linear_input_colors: bool,
+
+    /// Whether splat colors are premultiplied by their alpha.
+    alpha_mode: AlphaMode,
+}
+
+/// Composites `prepared_splats` (ordered back to front) onto a single frame tile.
+/// Only the splats at `splat_indices` (which must also be in back-to-front order) are considered.
+fn composite_splats_onto_tile(
+    tile: FrameTile,
+    prepared_splats: &[PreparedSplat],
+    splat_indices: &[u32],
+    parameters: &CompositingParameters,
+) {
+    let FrameTile {
+        rows,
+        frame,
+        depth_buffer,
+        mut hdr_frame,
+    } = tile;
+
+    let (render_width, _) = parameters.viewport_size;
+    let falloff_sigma = parameters.falloff_sigma;
+
+    if parameters.render_mode == RenderMode::Points {
+        composite_splat_points_onto_tile(
+            FrameTile {
+                rows,
+                frame,
+                depth_buffer,
+                hdr_frame,
+            },
+            prepared_splats,
+            splat_indices,
+            parameters,
         );
 
-        inner_locked.pending_rerender = false;
+        return;
     }
 
-    pub fn save_screenshot_to_disk(&self) {
-        let screenshot_time_string = Local::now().format("%Y-%m-%d_%H-%M-%S-%3f");
-        let screenshot_name = format!("nrg-screenshot_{}.png", screenshot_time_string);
+    for prepared_splat in splat_indices
+        .iter()
+        .map(|splat_index| &prepared_splats[*splat_index as usize])
+    {
+        // The falloff is cut off after a few standard deviations,
+        // so we only need to visit pixels up to that distance.
+        let falloff_footprint = prepared_splat
+            .footprint
+            .scaled(GAUSSIAN_FALLOFF_CUTOFF_IN_SIGMAS * falloff_sigma);
+
+        let billboard_pixel_iterator = BillboardCoordinatesIterator::from_center_and_footprint(
+            parameters.viewport_size,
+            prepared_splat.center_pixel_in_viewport,
+            falloff_footprint,
+        )
+        .restricted_to_rows(rows.clone())
+        .fuse();
+
+        let mut splat_rgb = Vector3::new(
+            (prepared_splat.color.x as f32) / (u8::MAX as f32),
+            (prepared_splat.color.y as f32) / (u8::MAX as f32),
+            (prepared_splat.color.z as f32) / (u8::MAX as f32),
+        );
 
-        let full_screenshot_path = self
-            .configuration
-            .screenshot
-            .screenshot_path(&screenshot_name);
+        // The full-precision frame always holds linear colors, regardless of the blending space.
+        let linear_splat_rgb = if parameters.linear_input_colors {
+            splat_rgb
+        } else {
+            splat_rgb.map(srgb_to_linear)
+        };
 
+        if parameters.linear_blending {
+            splat_rgb = linear_splat_rgb;
+        }
 
-        let buffer_as_image = {
-            let inner_locked = self.inner.read();
+        for pixel in billboard_pixel_iterator {
+            let pixel_offset = Vector2::new(
+                pixel.x as f32 - prepared_splat.center_pixel_in_viewport.0,
+                pixel.y as f32 - prepared_splat.center_pixel_in_viewport.1,
+            );
 
+            let falloff = get_gaussian_falloff(
+                pixel_offset,
+                &prepared_splat.footprint,
+                falloff_sigma,
+            );
+            if falloff <= 0.0 {
+                continue;
+            }
 
-            let opaque_frame = {
-                let mut cloned_frame = inner_locked.frame.clone();
+            // Index of the pixel relative to the start of this tile.
+            let pixel_index_in_tile = ((pixel.y - rows.start) * render_width + pixel.x) as usize;
+
+            if parameters.depth_test_enabled
+                && prepared_splat.distance_from_camera > depth_buffer[pixel_index_in_tile]
+            {
+                continue;
+            }
+
+            // The falloff makes the edges of even a fully opaque splat translucent,
+            // so only the pixels the splat (nearly) fully covers write to the depth buffer
+            // (all splats are tested against it, though).
+            let splat_alpha = prepared_splat.opacity * falloff;
+
+            if parameters.depth_test_enabled && splat_alpha >= DEPTH_WRITE_ALPHA_THRESHOLD {
+                depth_buffer[pixel_index_in_tile] = prepared_splat.distance_from_camera;
+            }
+
+            let pixel_index = pixel_index_in_tile * 4;
+
+            let existing_pixel_r = frame[pixel_index];
+            let existing_pixel_g = frame[pixel_index + 1];
+            let existing_pixel_b = frame[pixel_index + 2];
+            let existing_pixel_a = frame[pixel_index + 3];
+
+
+            let mut existing_rgb = Vector3::new(
+                (existing_pixel_r as f32) / (u8::MAX as f32),
+                (existing_pixel_g as f32) / (u8::MAX as f32),
+                (existing_pixel_b as f32) / (u8::MAX as f32),
+            );
+            let existing_alpha = (existing_pixel_a as f32) / (u8::MAX as f32);
+
+            if parameters.linear_blending {
+                existing_rgb.apply(|component| *component = srgb_to_linear(*component));
+            }
 
-                for pixel in cloned_frame.chunks_exact_mut(4) {
-                    pixel[3] = 255;
-                }
 
-                cloned_frame
+            // The frame stores straight (non-premultiplied) colors along with the accumulated
+            // alpha, so we composite the splat over it with the "over" operator.
+            // For an opaque frame this reduces to the usual `(1 - a) * existing + a * splat`.
+            let splat_inverted_alpha = 1.0 - splat_alpha;
+
+            // Premultiplied colors already include the splat's alpha (but not the falloff),
+            // i.e. `(1 - a) * existing + splat` for an opaque frame.
+            let splat_color_weight = match parameters.alpha_mode {
+                AlphaMode::Straight => splat_alpha,
+                AlphaMode::Premultiplied => falloff,
             };
 
-            let Some(image) = RgbaImage::from_vec(
-                self.render_width,
-                self.render_height,
-                opaque_frame,
-            ) else {
-                error!("Failed to save screenshot: buffer is not big enough.");
-                return;
+            let existing_weight = existing_alpha * splat_inverted_alpha;
+            let final_alpha = splat_alpha + existing_weight;
+
+            let mut final_rgb_f32 = if final_alpha > 0.0 {
+                (existing_weight * existing_rgb + splat_color_weight * splat_rgb) / final_alpha
+            } else {
+                existing_rgb
             };
 
-            image
-        };
+            if parameters.linear_blending {
+                final_rgb_f32.apply(|component| *component = linear_to_srgb(*component));
+            }
 
+            let final_rgba_u8 = [
+                (final_rgb_f32.x * (u8::MAX as f32)).round() as u8,
+                (final_rgb_f32.y * (u8::MAX as f32)).round() as u8,
+                (final_rgb_f32.z * (u8::MAX as f32)).round() as u8,
+                (final_alpha * (u8::MAX as f32)).round() as u8,
+            ];
 
-        let save_result = buffer_as_image.save_with_format(full_screenshot_path, ImageFormat::Png);
+            frame[pixel_index..pixel_index + 4].copy_from_slice(&final_rgba_u8);
 
-        if let Err(save_error) = save_result {
-            error!(
-                "Failed to save screenshot: erorred while saving as PNG: {:?}",
-                save_error
-            );
-        }
 
-        info!("Screenshot saved to disk as {}.", screenshot_name);
+            // When a full-precision output (floating-point or 16-bit) is configured, also composite
+            // in full precision (on top of the unrounded previous values, always in linear
+            // color space, even if the 8-bit frame is blended in sRGB space).
+            if let Some(hdr_frame) = hdr_frame.as_mut() {
+                let hdr_pixel_index = pixel_index_in_tile * 3;
+                let hdr_pixel = &mut hdr_frame[hdr_pixel_index..hdr_pixel_index + 3];
+
+                for (component, splat_component) in hdr_pixel.iter_mut().zip(linear_splat_rgb.iter())
+                {
+                    if final_alpha > 0.0 {
+                        *component = (existing_weight * *component
+                            + splat_color_weight * splat_component)
+                            / final_alpha;
+                    }
+                }
+            }
+        }
     }
 }
 
-impl PixelSurfaceRenderer for SplatRenderer {
-    fn draw(&self, frame: &mut [u8]) {
-        let inner_locked_read_only = self.inner.read();
+/// Draws each of the `prepared_splats` at `splat_indices` onto a single frame tile
+/// as one opaque pixel at its projected center (see [`RenderMode::Points`]).
+/// The splat alpha, billboard footprint and blending settings are ignored.
+fn composite_splat_points_onto_tile(
+    tile: FrameTile,
+    prepared_splats: &[PreparedSplat],
+    splat_indices: &[u32],
+    parameters: &CompositingParameters,
+) {
+    let FrameTile {
+        rows,
+        frame,
+        depth_buffer,
+        mut hdr_frame,
+    } = tile;
+
+    let (render_width, _) = parameters.viewport_size;
+
+    for prepared_splat in splat_indices
+        .iter()
+        .map(|splat_index| &prepared_splats[*splat_index as usize])
+    {
+        let (center_x, center_y) = get_nearest_pixel(prepared_splat.center_pixel_in_viewport);
+        if !rows.contains(&center_y) {
+            continue;
+        }
 
-        if inner_locked_read_only.pending_rerender {
-            drop(inner_locked_read_only);
+        let pixel_index_in_tile = ((center_y - rows.start) * render_width + center_x) as usize;
 
-            debug!("Resolving pending rerender.");
-            let time_render_start = Instant::now();
-            self.render_in_place();
-            debug!(
-                "Rerender took {} milliseconds.",
-                (time_render_start.elapsed().as_secs_f64() * 1000.0).round() as u32
-            );
+        if parameters.depth_test_enabled {
+            if prepared_splat.distance_from_camera > depth_buffer[pixel_index_in_tile] {
+                continue;
+            }
 
-            let inner_locked_read_only = self.inner.read();
-            frame.copy_from_slice(&inner_locked_read_only.frame);
-        } else {
-            frame.copy_from_slice(&inner_locked_read_only.frame);
+            depth_buffer[pixel_index_in_tile] = prepared_splat.distance_from_camera;
         }
 
-        // for (pixel_index, pixel) in frame.chunks_exact_mut(4).enumerate() {
-        //      pixel.copy_from_slice(&[133, 255, 211, 255]);
-        // }
+        let splat_rgb = Vector3::new(
+            (prepared_splat.color.x as f32) / (u8::MAX as f32),
+            (prepared_splat.color.y as f32) / (u8::MAX as f32),
+            (prepared_splat.color.z as f32) / (u8::MAX as f32),
+        );
+
+        // The 8-bit frame holds sRGB-encoded colors, like in the billboard render modes.
+        let pixel_index = pixel_index_in_tile * 4;
+        let srgb_rgba_u8 = if parameters.linear_input_colors {
+            let srgb_rgb = splat_rgb.map(linear_to_srgb);
+
+            [
+                (srgb_rgb.x * (u8::MAX as f32)).round() as u8,
+                (srgb_rgb.y * (u8::MAX as f32)).round() as u8,
+                (srgb_rgb.z * (u8::MAX as f32)).round() as u8,
+                u8::MAX,
+            ]
+        } else {
+            [
+                prepared_splat.color.x,
+                prepared_splat.color.y,
+                prepared_splat.color.z,
+                u8::MAX,
+            ]
+        };
+        frame[pixel_index..pixel_index + 4].copy_from_slice(&srgb_rgba_u8);
+
+        // The full-precision frame always holds linear colors.
+        if let Some(hdr_frame) = hdr_frame.as_mut() {
+            let linear_splat_rgb = if parameters.linear_input_colors {
+                splat_rgb
+            } else {
+                splat_rgb.map(srgb_to_linear)
+            };
+
+            let hdr_pixel_index = pixel_index_in_tile * 3;
+            hdr_frame[hdr_pixel_index..hdr_pixel_index + 3]
+                .copy_from_slice(linear_splat_rgb.as_slice());
+        }
     }
 }
 
-#[cfg(feature = "ui")]
-impl InteractiveRenderer for SplatRenderer {
-    fn handle_window_event(&mut self, window_event: &WindowEvent) -> Result<()> {
-        const MOVE_CAMERA_BY: f32 = 0.1;
+/// Returns whether compositing the `prepared_splat` (with the given render mode and falloff)
+/// draws onto the `pixel` of a canvas of `viewport_size`, i.e. whether the pixel lies within
+/// the same billboard extents as in [`composite_splats_onto_tile`]
+/// (or is the center pixel, in [`RenderMode::Points`]).
+fn does_prepared_splat_cover_pixel(
+    prepared_splat: &PreparedSplat,
+    pixel: (u32, u32),
+    viewport_size: (u32, u32),
+    render_mode: RenderMode,
+    falloff_sigma: f32,
+) -> bool {
+    if render_mode == RenderMode::Points {
+        return get_nearest_pixel(prepared_splat.center_pixel_in_viewport) == pixel;
+    }
 
-        match window_event {
-            WindowEvent::KeyboardInput { event, .. } => {
-                if let Key::Named(named_key) = &event.logical_key {
-                    if named_key == &NamedKey::Control {
-                        match event.state {
-                            ElementState::Pressed => {
-                                info!("User is holding down Ctrl key.");
-                                self.user_control.control_key_pressed = true;
-                            }
-                            ElementState::Released => {
-                                info!("User released Ctrl key.");
-                                self.user_control.control_key_pressed = false;
-                            }
-                        }
-                    }
-                };
+    let (pixel_x, pixel_y) = pixel;
 
+    let falloff_footprint = prepared_splat
+        .footprint
+        .scaled(GAUSSIAN_FALLOFF_CUTOFF_IN_SIGMAS * falloff_sigma);
 
-                let Key::Character(input_key) = &event.logical_key else {
-                    return Ok(());
-                };
+    let is_inside_footprint = BillboardCoordinatesIterator::from_center_and_footprint(
+        viewport_size,
+        prepared_splat.center_pixel_in_viewport,
+        falloff_footprint,
+    )
+    .restricted_to_rows(pixel_y..pixel_y + 1)
+    .any(|footprint_pixel| footprint_pixel.x == pixel_x);
 
-                if event.state != ElementState::Released {
-                    return Ok(());
-                }
+    if !is_inside_footprint {
+        return false;
+    }
 
+    let pixel_offset = Vector2::new(
+        pixel_x as f32 - prepared_splat.center_pixel_in_viewport.0,
+        pixel_y as f32 - prepared_splat.center_pixel_in_viewport.1,
+    );
 
-                // Check for Ctrl+S (screenshot shortcut).
-                if input_key == "s" && self.user_control.control_key_pressed {
-                    info!("User pressed \"Ctrl+s\", saving screenhot.");
-                    self.save_screenshot_to_disk();
-                    return Ok(());
-                }
+    get_gaussian_falloff(
+        pixel_offset,
+        &prepared_splat.footprint,
+        falloff_sigma,
+    ) > 0.0
+}
 
 
-                if !self.user_control.left_mouse_pressed && self.user_control.control_key_pressed {
-                    return Ok(());
-                }
+/// A world-space half-space `normal · p + offset >= 0`. Splats outside it are culled
+/// (see [`SplatRenderer::set_clip_planes`]).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ClipPlane {
+    pub normal: Vector3<f32>,
 
+    pub offset: f32,
+}
 
-                let mut inner_locked = self.inner.write();
+impl ClipPlane {
+    pub fn new(normal: Vector3<f32>, offset: f32) -> Self {
+        Self { normal, offset }
+    }
+
+    /// Returns whether `position` lies on the kept (non-negative) side of the plane.
+    #[inline]
+    pub fn contains(&self, position: &Vector3<f32>) -> bool {
+        self.normal.dot(position) + self.offset >= 0.0
+    }
+}
+
+
+/// Camera-dependent state needed to project individual splats
+/// (see [`SplatRenderer::create_projection_context`]).
+struct SplatProjectionContext {
+    look_at_matrix: Matrix4<f32>,
 
+    /// Projection matrix multiplied by the view (look-at) matrix.
+    joint_matrix: Matrix4<f32>,
 
-                if input_key == "s" {
-                    info!(
-                        "User pressed \"s\", moving camera x backwards by {}.",
-                        MOVE_CAMERA_BY
-                    );
+    /// Rotational part of the view matrix.
+    view_rotation: Matrix3<f32>,
 
-                    inner_locked.camera_position.x -= MOVE_CAMERA_BY;
-                    // inner_locked.camera_look_target.x -= MOVE_CAMERA_BY;
+    focal_lengths_in_pixels: (f32, f32),
 
-                    inner_locked.pending_rerender = true;
-                } else if input_key == "w" {
-                    info!(
-                        "User pressed \"w\", moving camera x forwards by {}.",
-                        MOVE_CAMERA_BY
-                    );
+    /// Size of the (possibly supersampled) canvas the splats are projected onto.
+    canvas_size: (u32, u32),
 
-                    inner_locked.camera_position.x += MOVE_CAMERA_BY;
-                    // inner_locked.camera_look_target.x += MOVE_CAMERA_BY;
+    splat_scaling_factor: f32,
+
+    /// Distance of the far clipping plane from the camera.
+    far_plane: f32,
 
-                    inner_locked.pending_rerender = true;
-                } else if input_key == "d" {
-                    info!(
-                        "User pressed \"d\", moving camera y backwards by {}.",
-                        MOVE_CAMERA_BY
-                    );
+    /// Splats outside any of these half-spaces are culled.
+    clip_planes: Vec<ClipPlane>,
 
-                    inner_locked.camera_position.y -= MOVE_CAMERA_BY;
-                    // inner_locked.camera_look_target.y -= MOVE_CAMERA_BY;
+    camera_position: Point3<f32>,
+}
 
-                    inner_locked.pending_rerender = true;
-                } else if input_key == "e" {
-                    info!(
-                        "User pressed \"e\", moving camera y forwards by {}.",
-                        MOVE_CAMERA_BY
-                    );
+/// Result of projecting a single splat (see [`SplatRenderer::project_splat`]).
+struct SplatProjection {
+    position_in_clip_space: Vector4<f32>,
 
-                    inner_locked.camera_position.y += MOVE_CAMERA_BY;
-                    // inner_locked.camera_look_target.y += MOVE_CAMERA_BY;
+    distance_from_camera: f32,
 
-                    inner_locked.pending_rerender = true;
-                } else if input_key == "f" {
-                    info!(
-                        "User pressed \"f\", moving camera z backwards by {}.",
-                        MOVE_CAMERA_BY
-                    );
+    /// Whether the splat lies between the near and far clipping planes.
+    is_inside_depth_frustum: bool,
 
-                    inner_locked.camera_position.z -= MOVE_CAMERA_BY;
-                    // inner_locked.camera_look_target.z -= MOVE_CAMERA_BY;
+    /// Whether the splat lies inside all world-space clip planes.
+    is_inside_clip_planes: bool,
 
-                    inner_locked.pending_rerender = true;
-                } else if input_key == "r" {
-                    info!(
-                        "User pressed \"r\", moving camera z forwards by {}.",
-                        MOVE_CAMERA_BY
-                    );
+    pixel_coordinates: ProjectedPixelCoordinates,
 
-                    inner_locked.camera_position.z += MOVE_CAMERA_BY;
-                    // inner_locked.camera_look_target.z += MOVE_CAMERA_BY;
+    /// `None` if the splat was culled.
+    prepared_splat: Option<PreparedSplat>,
+}
 
-                    inner_locked.pending_rerender = true;
-                } else if input_key == "t" {
-                    info!("User pressed \"t\", zooming outwards.");
 
-                    let camera_position_movement =
-                        (inner_locked.camera_look_target - inner_locked.camera_position).normalize()
-                            * MOVE_CAMERA_BY;
+/// Buffers the splats are composited into when supersampling is enabled.
+/// Both are `ssaa_factor` times larger than the output frame in each dimension.
+struct SupersampledBuffers {
+    /// RGBA (u8 each) for each supersampled pixel.
+    frame: Vec<u8>,
 
-                    inner_locked.camera_position -= camera_position_movement;
+    /// Linear RGB (f32 each) for each supersampled pixel, if a floating-point
+    /// screenshot format is configured.
+    hdr_frame: Option<Vec<f32>>,
+}
 
-                    inner_locked.pending_rerender = true;
-                } else if input_key == "g" {
-                    info!("User pressed \"g\", zooming inwards.");
+/// Box-downsamples the RGBA `supersampled_frame` (`ssaa_factor` times larger in each dimension)
+/// into `frame`, which is `frame_width` pixels wide.
+///
+/// Since the frames store straight (non-premultiplied) alpha, colors are weighted by their alpha.
+fn downsample_supersampled_frame(
+    supersampled_frame: &[u8],
+    frame: &mut [u8],
+    frame_width: u32,
+    ssaa_factor: u32,
+) {
+    let frame_width = frame_width as usize;
+    let ssaa_factor = ssaa_factor as usize;
+    let supersampled_width = frame_width * ssaa_factor;
+    let samples_per_pixel = (ssaa_factor * ssaa_factor) as f32;
+
+    frame
+        .par_chunks_mut(frame_width * 4)
+        .enumerate()
+        .for_each(|(y, frame_row)| {
+            for (x, pixel) in frame_row.chunks_exact_mut(4).enumerate() {
+                let mut weighted_color_sum = [0f32; 3];
+                let mut color_sum = [0f32; 3];
+                let mut alpha_sum = 0f32;
+
+                for sample_y in y * ssaa_factor..(y + 1) * ssaa_factor {
+                    let row_start = sample_y * supersampled_width;
+
+                    for sample_x in x * ssaa_factor..(x + 1) * ssaa_factor {
+                        let sample_index = (row_start + sample_x) * 4;
+                        let sample = &supersampled_frame[sample_index..sample_index + 4];
+                        let sample_alpha = sample[3] as f32;
+
+                        for component_index in 0..3 {
+                            weighted_color_sum[component_index] +=
+                                sample[component_index] as f32 * sample_alpha;
+                            color_sum[component_index] += sample[component_index] as f32;
+                        }
 
-                    let camera_position_movement =
-                        (inner_locked.camera_look_target - inner_locked.camera_position).normalize()
-                            * MOVE_CAMERA_BY;
+                        alpha_sum += sample_alpha;
+                    }
+                }
 
-                    inner_locked.camera_position += camera_position_movement;
+                for component_index in 0..3 {
+                    let component = if alpha_sum > 0.0 {
+                        weighted_color_sum[component_index] / alpha_sum
+                    } else {
+                        color_sum[component_index] / samples_per_pixel
+                    };
 
-                    inner_locked.pending_rerender = true;
+                    pixel[component_index] = component.round() as u8;
                 }
 
-                drop(inner_locked);
+                pixel[3] = (alpha_sum / samples_per_pixel).round() as u8;
             }
-            WindowEvent::MouseInput { state, button, .. } => {
-                if matches!(button, MouseButton::Left) {
-                    match state {
-                        ElementState::Pressed => {
-                            info!("Left mouse button pressed.");
-
-                            self.user_control.left_mouse_pressed = true;
-                        }
-                        ElementState::Released => {
-                            info!("Left mouse button released.");
+        });
+}
 
-                            self.user_control.left_mouse_pressed = false;
+/// Box-downsamples the RGB `supersampled_hdr_frame` (`ssaa_factor` times larger in each dimension)
+/// into `hdr_frame`, which is `frame_width` pixels wide.
+fn downsample_supersampled_hdr_frame(
+    supersampled_hdr_frame: &[f32],
+    hdr_frame: &mut [f32],
+    frame_width: u32,
+    ssaa_factor: u32,
+) {
+    let frame_width = frame_width as usize;
+    let ssaa_factor = ssaa_factor as usize;
+    let supersampled_width = frame_width * ssaa_factor;
+    let samples_per_pixel = (ssaa_factor * ssaa_factor) as f32;
+
+    hdr_frame
+        .par_chunks_mut(frame_width * 3)
+        .enumerate()
+        .for_each(|(y, hdr_frame_row)| {
+            for (x, pixel) in hdr_frame_row.chunks_exact_mut(3).enumerate() {
+                let mut color_sum = [0f32; 3];
+
+                for sample_y in y * ssaa_factor..(y + 1) * ssaa_factor {
+                    let row_start = sample_y * supersampled_width;
+
+                    for sample_x in x * ssaa_factor..(x + 1) * ssaa_factor {
+                        let sample_index = (row_start + sample_x) * 3;
+
+                        for (component_sum, sample_component) in color_sum
+                            .iter_mut()
+                            .zip(&supersampled_hdr_frame[sample_index..sample_index + 3])
+                        {
+                            *component_sum += sample_component;
                         }
                     }
                 }
-            }
-            WindowEvent::CursorLeft { .. } => {
-                info!("Cursor has left the window.");
 
-                self.user_control.left_mouse_pressed = false;
+                for (component, component_sum) in pixel.iter_mut().zip(color_sum) {
+                    *component = component_sum / samples_per_pixel;
+                }
             }
-            _ => {}
-        };
+        });
+}
 
-        Ok(())
+
+/// Durations of the individual phases of a single [`SplatRenderer::render_in_place`] call.
+///
+/// The preparation and sorting phases are zero if the cached projection was reused.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct RenderTimings {
+    /// Projecting, culling and computing the footprints of the splats.
+    pub preparation: Duration,
+
+    /// Sorting the prepared splats back to front.
+    pub sorting: Duration,
+
+    /// Clearing the frame (and depth) buffers.
+    pub canvas_reset: Duration,
+
+    /// Binning and compositing the splats onto the frame.
+    pub compositing: Duration,
+
+    /// The entire render (excluding the statistics overlay).
+    pub total: Duration,
+}
+
+/// Statistics of a single [`SplatRenderer::render_in_place`] call.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct RenderStats {
+    /// Number of splats that were composited onto the frame.
+    pub visible_splats: usize,
+
+    /// Number of splats that were not drawn, whether culled (by the depth frustum, the viewport
+    /// or the clip planes) or skipped (by level of detail or the interactive frame budget).
+    /// Together with `visible_splats`, this adds up to the number of splats in the scene.
+    pub culled_splats: usize,
+
+    /// Durations of the individual phases of the render.
+    pub timings: RenderTimings,
+}
+
+
+#[derive(Clone)]
+struct SplatRendererInner {
+    pending_rerender: bool,
+
+    /// Whether the camera (or viewport) changed since the splats were last projected.
+    /// If `false`, rerenders reuse the prepared splats and only redo the compositing.
+    pending_reprojection: bool,
+
+    /// Current render resolution (the next render will be of this size).
+    render_width: u32,
+
+    render_height: u32,
+
+    camera_position: Point3<f32>,
+
+    camera_look_target: Point3<f32>,
+
+    forward_vector: Vector3<f32>,
+
+    side_vector: Vector3<f32>,
+
+    up_vector: Vector3<f32>,
+
+    /// RGBA (u8 each) for each pixel of the most recently completed frame.
+    frame: Vec<u8>,
+
+    /// Resolution of `frame`, which lags behind the render resolution
+    /// until the first render after a resize completes.
+    frame_dimensions: (u32, u32),
+
+    /// Uniform scale applied to splat billboards (adjustable at runtime).
+    splat_scaling_factor: f32,
+
+    /// Vertical field of view of the projection, in degrees.
+    vertical_field_of_view_in_degrees: f32,
+
+    /// Distance of the near clipping plane from the camera.
+    near_plane: f32,
+
+    /// Distance of the far clipping plane from the camera.
+    far_plane: f32,
+
+    /// RGBA color the canvas is cleared to before drawing the splats.
+    background_color: [u8; 4],
+
+    /// World-space half-spaces the splats are clipped to (see [`ClipPlane`]).
+    clip_planes: Vec<ClipPlane>,
+
+    /// Whether to draw the world axes (X, Y and Z, from the origin) over the splats.
+    show_axes: bool,
+
+    /// Whether to draw the render statistics overlay (render time, splat count) into the frame.
+    show_statistics_overlay: bool,
+
+    /// Whether to draw the list of keyboard shortcuts into the frame.
+    show_help_overlay: bool,
+
+    /// How long the last call to `render_in_place` took (excluding the overlay itself).
+    last_render_duration: Option<Duration>,
+
+    /// How long the last full-quality render took (used to enforce the frame budget).
+    last_full_render_duration: Option<Duration>,
+
+    /// When the camera (or viewport, or splat scaling) last changed.
+    last_camera_change: Instant,
+
+    /// Whether the frame only contains a subset of the splats (because the frame budget
+    /// was exceeded while the camera was moving) and should be rendered in full once
+    /// the camera settles.
+    showing_reduced_quality_frame: bool,
+
+    /// Angular speed (in degrees per second) the camera orbits its look target with
+    /// in turntable mode, if enabled.
+    #[cfg(feature = "ui")]
+    turntable_degrees_per_second: Option<f32>,
+}
+
+impl SplatRendererInner {
+    /// Translates both the camera position and its look target by `translation`,
+    /// sliding the framing without changing the viewing direction.
+    fn pan_camera(&mut self, translation: Vector3<f32>) {
+        self.camera_position += translation;
+        self.camera_look_target += translation;
+
+        self.mark_camera_changed();
+    }
+
+    /// Marks the camera (or viewport, or splat scaling) as changed, meaning the splats must
+    /// be projected and sorted again on the next render.
+    #[inline]
+    fn mark_camera_changed(&mut self) {
+        self.pending_reprojection = true;
+        self.pending_rerender = true;
+        self.last_camera_change = Instant::now();
+    }
+
+    /// Returns whether the camera has not changed for at least [`CAMERA_SETTLE_DELAY`].
+    #[inline]
+    fn is_camera_settled(&self) -> bool {
+        self.last_camera_change.elapsed() >= CAMERA_SETTLE_DELAY
+    }
+}
+
+/// Buffers a render works in. They are kept apart from [`SplatRendererInner`] (behind their own lock),
+/// so that the camera can be changed and the last completed frame drawn while a render is in progress.
+struct RenderBuffers {
+    /// Render resolution the buffers were allocated for.
+    render_dimensions: (u32, u32),
+
+    /// Splats projected onto the screen and ordered back to front (cached between renders).
+    prepared_splats: Vec<PreparedSplat>,
+
+    /// RGBA (u8 each) for each pixel of the frame being rendered. Once the render completes,
+    /// it is swapped with the frame in [`SplatRendererInner`].
+    frame: Vec<u8>,
+
+    /// Distance from the camera of the nearest opaque splat drawn at each pixel
+    /// (at the supersampled resolution, if supersampling is enabled).
+    /// Only used when depth testing is enabled in the rendering configuration.
+    depth_buffer: Vec<f32>,
+
+    /// Linear RGB (f32 each) for each pixel, composited without rounding to 8 bits.
+    /// Only allocated when a floating-point screenshot format (or 16-bit PNG) is configured.
+    hdr_frame: Option<Vec<f32>>,
+
+    /// Buffers the splats are composited into before being downsampled into `frame`
+    /// (and `hdr_frame`). Only allocated when supersampling is enabled.
+    supersampled_buffers: Option<SupersampledBuffers>,
+}
+
+impl RenderBuffers {
+    /// Allocates the frame, depth and (if `with_hdr_frame`) floating-point frame buffers
+    /// for the given output resolution and supersampling factor.
+    fn new(render_dimensions: (u32, u32), ssaa_factor: u32, with_hdr_frame: bool) -> Self {
+        let (render_width, render_height) = render_dimensions;
+
+        let pixel_count = render_width as usize * render_height as usize;
+        let supersampled_pixel_count = pixel_count * (ssaa_factor * ssaa_factor) as usize;
+
+        Self {
+            render_dimensions,
+            prepared_splats: Vec::new(),
+            frame: vec![0; pixel_count * 4],
+            depth_buffer: vec![f32::INFINITY; supersampled_pixel_count],
+            hdr_frame: with_hdr_frame.then(|| vec![0f32; pixel_count * 3]),
+            supersampled_buffers: (ssaa_factor > 1).then(|| SupersampledBuffers {
+                frame: vec![0; supersampled_pixel_count * 4],
+                hdr_frame: with_hdr_frame.then(|| vec![0f32; supersampled_pixel_count * 3]),
+            }),
+        }
+    }
+}
+
+/// Camera parameters the renderer was constructed with (used to reset the camera).
+#[derive(Clone, Copy, Debug)]
+struct InitialCameraState {
+    camera_position: Point3<f32>,
+
+    camera_look_target: Point3<f32>,
+
+    forward_vector: Vector3<f32>,
+
+    side_vector: Vector3<f32>,
+
+    up_vector: Vector3<f32>,
+}
+
+struct SplatRendererUserControlState {
+    left_mouse_pressed: bool,
+    control_key_pressed: bool,
+
+    /// Last known cursor position inside the window (in physical pixels).
+    last_cursor_position: Option<(f64, f64)>,
+}
+
+pub struct SplatRenderer {
+    configuration: Configuration,
+
+    splat_file: Splats,
+
+    /// Spatial grid of the splats, used to select which splats to draw
+    /// (only present if level of detail is enabled in the rendering configuration).
+    lod_grid: Option<LodGrid>,
+
+    /// How far (in world units) the camera moves on each movement or zoom step.
+    camera_movement_speed: f32,
+
+    /// The (normalized) up vector the renderer was constructed with.
+    /// Orbiting the camera revolves around this axis.
+    world_up_vector: Vector3<f32>,
+
+    /// Camera parameters at construction, restored when the camera is reset.
+    initial_camera: InitialCameraState,
+
+    user_control: Mutex<SplatRendererUserControlState>,
+
+    inner: RwLock<SplatRendererInner>,
+
+    /// Held for the entire duration of a render (so only one render runs at a time).
+    render_buffers: Mutex<RenderBuffers>,
+}
+
+impl SplatRenderer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        configuration: Configuration,
+        render_width: u32,
+        render_height: u32,
+        splat_file: Splats,
+        splat_scaling_factor: Option<f32>,
+        camera_movement_speed: Option<f32>,
+        vertical_field_of_view_in_degrees: Option<f32>,
+        initial_camera_position: Option<Point3<f32>>,
+        initial_camera_look_target: Option<Point3<f32>>,
+        initial_camera_up_vector: Option<Vector3<f32>>,
+    ) -> Self {
+        let splat_scaling_factor = splat_scaling_factor.unwrap_or(2.0);
+        debug!("Splat scaling factor: {}", splat_scaling_factor);
+
+        let camera_movement_speed =
+            camera_movement_speed.unwrap_or(configuration.camera.movement_speed);
+        debug!("Camera movement speed: {}", camera_movement_speed);
+
+        let vertical_field_of_view_in_degrees = vertical_field_of_view_in_degrees
+            .unwrap_or(configuration.camera.vertical_field_of_view_in_degrees);
+        debug!(
+            "Vertical field of view: {} degrees",
+            vertical_field_of_view_in_degrees
+        );
+
+        let camera_position = initial_camera_position.unwrap_or_else(|| Point3::new(3.0, 3.0, 3.0));
+        debug!("Starting camera position: {:?}", camera_position);
+
+        let camera_look_target =
+            initial_camera_look_target.unwrap_or_else(|| get_average_splat_coordinates(&splat_file));
+        debug!(
+            "Starting camera look target: {:?}",
+            camera_look_target
+        );
+
+
+        let initial_up_vector = initial_camera_up_vector
+            .unwrap_or_else(|| Vector3::new(0.0, 1.0, 0.0))
+            .normalize();
+        debug!(
+            "Starting camera up vector: {:?}",
+            initial_up_vector
+        );
+
+
+        let forward_vector = (camera_look_target - camera_position).normalize();
+        let (side_vector, up_vector) =
+            get_camera_side_and_up_vectors(&forward_vector, &initial_up_vector);
+
+
+        let initial_camera = InitialCameraState {
+            camera_position,
+            camera_look_target,
+            forward_vector,
+            side_vector,
+            up_vector,
+        };
+
+        let user_control = SplatRendererUserControlState {
+            left_mouse_pressed: false,
+            control_key_pressed: false,
+            last_cursor_position: None,
+        };
+
+        let inner = SplatRendererInner {
+            pending_rerender: true,
+            pending_reprojection: true,
+            render_width,
+            render_height,
+            camera_position,
+            camera_look_target,
+            forward_vector,
+            side_vector,
+            up_vector,
+            frame: vec![0; render_width as usize * render_height as usize * 4],
+            frame_dimensions: (render_width, render_height),
+            splat_scaling_factor,
+            vertical_field_of_view_in_degrees,
+            near_plane: configuration.rendering.near_plane,
+            far_plane: configuration.rendering.far_plane,
+            background_color: configuration.rendering.background_color,
+            clip_planes: Vec::new(),
+            show_axes: configuration.rendering.show_axes,
+            show_statistics_overlay: false,
+            show_help_overlay: false,
+            last_render_duration: None,
+            last_full_render_duration: None,
+            last_camera_change: Instant::now(),
+            showing_reduced_quality_frame: false,
+            #[cfg(feature = "ui")]
+            turntable_degrees_per_second: None,
+        };
+
+        let render_buffers = RenderBuffers::new(
+            (render_width, render_height),
+            configuration.rendering.ssaa_factor,
+            needs_full_precision_frame(&configuration),
+        );
+
+
+        let lod_grid = if configuration.rendering.lod_enabled {
+            let time_lod_grid_start = Instant::now();
+            let lod_grid = LodGrid::build(&splat_file);
+
+            info!(
+                "Built level of detail grid ({} non-empty cells) in {} milliseconds.",
+                lod_grid.cell_count(),
+                (time_lod_grid_start.elapsed().as_secs_f64() * 1000.0).round() as u32
+            );
+
+            Some(lod_grid)
+        } else {
+            if splat_file.len() > configuration.rendering.splat_count_warning_threshold {
+                warn!(
+                    "The scene contains {} splats (more than {}), so rendering may be slow. \
+                     Consider enabling lod_enabled in the rendering configuration.",
+                    splat_file.len(),
+                    configuration.rendering.splat_count_warning_threshold
+                );
+            }
+
+            None
+        };
+
+
+        Self {
+            configuration,
+            splat_file,
+            lod_grid,
+            camera_movement_speed,
+            world_up_vector: initial_up_vector,
+            initial_camera,
+            user_control: Mutex::new(user_control),
+            inner: RwLock::new(inner),
+            render_buffers: Mutex::new(render_buffers),
+        }
+    }
+
+    /// Creates a renderer for tests, rendering the `splats` at `render_dimensions` from
+    /// `camera_position` towards `camera_look_target`. The configuration consists of the required
+    /// tables and `additional_configuration_tables` (see [`Configuration::load_for_tests`]).
+    /// Also used by the benchmarks in `benches/`.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn new_for_tests(
+        splats: Splats,
+        render_dimensions: (u32, u32),
+        camera_position: Point3<f32>,
+        camera_look_target: Point3<f32>,
+        additional_configuration_tables: &str,
+    ) -> Self {
+        Self::new(
+            Configuration::load_for_tests(additional_configuration_tables),
+            render_dimensions.0,
+            render_dimensions.1,
+            splats,
+            None,
+            None,
+            None,
+            Some(camera_position),
+            Some(camera_look_target),
+            None,
+        )
+    }
+
+    /// Returns the render dimensions uniformly scaled down (keeping the aspect ratio) so that
+    /// neither exceeds the configured `max_render_dimension`.
+    /// Dimensions of zero are clamped to one pixel.
+    fn get_clamped_render_dimensions(&self, render_width: u32, render_height: u32) -> (u32, u32) {
+        let max_render_dimension = self.configuration.rendering.max_render_dimension;
+
+        let larger_dimension = render_width.max(render_height);
+        if larger_dimension <= max_render_dimension {
+            return (render_width.max(1), render_height.max(1));
+        }
+
+        let scale = max_render_dimension as f64 / larger_dimension as f64;
+        let scale_dimension = |dimension: u32| {
+            ((dimension as f64 * scale).round() as u32).clamp(1, max_render_dimension)
+        };
+
+        (
+            scale_dimension(render_width),
+            scale_dimension(render_height),
+        )
+    }
+
+    /// Changes the render resolution. A rerender is scheduled, since the aspect ratio
+    /// of the projection may have changed (the buffers are reallocated once it starts).
+    /// The dimensions are clamped first (see [`Self::get_clamped_render_dimensions`]).
+    /// Returns the render resolution that is used from now on.
+    pub fn set_render_dimensions(&self, render_width: u32, render_height: u32) -> (u32, u32) {
+        let (render_width, render_height) =
+            self.get_clamped_render_dimensions(render_width, render_height);
+
+        let mut inner_locked = self.inner.write();
+
+        if render_width == inner_locked.render_width && render_height == inner_locked.render_height {
+            return (render_width, render_height);
+        }
+
+        debug!(
+            "Changing render dimensions from {}x{} to {}x{}.",
+            inner_locked.render_width, inner_locked.render_height, render_width, render_height
+        );
+
+        inner_locked.render_width = render_width;
+        inner_locked.render_height = render_height;
+        inner_locked.mark_camera_changed();
+
+        (render_width, render_height)
+    }
+
+    /// Returns the resolution splats are composited at, i.e. the render resolution
+    /// multiplied by the configured supersampling factor.
+    fn get_supersampled_dimensions(&self, inner: &SplatRendererInner) -> (u32, u32) {
+        let ssaa_factor = self.configuration.rendering.ssaa_factor;
+
+        (
+            inner.render_width * ssaa_factor,
+            inner.render_height * ssaa_factor,
+        )
+    }
+
+    /// Returns the view (look-at) and perspective projection matrices for the current camera.
+    fn get_view_and_projection_matrices(
+        &self,
+        inner: &SplatRendererInner,
+    ) -> (Matrix4<f32>, Perspective3<f32>) {
+        (
+            Self::get_view_matrix(inner),
+            self.get_projection(inner),
+        )
+    }
+
+    /// Returns the (right-handed) look-at matrix for the current camera state in `inner`.
+    fn get_view_matrix(inner: &SplatRendererInner) -> Matrix4<f32> {
+        Matrix4::<f32>::look_at_rh(
+            &inner.camera_position,
+            &inner.camera_look_target,
+            &inner.up_vector,
+        )
+    }
+
+    /// Returns the perspective projection for the current field of view and clipping planes.
+    fn get_projection(&self, inner: &SplatRendererInner) -> Perspective3<f32> {
+        // Splats are projected onto the (possibly supersampled) canvas,
+        // so the aspect ratio must match its remap exactly.
+        let (canvas_width, canvas_height) = self.get_supersampled_dimensions(inner);
+
+        Perspective3::<f32>::new(
+            get_viewport_aspect_ratio(canvas_width, canvas_height),
+            inner.vertical_field_of_view_in_degrees.to_radians(),
+            inner.near_plane,
+            inner.far_plane,
+        )
+    }
+
+    /// Returns the view (look-at) matrix the renderer currently uses
+    /// to transform world coordinates into camera coordinates.
+    #[allow(dead_code)]
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        Self::get_view_matrix(&self.inner.read())
+    }
+
+    /// Returns the projection matrix the renderer currently uses
+    /// to transform camera coordinates into clip space.
+    #[allow(dead_code)]
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        self.get_projection(&self.inner.read()).to_homogeneous()
+    }
+
+    /// Returns the combined view-projection matrix (`projection_matrix() * view_matrix()`),
+    /// which transforms world coordinates directly into clip space.
+    /// The look target, for example, maps to the center of the normalized device coordinates.
+    #[allow(dead_code)]
+    pub fn view_projection_matrix(&self) -> Matrix4<f32> {
+        self.projection_matrix() * self.view_matrix()
+    }
+
+    /// Draws the world X, Y and Z axes (in red, green and blue) from the origin into `frame`
+    /// (of size `frame_dimensions`), projecting them with the view-projection `joint_matrix`.
+    fn draw_world_axes(frame: &mut [u8], frame_dimensions: (u32, u32), joint_matrix: &Matrix4<f32>) {
+        let (frame_width, frame_height) = frame_dimensions;
+
+        let origin_in_clip_space = joint_matrix * Vector4::new(0.0, 0.0, 0.0, 1.0);
+
+        let axes = [
+            (
+                Vector4::new(WORLD_AXIS_LENGTH, 0.0, 0.0, 1.0),
+                [255, 64, 64, 255],
+            ),
+            (
+                Vector4::new(0.0, WORLD_AXIS_LENGTH, 0.0, 1.0),
+                [64, 255, 64, 255],
+            ),
+            (
+                Vector4::new(0.0, 0.0, WORLD_AXIS_LENGTH, 1.0),
+                [64, 128, 255, 255],
+            ),
+        ];
+
+        for (axis_end_in_world_space, axis_color) in axes {
+            let axis_end_in_clip_space = joint_matrix * axis_end_in_world_space;
+
+            let Some((segment_start, segment_end)) =
+                clip_segment_in_front_of_camera(origin_in_clip_space, axis_end_in_clip_space)
+            else {
+                continue;
+            };
+
+            let (Some(start_coordinates), Some(end_coordinates)) = (
+                get_normalized_device_coordinates(segment_start),
+                get_normalized_device_coordinates(segment_end),
+            ) else {
+                continue;
+            };
+
+            overlay::draw_line(
+                frame,
+                frame_dimensions,
+                get_viewport_coordinates_from_normalized_device_coordinates(
+                    start_coordinates,
+                    frame_width,
+                    frame_height,
+                ),
+                get_viewport_coordinates_from_normalized_device_coordinates(
+                    end_coordinates,
+                    frame_width,
+                    frame_height,
+                ),
+                axis_color,
+            );
+        }
+    }
+
+    /// Draws the list of keyboard shortcuts into the top right corner of `frame`
+    /// (of size `frame_dimensions`), at the largest text scale that fits.
+    fn draw_help_overlay(frame: &mut [u8], frame_dimensions: (u32, u32), keymap: &Keymap) {
+        let help_text = format!("KEYS\n{}", keymap.format());
+
+        let text_scale = (1..=HELP_OVERLAY_MAX_TEXT_SCALE)
+            .rev()
+            .find(|text_scale| {
+                let (text_width, text_height) =
+                    overlay::get_text_size_with_background(&help_text, *text_scale);
+
+                text_width + 8 <= frame_dimensions.0 && text_height + 8 <= frame_dimensions.1
+            })
+            .unwrap_or(1);
+
+        let (text_width, _) = overlay::get_text_size_with_background(&help_text, text_scale);
+
+        overlay::draw_text_with_background(
+            frame,
+            frame_dimensions,
+            (
+                frame_dimensions.0.saturating_sub(text_width + 4),
+                4,
+            ),
+            &help_text,
+            [255, 255, 255, 255],
+            [0, 0, 0, 192],
+            text_scale,
+        );
+    }
+
+    /// Updates the camera's orthonormal basis and returns the camera-dependent state
+    /// needed to project individual splats with [`Self::project_splat`].
+    fn create_projection_context(&self, inner: &mut SplatRendererInner) -> SplatProjectionContext {
+        // Transform the world coordinates of each splat to camera coordinates.
+
+        let updated_forward_vector = (inner.camera_look_target - inner.camera_position).normalize();
+        let (updated_side_vector, updated_up_vector) =
+            get_camera_side_and_up_vectors(&updated_forward_vector, &inner.up_vector);
+
+        inner.forward_vector = updated_forward_vector;
+        inner.side_vector = updated_side_vector;
+        inner.up_vector = updated_up_vector;
+
+        debug!(
+            "Performing render. Context:\n \
+             -> camera position: {:?}\n \
+             -> camera look target: {:?}\n \
+             -> forward vector: {:?}\n \
+             -> side vector: {:?}\n \
+             -> up vector: {:?}",
+            inner.camera_position,
+            inner.camera_look_target,
+            updated_forward_vector,
+            updated_side_vector,
+            updated_up_vector
+        );
+
+
+        let (look_at_matrix, projection_matrix) = self.get_view_and_projection_matrices(inner);
+
+        let joint_matrix = projection_matrix.as_matrix() * look_at_matrix;
+        let view_rotation: Matrix3<f32> = look_at_matrix.fixed_view::<3, 3>(0, 0).into_owned();
+
+        // Splats are projected onto the (possibly supersampled) canvas they will be composited into.
+        let canvas_size = self.get_supersampled_dimensions(inner);
+
+        // Focal lengths of the projection, expressed in pixels (used to project splat footprints).
+        let focal_lengths_in_pixels = (
+            projection_matrix.as_matrix().m11 * (canvas_size.0 as f32 - 1.0) / 2.0,
+            projection_matrix.as_matrix().m22 * (canvas_size.1 as f32 - 1.0) / 2.0,
+        );
+
+
+        SplatProjectionContext {
+            look_at_matrix,
+            joint_matrix,
+            view_rotation,
+            focal_lengths_in_pixels,
+            canvas_size,
+            splat_scaling_factor: inner.splat_scaling_factor,
+            far_plane: inner.far_plane,
+            clip_planes: inner.clip_planes.clone(),
+            camera_position: inner.camera_position,
+        }
+    }
+
+    /// Projects a single splat (at `splat_index` in the original splat list) onto the canvas.
+    /// The returned [`SplatProjection`] contains a [`PreparedSplat`] only if the splat is visible
+    /// (i.e. was not culled).
+    fn project_splat(
+        &self,
+        splat_index: usize,
+        splat: &Splat,
+        context: &SplatProjectionContext,
+    ) -> SplatProjection {
+        let ssaa_factor = self.configuration.rendering.ssaa_factor;
+
+        let position_in_world_space = Vector4::new(
+            splat.position.x,
+            splat.position.y,
+            splat.position.z,
+            1f32,
+        );
+
+        // let position_in_camera_space = look_at_matrix * position_in_world_space;
+        // let position_in_clip_space =
+        //     projection_matrix.as_matrix() * position_in_camera_space;
+
+        let position_in_clip_space = context.joint_matrix * position_in_world_space;
+        let distance_from_camera = get_splat_distance_from_camera(&position_in_clip_space);
+
+        let pixel_coordinates = get_pixel_coordinates_from_projected_coordinates(
+            position_in_clip_space,
+            context.canvas_size.0,
+            context.canvas_size.1,
+            self.configuration.rendering.clamp_offscreen,
+        );
+
+        let mut projection = SplatProjection {
+            position_in_clip_space,
+            distance_from_camera,
+            is_inside_depth_frustum: is_clip_space_position_inside_depth_frustum(
+                &position_in_clip_space,
+            ),
+            is_inside_clip_planes: context
+                .clip_planes
+                .iter()
+                .all(|clip_plane| clip_plane.contains(&splat.position)),
+            pixel_coordinates,
+            prepared_splat: None,
+        };
+
+        if !projection.is_inside_depth_frustum || !projection.is_inside_clip_planes {
+            return projection;
+        }
+
+        let Some(center_pixel_in_viewport) = pixel_coordinates.subpixel() else {
+            return projection;
+        };
+
+
+        let billboard_size_model = self.configuration.rendering.billboard_size_model;
+
+        let billboard_size = (billboard_size_for_distance(
+            billboard_size_model,
+            context.splat_scaling_factor,
+            distance_from_camera,
+            context.far_plane,
+        ) * ssaa_factor as f32)
+            .round() as u32;
+
+        // Projected (elliptical) footprints already shrink with `1 / d` through the perspective
+        // projection, so other size models scale them by their ratio to the `1 / d` model.
+        let footprint_scaling_factor = context.splat_scaling_factor
+            * billboard_size_for_distance(
+                billboard_size_model,
+                1.0,
+                distance_from_camera,
+                context.far_plane,
+            )
+            / billboard_size_for_distance(
+                BillboardSizeModel::InverseDistance,
+                1.0,
+                distance_from_camera,
+                context.far_plane,
+            );
+
+        projection.prepared_splat = Some(PreparedSplat {
+            splat_index,
+            distance_from_camera,
+            center_pixel_in_viewport,
+            footprint: get_billboard_footprint(
+                splat,
+                &(context.look_at_matrix * position_in_world_space).xyz(),
+                &context.view_rotation,
+                context.focal_lengths_in_pixels,
+                footprint_scaling_factor,
+                billboard_size,
+                self.configuration.rendering.billboard_shape,
+            )
+            .clamped_to_size(
+                self.configuration.rendering.min_billboard_size_pixels * ssaa_factor,
+                self.configuration.rendering.max_billboard_size_pixels * ssaa_factor,
+            ),
+            color: splat.color_for_view_direction(
+                &(splat.position - context.camera_position.coords).normalize(),
+            ),
+            opacity: splat.opacity(),
+        });
+
+        projection
+    }
+
+    /// Projects every `splat_stride`-th splat (all of them with a stride of 1) onto the screen
+    /// (using the camera parameters captured in `projection_context`) and orders them back to front.
+    ///
+    /// If level of detail is enabled, only the splats selected by the [`LodGrid`] are considered.
+    fn project_and_sort_splats(
+        &self,
+        projection_context: &SplatProjectionContext,
+        splat_stride: usize,
+        timings: &mut RenderTimings,
+    ) -> Vec<PreparedSplat> {
+        let mut prepared_splats = self.project_splats(projection_context, splat_stride, timings);
+        self.sort_prepared_splats(&mut prepared_splats, timings);
+
+        prepared_splats
+    }
+
+    /// Projects every `splat_stride`-th splat (see [`Self::project_and_sort_splats`]),
+    /// discarding the culled ones. The splats are returned in no particular order.
+    fn project_splats(
+        &self,
+        projection_context: &SplatProjectionContext,
+        splat_stride: usize,
+        timings: &mut RenderTimings,
+    ) -> Vec<PreparedSplat> {
+        let time_prepare_splats_start = Instant::now();
+
+        let clamp_offscreen = self.configuration.rendering.clamp_offscreen;
+        let clamped_splat_count = AtomicUsize::new(0);
+
+        let prepare_splat = |splat_index: usize| {
+            let projection = self.project_splat(
+                splat_index,
+                &self.splat_file.splats[splat_index],
+                projection_context,
+            );
+
+            if projection.is_inside_depth_frustum
+                && projection.is_inside_clip_planes
+                && matches!(
+                    projection.pixel_coordinates,
+                    ProjectedPixelCoordinates::Clamped(_)
+                )
+            {
+                clamped_splat_count.fetch_add(1, Ordering::Relaxed);
+            }
+
+            projection.prepared_splat
+        };
+
+        let prepared_splats = match self.lod_grid.as_ref() {
+            Some(lod_grid) => {
+                let selected_splat_indices = lod_grid
+                    .selected_splat_indices(&projection_context.camera_position, splat_stride);
+
+                debug!(
+                    "Level of detail selected {} of {} splats.",
+                    selected_splat_indices.len(),
+                    self.splat_file.len()
+                );
+
+                selected_splat_indices
+                    .into_par_iter()
+                    .filter_map(prepare_splat)
+                    .collect::<Vec<_>>()
+            }
+            None => (0..self.splat_file.len())
+                .into_par_iter()
+                .step_by(splat_stride)
+                .filter_map(prepare_splat)
+                .collect::<Vec<_>>(),
+        };
+
+        timings.preparation = time_prepare_splats_start.elapsed();
+
+        debug!(
+            "Preparing splats (projection + frustum culling + viewport filtering + distance calculation) took {} milliseconds.",
+            (timings.preparation.as_secs_f64() * 1000.0).round() as u32
+        );
+
+        if clamp_offscreen {
+            info!(
+                "Clamped {} off-screen splats to the viewport border.",
+                clamped_splat_count.into_inner()
+            );
+        }
+
+        prepared_splats
+    }
+
+    /// Orders the prepared splats for compositing (by the configured [`SortKey`]).
+    fn sort_prepared_splats(
+        &self,
+        prepared_splats: &mut [PreparedSplat],
+        timings: &mut RenderTimings,
+    ) {
+        let time_prepared_splat_sort_start = Instant::now();
+
+        let sort_key = self.configuration.rendering.sort_key;
+        prepared_splats
+            .par_sort_unstable_by(|first, second| compare_prepared_splats(first, second, sort_key));
+
+        timings.sorting = time_prepared_splat_sort_start.elapsed();
+
+        debug!(
+            "Sorting prepared splats (by {:?}) took {} milliseconds.",
+            sort_key,
+            (timings.sorting.as_secs_f64() * 1000.0).round() as u32
+        );
+
+
+
+        // The scene may be empty (or entirely culled), in which case there is nothing to log.
+        if let (Some(first_splat), Some(last_splat)) =
+            (prepared_splats.first(), prepared_splats.last())
+        {
+            trace!(
+                "Distance of first ordered splat: {}",
+                first_splat.distance_from_camera
+            );
+            trace!(
+                "Distance of last ordered splat: {}",
+                last_splat.distance_from_camera
+            );
+        }
+    }
+
+    /// Runs only the projection phase of a render (see [`Self::project_and_sort_splats`])
+    /// for all splats, with the current camera. Used by the benchmarks in `benches/`.
+    #[allow(dead_code)]
+    pub(crate) fn project_splats_with_current_camera(&self) -> Vec<PreparedSplat> {
+        let projection_context = self.create_projection_context(&mut self.inner.write());
+        self.project_splats(
+            &projection_context,
+            1,
+            &mut RenderTimings::default(),
+        )
+    }
+
+    /// Runs only the sorting phase of a render on splats returned by
+    /// [`Self::project_splats_with_current_camera`]. Used by the benchmarks in `benches/`.
+    #[allow(dead_code)]
+    pub(crate) fn sort_projected_splats(&self, prepared_splats: &mut [PreparedSplat]) {
+        self.sort_prepared_splats(prepared_splats, &mut RenderTimings::default());
+    }
+
+    /// Renders the scene into the internal frame buffer and returns the splat counts
+    /// and the timings of each phase.
+    pub fn render_in_place(&self) -> RenderStats {
+        self.render_in_place_with_splat_stride(1)
+    }
+
+    /// Renders the scene into the internal frame buffer, only drawing every `splat_stride`-th splat
+    /// (a stride of 1 draws all of them), and returns the splat counts and the timings of each phase.
+    ///
+    /// Reduced-quality renders (with a stride above 1) are not cached: the next render
+    /// projects all splats again.
+    fn render_in_place_with_splat_stride(&self, splat_stride: usize) -> RenderStats {
+        let mut render_buffers = self.render_buffers.lock();
+        self.render_into_buffers(&self.inner, &mut render_buffers, splat_stride)
+    }
+
+    /// Renders the scene at `render_width` by `render_height` pixels (regardless of the current
+    /// render resolution) and returns the frame, e.g. for high-resolution screenshots.
+    ///
+    /// The render uses a copy of the current camera and settings and its own (temporary) buffers,
+    /// so the live state (including the last completed frame) is left untouched.
+    #[allow(dead_code)]
+    pub fn render_at(&self, render_width: u32, render_height: u32) -> RgbaImage {
+        self.render_detached_at(render_width, render_height).0
+    }
+
+    /// Renders the scene like [`Self::render_at`], also returning the full-precision frame
+    /// (see [`Self::render_to_hdr_image`]) if it is allocated.
+    fn render_detached_at(
+        &self,
+        render_width: u32,
+        render_height: u32,
+    ) -> (RgbaImage, Option<Rgb32FImage>) {
+        let detached_inner = {
+            let inner_locked = self.inner.read();
+
+            RwLock::new(SplatRendererInner {
+                render_width,
+                render_height,
+                pending_reprojection: true,
+                ..inner_locked.clone()
+            })
+        };
+
+        let mut render_buffers = RenderBuffers::new(
+            (render_width, render_height),
+            self.configuration.rendering.ssaa_factor,
+            needs_full_precision_frame(&self.configuration),
+        );
+
+        self.render_into_buffers(&detached_inner, &mut render_buffers, 1);
+
+
+        let detached_inner = detached_inner.into_inner();
+        let hdr_image = render_buffers.hdr_frame.map(|hdr_frame| {
+            // PANIC SAFETY: The HDR frame buffer is always allocated with `render_dimensions` pixels.
+            Rgb32FImage::from_vec(render_width, render_height, hdr_frame)
+                .expect("BUG: HDR frame buffer should match the render dimensions.")
+        });
+
+        (
+            self.frame_to_image(
+                detached_inner.frame,
+                detached_inner.frame_dimensions,
+            ),
+            hdr_image,
+        )
+    }
+
+    /// Renders the scene into `render_buffers` (see [`Self::render_in_place_with_splat_stride`])
+    /// using the camera and settings in `inner` (usually [`Self::inner`]) and, once done,
+    /// swaps the completed frame into `inner`.
+    ///
+    /// The camera state is only locked at the start and end of the render,
+    /// so the camera can keep changing while the splats are being composited.
+    fn render_into_buffers(
+        &self,
+        inner: &RwLock<SplatRendererInner>,
+        render_buffers: &mut RenderBuffers,
+        splat_stride: usize,
+    ) -> RenderStats {
+        let time_render_start = Instant::now();
+        let mut timings = RenderTimings::default();
+
+
+        // Capture the current camera and settings. Changes made after this point
+        // are picked up by the next render.
+        let mut inner_locked = inner.write();
+
+        let render_dimensions = (
+            inner_locked.render_width,
+            inner_locked.render_height,
+        );
+        if render_buffers.render_dimensions != render_dimensions {
+            *render_buffers = RenderBuffers::new(
+                render_dimensions,
+                self.configuration.rendering.ssaa_factor,
+                needs_full_precision_frame(&self.configuration),
+            );
+
+            // Freshly allocated buffers have no prepared splats to reuse.
+            inner_locked.pending_reprojection = true;
+        }
+
+        let projection_context = (inner_locked.pending_reprojection || splat_stride > 1)
+            .then(|| self.create_projection_context(&mut inner_locked));
+
+        let world_axes_matrix = inner_locked.show_axes.then(|| {
+            let (look_at_matrix, projection_matrix) =
+                self.get_view_and_projection_matrices(&inner_locked);
+
+            projection_matrix.as_matrix() * look_at_matrix
+        });
+
+        let background_color = inner_locked.background_color;
+        let show_statistics_overlay = inner_locked.show_statistics_overlay;
+        let show_help_overlay = inner_locked.show_help_overlay;
+
+        inner_locked.pending_reprojection = splat_stride > 1;
+        inner_locked.pending_rerender = false;
+
+        drop(inner_locked);
+
+
+        // Project splats to camera space and order them back to front,
+        // unless the camera did not change since the last (full) projection.
+        if let Some(projection_context) = projection_context {
+            render_buffers.prepared_splats =
+                self.project_and_sort_splats(&projection_context, splat_stride, &mut timings);
+        } else {
+            debug!("Camera is unchanged, reusing the cached splat projection.");
+        }
+
+
+
+        // Reset canvas. When supersampling is enabled, splats are composited into the larger
+        // supersampled buffers, which are downsampled into the output frame afterwards.
+        let time_canvas_reset_start = Instant::now();
+
+        let ssaa_factor = self.configuration.rendering.ssaa_factor;
+        let (canvas_width, canvas_height) = (
+            render_dimensions.0 * ssaa_factor,
+            render_dimensions.1 * ssaa_factor,
+        );
+
+        let (canvas_frame, mut canvas_hdr_frame) = match render_buffers.supersampled_buffers.as_mut()
+        {
+            Some(supersampled_buffers) => (
+                &mut supersampled_buffers.frame,
+                supersampled_buffers.hdr_frame.as_mut(),
+            ),
+            None => (
+                &mut render_buffers.frame,
+                render_buffers.hdr_frame.as_mut(),
+            ),
+        };
+
+        // Checkerboard tiles are sized in output pixels, so they scale with supersampling.
+        let checker_tile_size = match self.configuration.rendering.backdrop {
+            Backdrop::Solid => None,
+            Backdrop::Checker => Some(self.configuration.rendering.checker_tile_size * ssaa_factor),
+        };
+
+        let get_backdrop_color = |pixel_index: usize| -> [u8; 4] {
+            let Some(checker_tile_size) = checker_tile_size else {
+                return background_color;
+            };
+
+            get_checker_tile_color(
+                pixel_index as u32 % canvas_width,
+                pixel_index as u32 / canvas_width,
+                checker_tile_size,
+            )
+        };
+
+        for (pixel_index, pixel) in canvas_frame.chunks_exact_mut(4).enumerate() {
+            pixel.copy_from_slice(&get_backdrop_color(pixel_index));
+        }
+
+        if let Some(hdr_frame) = canvas_hdr_frame.as_deref_mut() {
+            for (pixel_index, pixel) in hdr_frame.chunks_exact_mut(3).enumerate() {
+                let backdrop_color = get_backdrop_color(pixel_index);
+
+                // The floating-point frame always holds linear (not sRGB) colors.
+                let backdrop_color_f32 = [
+                    srgb_to_linear(backdrop_color[0] as f32 / u8::MAX as f32),
+                    srgb_to_linear(backdrop_color[1] as f32 / u8::MAX as f32),
+                    srgb_to_linear(backdrop_color[2] as f32 / u8::MAX as f32),
+                ];
+
+                pixel.copy_from_slice(&backdrop_color_f32);
+            }
+        }
+
+        let depth_test_enabled = self.configuration.rendering.depth_test;
+        if depth_test_enabled {
+            render_buffers.depth_buffer.fill(f32::INFINITY);
+        }
+
+        timings.canvas_reset = time_canvas_reset_start.elapsed();
+
+        debug!(
+            "Resetting the canvas took {} milliseconds.",
+            (timings.canvas_reset.as_secs_f64() * 1000.0).round() as u32
+        );
+
+
+        // Splats have been prepared and ordered back to front, render them.
+        // The frame is split into horizontal tiles that are composited in parallel.
+        // Each splat is binned into every tile its billboard overlaps
+        // (preserving the back-to-front order inside each tile).
+        let time_compositing_start = Instant::now();
+
+        let compositing_parameters = CompositingParameters {
+            viewport_size: (canvas_width, canvas_height),
+            render_mode: self.configuration.rendering.render_mode,
+            falloff_sigma: self.configuration.rendering.gaussian_falloff_sigma,
+            depth_test_enabled,
+            linear_blending: self.configuration.rendering.blends_in_linear_color_space(),
+            linear_input_colors: self.configuration.rendering.input_color_space
+                == InputColorSpace::Linear,
+            alpha_mode: self.configuration.rendering.alpha_mode,
+        };
+
+        let prepared_splats = &render_buffers.prepared_splats;
+        let visible_splat_count = prepared_splats.len();
+
+        let tile_count = canvas_height.div_ceil(COMPOSITING_TILE_HEIGHT_IN_ROWS) as usize;
+
+        let mut tile_bins: Vec<Vec<u32>> = vec![Vec::new(); tile_count];
+        for (splat_index, prepared_splat) in prepared_splats.iter().enumerate() {
+            // In points mode, each splat only covers its center pixel.
+            let (first_row, last_row) = match compositing_parameters.render_mode {
+                RenderMode::Billboards => {
+                    let (_, falloff_half_extent_y) = prepared_splat
+                        .footprint
+                        .scaled(
+                            GAUSSIAN_FALLOFF_CUTOFF_IN_SIGMAS * compositing_parameters.falloff_sigma,
+                        )
+                        .half_extents();
+
+                    get_covered_pixel_range(
+                        prepared_splat.center_pixel_in_viewport.1,
+                        falloff_half_extent_y,
+                    )
+                }
+                RenderMode::Points => {
+                    let (_, center_y) = get_nearest_pixel(prepared_splat.center_pixel_in_viewport);
+                    (center_y, center_y)
+                }
+            };
+
+            let last_row = last_row.min(canvas_height - 1);
+            if first_row > last_row {
+                continue;
+            }
+
+            let first_tile = (first_row / COMPOSITING_TILE_HEIGHT_IN_ROWS) as usize;
+            let last_tile = (last_row / COMPOSITING_TILE_HEIGHT_IN_ROWS) as usize;
+
+            for tile_bin in &mut tile_bins[first_tile..=last_tile] {
+                tile_bin.push(splat_index as u32);
+            }
+        }
+
+
+        let tile_row_length = canvas_width as usize * COMPOSITING_TILE_HEIGHT_IN_ROWS as usize;
+
+        let hdr_frame_tiles: Vec<Option<&mut [f32]>> = match canvas_hdr_frame {
+            Some(hdr_frame) => hdr_frame
+                .chunks_mut(tile_row_length * 3)
+                .map(Some)
+                .collect(),
+            None => (0..tile_count).map(|_| None).collect(),
+        };
+
+        let frame_tiles = canvas_frame
+            .chunks_mut(tile_row_length * 4)
+            .zip(render_buffers.depth_buffer.chunks_mut(tile_row_length))
+            .zip(hdr_frame_tiles)
+            .enumerate()
+            .map(
+                |(tile_index, ((frame, depth_buffer), hdr_frame))| {
+                    let first_row = tile_index as u32 * COMPOSITING_TILE_HEIGHT_IN_ROWS;
+                    let end_row = (first_row + COMPOSITING_TILE_HEIGHT_IN_ROWS).min(canvas_height);
+
+                    FrameTile {
+                        rows: first_row..end_row,
+                        frame,
+                        depth_buffer,
+                        hdr_frame,
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        frame_tiles
+            .into_par_iter()
+            .zip(tile_bins.par_iter())
+            .for_each(|(tile, tile_bin)| {
+                composite_splats_onto_tile(
+                    tile,
+                    prepared_splats,
+                    tile_bin,
+                    &compositing_parameters,
+                );
+            });
+
+
+        if let Some(supersampled_buffers) = render_buffers.supersampled_buffers.as_ref() {
+            downsample_supersampled_frame(
+                &supersampled_buffers.frame,
+                &mut render_buffers.frame,
+                render_dimensions.0,
+                ssaa_factor,
+            );
+
+            if let (Some(supersampled_hdr_frame), Some(hdr_frame)) = (
+                supersampled_buffers.hdr_frame.as_ref(),
+                render_buffers.hdr_frame.as_mut(),
+            ) {
+                downsample_supersampled_hdr_frame(
+                    supersampled_hdr_frame,
+                    hdr_frame,
+                    render_dimensions.0,
+                    ssaa_factor,
+                );
+            }
+        }
+
+        if let Some(hdr_frame) = render_buffers.hdr_frame.as_mut() {
+            apply_tone_mapping(
+                &mut render_buffers.frame,
+                hdr_frame,
+                self.configuration.rendering.tone_mapping,
+                self.configuration.rendering.exposure,
+            );
+        }
+
+        // When supersampling, this also includes the downsampling pass (and tone mapping).
+        timings.compositing = time_compositing_start.elapsed();
+
+        debug!(
+            "Compositing the splats took {} milliseconds.",
+            (timings.compositing.as_secs_f64() * 1000.0).round() as u32
+        );
+
+
+        if let Some(world_axes_matrix) = world_axes_matrix {
+            Self::draw_world_axes(
+                &mut render_buffers.frame,
+                render_dimensions,
+                &world_axes_matrix,
+            );
+        }
+
+
+        let render_duration = time_render_start.elapsed();
+        timings.total = render_duration;
+
+        if show_statistics_overlay {
+            let overlay_text = format!(
+                "RENDER: {:.1} MS\nSPLATS: {}/{}",
+                render_duration.as_secs_f64() * 1000.0,
+                visible_splat_count,
+                self.splat_file.len()
+            );
+
+            overlay::draw_text_with_background(
+                &mut render_buffers.frame,
+                render_dimensions,
+                (4, 4),
+                &overlay_text,
+                [255, 255, 255, 255],
+                [0, 0, 0, 160],
+                2,
+            );
+        }
+
+        if show_help_overlay {
+            Self::draw_help_overlay(
+                &mut render_buffers.frame,
+                render_dimensions,
+                &self.configuration.keymap,
+            );
+        }
+
+
+        // Present the completed frame by swapping it with the previous one
+        // (which will be overwritten by the next render).
+        let mut inner_locked = inner.write();
+
+        std::mem::swap(&mut inner_locked.frame, &mut render_buffers.frame);
+        inner_locked.frame_dimensions = render_dimensions;
+
+        inner_locked.last_render_duration = Some(render_duration);
+        inner_locked.showing_reduced_quality_frame = splat_stride > 1;
+        if splat_stride == 1 {
+            inner_locked.last_full_render_duration = Some(render_duration);
+        }
+
+        RenderStats {
+            visible_splats: visible_splat_count,
+            culled_splats: self.splat_file.len() - visible_splat_count,
+            timings,
+        }
+    }
+
+    /// Projects all splats using the current camera and writes the per-splat projection data
+    /// (world position, clip-space position, distance from the camera, pixel coordinates
+    /// and whether the splat was drawn or culled) to a CSV file, in file order.
+    ///
+    /// Pixel coordinates are those of the (possibly supersampled) canvas and are left empty
+    /// for splats that could not be placed in the viewport.
+    pub fn dump_projection_to_csv<P>(&self, output_file_path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut inner_locked = self.inner.write();
+        let projection_context = self.create_projection_context(&mut inner_locked);
+        drop(inner_locked);
+
+
+        let output_file = File::create(output_file_path.as_ref())
+            .into_diagnostic()
+            .wrap_err("Failed to create projection dump file.")?;
+        let mut output_writer = BufWriter::new(output_file);
+
+        writeln!(
+            output_writer,
+            "splat_index,world_x,world_y,world_z,clip_x,clip_y,clip_z,clip_w,distance,pixel_x,pixel_y,status"
+        )
+        .into_diagnostic()?;
+
+        for (splat_index, splat) in self.splat_file.iter().enumerate() {
+            let projection = self.project_splat(splat_index, splat, &projection_context);
+
+            let status = if !projection.is_inside_depth_frustum {
+                "depth-culled"
+            } else if !projection.is_inside_clip_planes {
+                "plane-clipped"
+            } else {
+                match projection.pixel_coordinates {
+                    ProjectedPixelCoordinates::Inside(_) => "inside",
+                    ProjectedPixelCoordinates::Clamped(_) => "clamped",
+                    ProjectedPixelCoordinates::Outside => "offscreen",
+                    ProjectedPixelCoordinates::BehindCamera => "behind-camera",
+                }
+            };
+
+            let (pixel_x, pixel_y) = match projection.pixel_coordinates.pixel() {
+                Some((pixel_x, pixel_y)) => (pixel_x.to_string(), pixel_y.to_string()),
+                None => (String::new(), String::new()),
+            };
+
+            let clip_position = projection.position_in_clip_space;
+
+            writeln!(
+                output_writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                splat_index,
+                splat.position.x,
+                splat.position.y,
+                splat.position.z,
+                clip_position.x,
+                clip_position.y,
+                clip_position.z,
+                clip_position.w,
+                projection.distance_from_camera,
+                pixel_x,
+                pixel_y,
+                status
+            )
+            .into_diagnostic()?;
+        }
+
+        output_writer
+            .flush()
+            .into_diagnostic()
+            .wrap_err("Failed to write projection dump file.")?;
+
+        info!(
+            "Dumped the projection of {} splats to {}.",
+            self.splat_file.len(),
+            output_file_path.as_ref().display()
+        );
+
+        Ok(())
+    }
+
+    /// Returns the (original) index and projected center pixel (in output frame coordinates)
+    /// of each splat that is visible from the current camera, i.e. whose center lies
+    /// between the clipping planes and projects inside the viewport.
+    ///
+    /// The splats are projected lazily (with the same math as when rendering), so the camera
+    /// captured when this is called is used even if it changes during iteration.
+    #[allow(dead_code)]
+    pub fn visible_splats(&self) -> impl Iterator<Item = (usize, (u32, u32))> + '_ {
+        let mut inner_locked = self.inner.write();
+        let projection_context = self.create_projection_context(&mut inner_locked);
+        drop(inner_locked);
+
+        let ssaa_factor = self.configuration.rendering.ssaa_factor;
+
+        self.splat_file
+            .splats
+            .iter()
+            .enumerate()
+            .filter_map(move |(splat_index, splat)| {
+                let projection = self.project_splat(splat_index, splat, &projection_context);
+                if !projection.is_inside_depth_frustum || !projection.is_inside_clip_planes {
+                    return None;
+                }
+
+                match projection.pixel_coordinates {
+                    ProjectedPixelCoordinates::Inside(subpixel) => {
+                        let (pixel_x, pixel_y) = get_nearest_pixel(subpixel);
+                        Some((
+                            splat_index,
+                            (pixel_x / ssaa_factor, pixel_y / ssaa_factor),
+                        ))
+                    }
+                    _ => None,
+                }
+            })
+    }
+
+    /// Returns which splats (every n-th) to draw in the next interactive frame
+    /// to stay within the configured frame budget.
+    ///
+    /// All splats are drawn if there is no frame budget, if the camera has settled,
+    /// or if the last full render fit into the budget.
+    fn get_interactive_splat_stride(&self, inner: &SplatRendererInner) -> usize {
+        let Some(frame_budget_milliseconds) = self.configuration.rendering.frame_budget_milliseconds
+        else {
+            return 1;
+        };
+
+        let Some(last_full_render_duration) = inner.last_full_render_duration else {
+            return 1;
+        };
+
+        if inner.is_camera_settled() {
+            return 1;
+        }
+
+        let last_full_render_milliseconds = last_full_render_duration.as_secs_f32() * 1000.0;
+        if last_full_render_milliseconds <= frame_budget_milliseconds {
+            return 1;
+        }
+
+        let splat_stride =
+            (last_full_render_milliseconds / frame_budget_milliseconds).ceil() as usize;
+
+        debug!(
+            "Last full render took {} milliseconds (over the {} millisecond budget), \
+             drawing one in every {} splats while the camera is moving.",
+            last_full_render_milliseconds.round() as u32,
+            frame_budget_milliseconds,
+            splat_stride
+        );
+
+        splat_stride
+    }
+
+    /// Returns the (original) index of the splat nearest to the camera that is drawn
+    /// onto pixel (`x`, `y`) of the output frame, or `None` if no splat covers that pixel.
+    ///
+    /// This uses the splat projection cached by the last render, so it describes the most recently
+    /// rendered frame (and waits for a render in progress to complete, see [`Self::try_pick`]).
+    #[allow(dead_code)]
+    pub fn pick(&self, x: u32, y: u32) -> Option<usize> {
+        let render_buffers = self.render_buffers.lock();
+        self.pick_from_buffers(&render_buffers, x, y)
+    }
+
+    /// Like [`Self::pick`], but returns `None` instead of waiting if a render is in progress,
+    /// so it can be called from the event loop without freezing the window.
+    pub fn try_pick(&self, x: u32, y: u32) -> Option<Option<usize>> {
+        let render_buffers = self.render_buffers.try_lock()?;
+        Some(self.pick_from_buffers(&render_buffers, x, y))
+    }
+
+    /// Picks the splat at pixel (`x`, `y`) from the projection cached in `render_buffers`
+    /// (see [`Self::pick`]).
+    fn pick_from_buffers(&self, render_buffers: &RenderBuffers, x: u32, y: u32) -> Option<usize> {
+        let (render_width, render_height) = render_buffers.render_dimensions;
+        if x >= render_width || y >= render_height {
+            return None;
+        }
+
+        // When supersampling, splats are projected onto the larger canvas,
+        // so use the canvas pixel at the center of the output pixel.
+        let ssaa_factor = self.configuration.rendering.ssaa_factor;
+        let canvas_size = (
+            render_width * ssaa_factor,
+            render_height * ssaa_factor,
+        );
+        let canvas_pixel = (
+            x * ssaa_factor + ssaa_factor / 2,
+            y * ssaa_factor + ssaa_factor / 2,
+        );
+
+        let render_mode = self.configuration.rendering.render_mode;
+        let falloff_sigma = self.configuration.rendering.gaussian_falloff_sigma;
+
+        render_buffers
+            .prepared_splats
+            .par_iter()
+            .filter(|prepared_splat| {
+                does_prepared_splat_cover_pixel(
+                    prepared_splat,
+                    canvas_pixel,
+                    canvas_size,
+                    render_mode,
+                    falloff_sigma,
+                )
+            })
+            .min_by(|first, second| {
+                first
+                    .distance_from_camera
+                    .total_cmp(&second.distance_from_camera)
+                    .then(first.splat_index.cmp(&second.splat_index))
+            })
+            .map(|prepared_splat| prepared_splat.splat_index)
+    }
+
+    /// Forces the next render to project and sort all splats again,
+    /// even if the camera did not change (e.g. to benchmark full renders).
+    pub fn invalidate_projection(&self) {
+        self.inner.write().mark_camera_changed();
+    }
+
+    /// Returns a copy of the rendered frame as an [`RgbaImage`].
+    ///
+    /// Alpha is forced to 255 unless `preserve_alpha` is enabled in the screenshot configuration,
+    /// in which case the accumulated per-pixel alpha is kept.
+    ///
+    /// The scene is only rendered again if the camera changed since the last render
+    /// (or only a reduced-quality frame was rendered), otherwise the cached frame is reused.
+    pub fn render_to_image(&self) -> RgbaImage {
+        let needs_full_render = {
+            let inner_locked = self.inner.read();
+            inner_locked.pending_rerender || inner_locked.showing_reduced_quality_frame
+        };
+
+        if needs_full_render {
+            self.render_in_place();
+        }
+
+        let inner_locked = self.inner.read();
+        self.frame_to_image(
+            inner_locked.frame.clone(),
+            inner_locked.frame_dimensions,
+        )
+    }
+
+    /// Converts a completed RGBA `frame` of `frame_dimensions` into an image,
+    /// making it opaque unless alpha is to be preserved in screenshots.
+    fn frame_to_image(&self, mut frame: Vec<u8>, frame_dimensions: (u32, u32)) -> RgbaImage {
+        if !self.configuration.screenshot.preserve_alpha {
+            for pixel in frame.chunks_exact_mut(4) {
+                pixel[3] = 255;
+            }
+        }
+
+        // PANIC SAFETY: A completed frame always has `frame_dimensions` pixels.
+        RgbaImage::from_vec(frame_dimensions.0, frame_dimensions.1, frame)
+            .expect("BUG: completed frame should match its dimensions.")
+    }
+
+    /// Returns a copy of the full-precision rendered frame as an [`Rgb32FImage`],
+    /// or `None` if no floating-point screenshot format (or 16-bit PNG) is configured.
+    ///
+    /// Like [`Self::render_to_image`], the scene is only rendered again if needed.
+    pub fn render_to_hdr_image(&self) -> Option<Rgb32FImage> {
+        // The HDR frame is not double-buffered, so keep the buffers locked
+        // until it is copied (another render could overwrite it otherwise).
+        let mut render_buffers = self.render_buffers.lock();
+
+        let needs_full_render = {
+            let inner_locked = self.inner.read();
+            inner_locked.pending_rerender || inner_locked.showing_reduced_quality_frame
+        };
+
+        if needs_full_render {
+            self.render_into_buffers(&self.inner, &mut render_buffers, 1);
+        }
+
+        let (frame_width, frame_height) = render_buffers.render_dimensions;
+        let hdr_frame = render_buffers.hdr_frame.as_ref()?;
+
+        // PANIC SAFETY: The HDR frame buffer is always allocated with `render_dimensions` pixels.
+        Some(
+            Rgb32FImage::from_vec(frame_width, frame_height, hdr_frame.clone())
+                .expect("BUG: HDR frame buffer should match the render dimensions."),
+        )
+    }
+
+    /// Moves the camera to `camera_position`, looking at `camera_look_target`,
+    /// and schedules a rerender.
+    pub fn set_camera_pose(&self, camera_position: Point3<f32>, camera_look_target: Point3<f32>) {
+        let mut inner_locked = self.inner.write();
+
+        inner_locked.camera_position = camera_position;
+        inner_locked.camera_look_target = camera_look_target;
+        inner_locked.mark_camera_changed();
+    }
+
+    /// Moves the camera to `camera_position`, looking at `camera_look_target` with its up vector
+    /// as close to `up_vector` as possible, and schedules a rerender.
+    ///
+    /// Unlike [`Self::set_camera_pose`], the camera's orthonormal basis is rebuilt around
+    /// the given up vector instead of the current one.
+    #[allow(dead_code)]
+    pub fn set_camera(
+        &self,
+        camera_position: Point3<f32>,
+        camera_look_target: Point3<f32>,
+        up_vector: Vector3<f32>,
+    ) {
+        let mut inner_locked = self.inner.write();
+
+        let forward_vector = (camera_look_target - camera_position).normalize();
+        let (side_vector, up_vector) = get_camera_side_and_up_vectors(&forward_vector, &up_vector);
+
+        inner_locked.camera_position = camera_position;
+        inner_locked.camera_look_target = camera_look_target;
+        inner_locked.forward_vector = forward_vector;
+        inner_locked.side_vector = side_vector;
+        inner_locked.up_vector = up_vector;
+        inner_locked.mark_camera_changed();
+    }
+
+    /// Returns the current camera position.
+    #[allow(dead_code)]
+    pub fn camera_position(&self) -> Point3<f32> {
+        self.inner.read().camera_position
+    }
+
+    /// Returns the point the camera is currently looking at.
+    #[allow(dead_code)]
+    pub fn camera_look_target(&self) -> Point3<f32> {
+        self.inner.read().camera_look_target
+    }
+
+    /// Returns the camera's current (normalized) up vector, which is perpendicular
+    /// to the viewing direction.
+    #[allow(dead_code)]
+    pub fn camera_up_vector(&self) -> Vector3<f32> {
+        self.inner.read().up_vector
+    }
+
+    /// Replaces the world-space clip planes (splats outside any of them are culled)
+    /// and schedules a rerender.
+    pub fn set_clip_planes(&self, clip_planes: Vec<ClipPlane>) {
+        let mut inner_locked = self.inner.write();
+
+        inner_locked.clip_planes = clip_planes;
+        inner_locked.mark_camera_changed();
+    }
+
+    /// Restores the camera position, look target and orientation
+    /// to the ones the renderer was constructed with.
+    pub fn reset_camera(&self) {
+        let mut inner_locked = self.inner.write();
+
+        inner_locked.camera_position = self.initial_camera.camera_position;
+        inner_locked.camera_look_target = self.initial_camera.camera_look_target;
+        inner_locked.forward_vector = self.initial_camera.forward_vector;
+        inner_locked.side_vector = self.initial_camera.side_vector;
+        inner_locked.up_vector = self.initial_camera.up_vector;
+        inner_locked.mark_camera_changed();
+    }
+
+    /// Returns the file name (without extension) to save the next screenshot as:
+    /// either the configured fixed name or one based on the current time.
+    fn get_screenshot_file_stem(&self) -> String {
+        if let Some(screenshot_name) = self.configuration.screenshot.screenshot_name.as_deref() {
+            let screenshot_extension = format!(
+                ".{}",
+                self.configuration
+                    .screenshot
+                    .screenshot_format
+                    .file_extension()
+            );
+
+            return screenshot_name
+                .strip_suffix(&screenshot_extension)
+                .unwrap_or(screenshot_name)
+                .to_string();
+        }
+
+
+        let screenshot_time_string = Local::now().format("%Y-%m-%d_%H-%M-%S-%3f");
+
+        format!("nrg-screenshot_{}", screenshot_time_string)
+    }
+
+    /// Renders (if needed) and saves a screenshot into the screenshot directory,
+    /// either with the configured fixed name or named by the current time.
+    pub fn save_screenshot_to_disk(&self) {
+        self.save_screenshot_to_disk_as(&self.get_screenshot_file_stem());
+    }
+
+    /// Renders the scene at `render_width` by `render_height` pixels (see [`Self::render_at`])
+    /// and saves it as a screenshot, named like in [`Self::save_screenshot_to_disk`].
+    pub fn save_screenshot_to_disk_at(&self, render_width: u32, render_height: u32) {
+        self.save_screenshot(
+            &self.get_screenshot_file_stem(),
+            Some((render_width, render_height)),
+        );
+    }
+
+    /// Renders (if needed) and saves a screenshot into the screenshot directory.
+    /// The file extension is appended to `screenshot_file_stem` based on the configured format.
+    pub fn save_screenshot_to_disk_as(&self, screenshot_file_stem: &str) {
+        self.save_screenshot(screenshot_file_stem, None);
+    }
+
+    /// Saves a screenshot of the live frame (rendering it if needed) or, if `render_dimensions`
+    /// are given, of a separate render at that resolution (see [`Self::render_at`]),
+    /// in the configured screenshot format.
+    fn save_screenshot(&self, screenshot_file_stem: &str, render_dimensions: Option<(u32, u32)>) {
+        let screenshot_format = self.configuration.screenshot.screenshot_format;
+
+        let screenshot_name = format!(
+            "{}.{}",
+            screenshot_file_stem,
+            screenshot_format.file_extension()
+        );
+
+        let full_screenshot_path = self
+            .configuration
+            .screenshot
+            .screenshot_path(&screenshot_name);
+
+
+        let (image, hdr_image) = match render_dimensions {
+            Some((render_width, render_height)) => {
+                self.render_detached_at(render_width, render_height)
+            }
+            None => {
+                let image = self.render_to_image();
+                let hdr_image = self
+                    .configuration
+                    .screenshot
+                    .needs_full_precision_frame()
+                    .then(|| self.render_to_hdr_image())
+                    .flatten();
+
+                (image, hdr_image)
+            }
+        };
+
+        let save_result = match (screenshot_format, hdr_image) {
+            (ScreenshotFormat::Png, Some(hdr_image))
+                if self.configuration.screenshot.screenshot_bit_depth == 16 =>
+            {
+                get_16_bit_image(&hdr_image, &image)
+                    .save_with_format(full_screenshot_path, ImageFormat::Png)
+            }
+            (ScreenshotFormat::Png, _) => {
+                image.save_with_format(full_screenshot_path, ImageFormat::Png)
+            }
+            (ScreenshotFormat::Hdr | ScreenshotFormat::Exr, hdr_image) => {
+                let image_format = if screenshot_format == ScreenshotFormat::Hdr {
+                    ImageFormat::Hdr
+                } else {
+                    ImageFormat::OpenExr
+                };
+
+                // PANIC SAFETY: The HDR frame is always allocated when a floating-point format is configured.
+                hdr_image
+                    .expect(
+                        "BUG: HDR frame should be allocated for floating-point screenshot formats.",
+                    )
+                    .save_with_format(full_screenshot_path, image_format)
+            }
+        };
+
+        if let Err(save_error) = save_result {
+            error!(
+                "Failed to save screenshot: erorred while saving as {}: {:?}",
+                screenshot_format.file_extension().to_uppercase(),
+                save_error
+            );
+            return;
+        }
+
+        let (image_width, image_height) = image.dimensions();
+        info!(
+            "Screenshot ({}x{}) saved to disk as {}.",
+            image_width, image_height, screenshot_name
+        );
+    }
+}
+
+impl PixelSurfaceRenderer for SplatRenderer {
+    fn draw(&self, frame: &mut [u8]) {
+        let inner_locked = self.inner.read();
+
+        // Right after a resize, the completed frame is still of the old size
+        // until the next render finishes. Keep showing whatever is on the surface until then.
+        if inner_locked.frame.len() == frame.len() {
+            frame.copy_from_slice(&inner_locked.frame);
+        }
+    }
+
+    fn needs_render(&self) -> bool {
+        let inner_locked = self.inner.read();
+
+        // A reduced-quality frame is replaced once the camera stops moving.
+        inner_locked.pending_rerender
+            || (inner_locked.showing_reduced_quality_frame && inner_locked.is_camera_settled())
+    }
+
+    fn render(&self) {
+        let splat_stride = self.get_interactive_splat_stride(&self.inner.read());
+
+        debug!("Resolving pending rerender.");
+        let time_render_start = Instant::now();
+        self.render_in_place_with_splat_stride(splat_stride);
+        debug!(
+            "Rerender took {} milliseconds.",
+            (time_render_start.elapsed().as_secs_f64() * 1000.0).round() as u32
+        );
+    }
+
+    fn resize(&self, width: u32, height: u32) -> (u32, u32) {
+        self.set_render_dimensions(width, height)
+    }
+
+    fn pending_refinement_delay(&self) -> Option<Duration> {
+        let inner_locked = self.inner.read();
+
+        inner_locked
+            .showing_reduced_quality_frame
+            .then(|| CAMERA_SETTLE_DELAY.saturating_sub(inner_locked.last_camera_change.elapsed()))
+    }
+}
+
+#[cfg(feature = "ui")]
+impl SplatRenderer {
+    /// Enables turntable mode, in which the camera continuously orbits its look target around
+    /// the world up axis at `degrees_per_second` (see [`InteractiveRenderer::advance_animation`]),
+    /// or disables it if `None`.
+    pub fn set_turntable_speed(&self, degrees_per_second: Option<f32>) {
+        self.inner.write().turntable_degrees_per_second = degrees_per_second;
+    }
+
+    /// Handles the key actions that move the camera or toggle rendering options,
+    /// `input_key` being the key that was pressed (used for logging).
+    fn handle_camera_key_action(&self, key_action: KeyAction, input_key: &str) {
+        let move_camera_by = self.camera_movement_speed;
+
+        let mut inner_locked = self.inner.write();
+
+
+        // Movement is relative to the current camera orientation.
+        let forward_vector = inner_locked.forward_vector;
+        let side_vector = inner_locked.side_vector;
+        let up_vector = inner_locked.up_vector;
+
+        match key_action {
+            KeyAction::MoveBackward => {
+                info!(
+                    "User pressed \"{}\", moving camera backwards by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.camera_position -= forward_vector * move_camera_by;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::MoveForward => {
+                info!(
+                    "User pressed \"{}\", moving camera forwards by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.camera_position += forward_vector * move_camera_by;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::MoveLeft => {
+                info!(
+                    "User pressed \"{}\", moving camera left by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.camera_position -= side_vector * move_camera_by;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::MoveRight => {
+                info!(
+                    "User pressed \"{}\", moving camera right by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.camera_position += side_vector * move_camera_by;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::MoveUp => {
+                info!(
+                    "User pressed \"{}\", moving camera up by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.camera_position += up_vector * move_camera_by;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::MoveDown => {
+                info!(
+                    "User pressed \"{}\", moving camera down by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.camera_position -= up_vector * move_camera_by;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::PanLeft => {
+                info!(
+                    "User pressed \"{}\", panning camera left by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.pan_camera(-side_vector * move_camera_by);
+            }
+            KeyAction::PanRight => {
+                info!(
+                    "User pressed \"{}\", panning camera right by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.pan_camera(side_vector * move_camera_by);
+            }
+            KeyAction::PanUp => {
+                info!(
+                    "User pressed \"{}\", panning camera up by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.pan_camera(up_vector * move_camera_by);
+            }
+            KeyAction::PanDown => {
+                info!(
+                    "User pressed \"{}\", panning camera down by {}.",
+                    input_key, move_camera_by
+                );
+
+                inner_locked.pan_camera(-up_vector * move_camera_by);
+            }
+            KeyAction::SnapToAxisAlignedView(view_index) => {
+                let (view_name, view_direction) = &AXIS_ALIGNED_VIEWS[view_index];
+
+                info!(
+                    "User pressed \"{}\", snapping camera to the {} axis-aligned view.",
+                    input_key, view_name
+                );
+
+                let (camera_position, up_vector) = get_axis_aligned_camera_pose(
+                    inner_locked.camera_position,
+                    inner_locked.camera_look_target,
+                    view_direction,
+                    &self.world_up_vector,
+                );
+
+                inner_locked.camera_position = camera_position;
+                inner_locked.up_vector = up_vector;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::ToggleStatisticsOverlay => {
+                inner_locked.show_statistics_overlay = !inner_locked.show_statistics_overlay;
+
+                info!(
+                    "User pressed \"{}\", statistics overlay is now {}.",
+                    input_key,
+                    if inner_locked.show_statistics_overlay {
+                        "shown"
+                    } else {
+                        "hidden"
+                    }
+                );
+
+                inner_locked.pending_rerender = true;
+            }
+            KeyAction::ToggleWorldAxes => {
+                inner_locked.show_axes = !inner_locked.show_axes;
+
+                info!(
+                    "User pressed \"{}\", world axes are now {}.",
+                    input_key,
+                    if inner_locked.show_axes {
+                        "shown"
+                    } else {
+                        "hidden"
+                    }
+                );
+
+                inner_locked.pending_rerender = true;
+            }
+            KeyAction::IncreaseSplatScale => {
+                inner_locked.splat_scaling_factor *= SPLAT_SCALING_FACTOR_STEP;
+
+                info!(
+                    "User pressed \"{}\", splat scaling factor is now {}.",
+                    input_key, inner_locked.splat_scaling_factor
+                );
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::RollLeft | KeyAction::RollRight => {
+                let roll_angle_in_degrees = if key_action == KeyAction::RollRight {
+                    CAMERA_ROLL_STEP_IN_DEGREES
+                } else {
+                    -CAMERA_ROLL_STEP_IN_DEGREES
+                };
+
+                info!(
+                    "User pressed \"{}\", rolling camera by {} degrees.",
+                    input_key, roll_angle_in_degrees
+                );
+
+                let rolled_up_vector = get_rolled_up_vector(
+                    &forward_vector,
+                    &up_vector,
+                    roll_angle_in_degrees.to_radians(),
+                );
+
+                let (side_vector, up_vector) =
+                    get_camera_side_and_up_vectors(&forward_vector, &rolled_up_vector);
+
+                inner_locked.side_vector = side_vector;
+                inner_locked.up_vector = up_vector;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::WidenFieldOfView | KeyAction::NarrowFieldOfView => {
+                let field_of_view_change_in_degrees = if key_action == KeyAction::WidenFieldOfView {
+                    FIELD_OF_VIEW_STEP_IN_DEGREES
+                } else {
+                    -FIELD_OF_VIEW_STEP_IN_DEGREES
+                };
+
+                inner_locked.vertical_field_of_view_in_degrees = get_clamped_field_of_view(
+                    inner_locked.vertical_field_of_view_in_degrees + field_of_view_change_in_degrees,
+                );
+
+                info!(
+                    "User pressed \"{}\", vertical field of view is now {} degrees.",
+                    input_key, inner_locked.vertical_field_of_view_in_degrees
+                );
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::DecreaseSplatScale => {
+                inner_locked.splat_scaling_factor /= SPLAT_SCALING_FACTOR_STEP;
+
+                info!(
+                    "User pressed \"{}\", splat scaling factor is now {}.",
+                    input_key, inner_locked.splat_scaling_factor
+                );
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::ZoomOut => {
+                info!(
+                    "User pressed \"{}\", zooming outwards.",
+                    input_key
+                );
+
+                let camera_position_movement =
+                    (inner_locked.camera_look_target - inner_locked.camera_position).normalize()
+                        * move_camera_by;
+
+                inner_locked.camera_position -= camera_position_movement;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::ZoomIn => {
+                info!("User pressed \"{}\", zooming inwards.", input_key);
+
+                let camera_position_movement =
+                    (inner_locked.camera_look_target - inner_locked.camera_position).normalize()
+                        * move_camera_by;
+
+                inner_locked.camera_position += camera_position_movement;
+
+                inner_locked.mark_camera_changed();
+            }
+            KeyAction::ToggleHelpOverlay => {
+                inner_locked.show_help_overlay = !inner_locked.show_help_overlay;
+
+                info!(
+                    "User pressed \"{}\", help overlay is now {}.",
+                    input_key,
+                    if inner_locked.show_help_overlay {
+                        "shown"
+                    } else {
+                        "hidden"
+                    }
+                );
+
+                inner_locked.pending_rerender = true;
+            }
+            KeyAction::Quit | KeyAction::SaveScreenshot | KeyAction::ResetCamera => {}
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl InteractiveRenderer for SplatRenderer {
+    fn keymap(&self) -> &Keymap {
+        &self.configuration.keymap
+    }
+
+    fn handle_window_event(&self, window_event: &WindowEvent) -> Result<()> {
+        let move_camera_by = self.camera_movement_speed;
+        let mut user_control = self.user_control.lock();
+
+        match window_event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                if event.logical_key == Key::Named(NamedKey::Control) {
+                    match event.state {
+                        ElementState::Pressed => {
+                            info!("User is holding down Ctrl key.");
+                            user_control.control_key_pressed = true;
+                        }
+                        ElementState::Released => {
+                            info!("User released Ctrl key.");
+                            user_control.control_key_pressed = false;
+                        }
+                    }
+
+                    return Ok(());
+                }
+
+                if event.state != ElementState::Released {
+                    return Ok(());
+                }
+
+
+                let Some(input_key) = get_key_name(&event.logical_key) else {
+                    return Ok(());
+                };
+
+                // While Ctrl is held, only Ctrl shortcuts are handled (unless the camera is being dragged).
+                let control_key = format!("Ctrl+{}", input_key);
+                let control_key_action = user_control
+                    .control_key_pressed
+                    .then(|| self.configuration.keymap.find_action(&control_key))
+                    .flatten();
+
+                let (bound_key, key_action) = match control_key_action {
+                    Some(control_key_action) => (control_key, control_key_action),
+                    None => {
+                        if user_control.control_key_pressed && !user_control.left_mouse_pressed {
+                            return Ok(());
+                        }
+
+                        let Some(key_action) = self.configuration.keymap.find_action(&input_key)
+                        else {
+                            return Ok(());
+                        };
+
+                        (input_key, key_action)
+                    }
+                };
+
+                drop(user_control);
+
+
+                match key_action {
+                    // Quitting is handled by the window manager.
+                    KeyAction::Quit => {}
+                    KeyAction::SaveScreenshot => {
+                        info!(
+                            "User pressed \"{}\", saving screenshot.",
+                            bound_key
+                        );
+                        self.save_screenshot_to_disk();
+                    }
+                    KeyAction::ResetCamera => {
+                        info!(
+                            "User pressed \"{}\", resetting camera.",
+                            bound_key
+                        );
+                        self.reset_camera();
+                    }
+                    _ => self.handle_camera_key_action(key_action, &bound_key),
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                if matches!(button, MouseButton::Left) {
+                    match state {
+                        ElementState::Pressed => {
+                            info!("Left mouse button pressed.");
+
+                            user_control.left_mouse_pressed = true;
+                        }
+                        ElementState::Released => {
+                            info!("Left mouse button released.");
+
+                            user_control.left_mouse_pressed = false;
+                        }
+                    }
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                // Scrolling upwards zooms in. Pixel deltas (e.g. from touchpads)
+                // are converted to lines first.
+                let scrolled_lines = match delta {
+                    MouseScrollDelta::LineDelta(_, vertical_lines) => *vertical_lines,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        (position.y / SCROLL_PIXELS_PER_LINE) as f32
+                    }
+                };
+
+                if scrolled_lines == 0.0 {
+                    return Ok(());
+                }
+
+                info!(
+                    "User scrolled {} lines, zooming by {}.",
+                    scrolled_lines,
+                    scrolled_lines * move_camera_by
+                );
+
+
+                let mut inner_locked = self.inner.write();
+
+                inner_locked.camera_position = get_zoomed_camera_position(
+                    inner_locked.camera_position,
+                    inner_locked.camera_look_target,
+                    scrolled_lines * move_camera_by,
+                );
+
+                inner_locked.mark_camera_changed();
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let previous_cursor_position = user_control
+                    .last_cursor_position
+                    .replace((position.x, position.y));
+
+                if !user_control.left_mouse_pressed {
+                    return Ok(());
+                }
+
+                let Some((previous_x, previous_y)) = previous_cursor_position else {
+                    return Ok(());
+                };
+
+
+                // Dragging to the right or downwards orbits the camera to the left or upwards,
+                // which makes it feel like the scene is being dragged along with the cursor.
+                let orbit_sensitivity = self.configuration.camera.orbit_sensitivity;
+                let yaw_delta = -(position.x - previous_x) as f32 * orbit_sensitivity;
+                let pitch_delta = -(position.y - previous_y) as f32 * orbit_sensitivity;
+
+                if yaw_delta == 0.0 && pitch_delta == 0.0 {
+                    return Ok(());
+                }
+
+
+                let mut inner_locked = self.inner.write();
+
+                inner_locked.camera_position = get_orbited_camera_position(
+                    inner_locked.camera_position,
+                    inner_locked.camera_look_target,
+                    &self.world_up_vector,
+                    yaw_delta,
+                    pitch_delta,
+                );
+                trace!(
+                    "Orbited camera to {:?}.",
+                    inner_locked.camera_position
+                );
+
+                inner_locked.mark_camera_changed();
+            }
+            WindowEvent::CursorLeft { .. } => {
+                info!("Cursor has left the window.");
+
+                user_control.left_mouse_pressed = false;
+                user_control.last_cursor_position = None;
+            }
+            _ => {}
+        };
+
+        Ok(())
+    }
+
+    #[cfg(feature = "egui")]
+    fn control_panel_state(&self) -> ControlPanelState {
+        let inner_locked = self.inner.read();
+
+        ControlPanelState {
+            render_settings: RenderSettings {
+                splat_scaling_factor: inner_locked.splat_scaling_factor,
+                background_color: inner_locked.background_color,
+                vertical_field_of_view_in_degrees: inner_locked.vertical_field_of_view_in_degrees,
+                near_plane: inner_locked.near_plane,
+                far_plane: inner_locked.far_plane,
+            },
+            camera_position: inner_locked.camera_position,
+            last_render_duration: inner_locked.last_render_duration,
+        }
+    }
+
+    #[cfg(feature = "egui")]
+    fn apply_render_settings(&self, render_settings: RenderSettings) {
+        if let Err(clip_planes_error) =
+            crate::configuration::RenderingConfiguration::validate_clip_planes(
+                render_settings.near_plane,
+                render_settings.far_plane,
+            )
+        {
+            warn!(
+                "Ignoring invalid clipping planes: {}",
+                clip_planes_error
+            );
+            return;
+        }
+
+        let mut inner_locked = self.inner.write();
+
+        if inner_locked.background_color != render_settings.background_color {
+            info!(
+                "Background color changed to {:?}.",
+                render_settings.background_color
+            );
+
+            inner_locked.background_color = render_settings.background_color;
+            inner_locked.pending_rerender = true;
+        }
+
+        // The remaining settings change the projection of the splats.
+        if inner_locked.splat_scaling_factor != render_settings.splat_scaling_factor
+            || inner_locked.vertical_field_of_view_in_degrees
+                != render_settings.vertical_field_of_view_in_degrees
+            || inner_locked.near_plane != render_settings.near_plane
+            || inner_locked.far_plane != render_settings.far_plane
+        {
+            debug!(
+                "Render settings changed to {:?}.",
+                render_settings
+            );
+
+            inner_locked.splat_scaling_factor = render_settings.splat_scaling_factor;
+            inner_locked.vertical_field_of_view_in_degrees =
+                render_settings.vertical_field_of_view_in_degrees;
+            inner_locked.near_plane = render_settings.near_plane;
+            inner_locked.far_plane = render_settings.far_plane;
+
+            inner_locked.mark_camera_changed();
+        }
+    }
+
+    fn advance_animation(&self, elapsed: Duration) -> bool {
+        let mut inner_locked = self.inner.write();
+
+        let Some(degrees_per_second) = inner_locked.turntable_degrees_per_second else {
+            return false;
+        };
+
+        inner_locked.camera_position = get_orbited_camera_position(
+            inner_locked.camera_position,
+            inner_locked.camera_look_target,
+            &self.world_up_vector,
+            (degrees_per_second * elapsed.as_secs_f32()).to_radians(),
+            0.0,
+        );
+        inner_locked.mark_camera_changed();
+
+        true
+    }
+
+    fn handle_pixel_click(&self, pixel: (u32, u32)) {
+        let (pixel_x, pixel_y) = pixel;
+
+        let Some(picked_splat_index) = self.try_pick(pixel_x, pixel_y) else {
+            info!(
+                "User clicked pixel ({}, {}), but a render is in progress. Please click again.",
+                pixel_x, pixel_y
+            );
+            return;
+        };
+
+        let Some(splat_index) = picked_splat_index else {
+            info!(
+                "User clicked pixel ({}, {}), no splat is drawn there.",
+                pixel_x, pixel_y
+            );
+            return;
+        };
+
+        let splat = &self.splat_file.splats[splat_index];
+        info!(
+            "User clicked pixel ({}, {}), the nearest splat there is #{}: position ({}, {}, {}), \
+             color RGBA ({}, {}, {}, {}).",
+            pixel_x,
+            pixel_y,
+            splat_index,
+            splat.position.x,
+            splat.position.y,
+            splat.position.z,
+            splat.color.x,
+            splat.color.y,
+            splat.color.z,
+            splat.color.w
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::splat_decoder::{get_testing_splat_scene, DecodeOptions};
+
+    /// Returns the bounding box (`(min_x, min_y, max_x, max_y)`) of the pixels
+    /// with any RGB component brighter than `threshold`.
+    fn get_bright_pixel_bounds(image: &RgbaImage, threshold: u8) -> Option<(u32, u32, u32, u32)> {
+        image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel.0[..3].iter().any(|component| *component > threshold))
+            .fold(None, |bounds, (x, y, _)| {
+                Some(match bounds {
+                    None => (x, y, x, y),
+                    Some((min_x, min_y, max_x, max_y)) => (
+                        min_x.min(x),
+                        min_y.min(y),
+                        max_x.max(x),
+                        max_y.max(y),
+                    ),
+                })
+            })
+    }
+
+    /// Returns a small, opaque, white splat at `position`.
+    fn get_white_splat(position: Vector3<f32>) -> Splat {
+        Splat::new(
+            position,
+            Vector3::new(0.01, 0.01, 0.01),
+            Vector4::new(255, 255, 255, 255),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        )
+    }
+
+
+    /// Returns an opaque splat at `position`, of the given `size` (along every axis) and RGB `color`.
+    fn get_opaque_splat(position: Vector3<f32>, size: f32, color: [u8; 3]) -> Splat {
+        Splat::new(
+            position,
+            Vector3::new(size, size, size),
+            Vector4::new(color[0], color[1], color[2], 255),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        )
+    }
+
+    /// Configuration with the depth test enabled. Splats are sorted by size (largest first),
+    /// so a large near splat is drawn before a small far one and only the depth test can hide it.
+    const DEPTH_TEST_CONFIGURATION: &str = "[rendering]\ndepth_test = true\nsort_key = \"size\"\n";
+
+
+    #[test]
+    fn depth_test_keeps_the_nearer_opaque_splat() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![
+                get_opaque_splat(Vector3::new(0.0, 0.0, 0.5), 0.3, [255, 0, 0]),
+                get_opaque_splat(Vector3::new(0.0, 0.0, -0.5), 0.03, [0, 255, 0]),
+            ]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            DEPTH_TEST_CONFIGURATION,
+        );
+
+        let center_pixel = renderer.render_to_image().get_pixel(32, 32).0;
+
+        assert!(
+            center_pixel[0] > 200 && center_pixel[1] < 50,
+            "center pixel is {:?}",
+            center_pixel
+        );
+    }
+
+    #[test]
+    fn faint_edges_of_opaque_splats_do_not_occlude() {
+        let near_splat = get_opaque_splat(Vector3::new(0.0, 0.0, 0.5), 0.3, [255, 0, 0]);
+        let render_dimensions = (64, 64);
+        let camera_position = Point3::new(0.0, 0.0, 3.0);
+
+        // Find a pixel (on the center row) at the faint edge of the near splat.
+        let near_splat_renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![near_splat.clone()]),
+            render_dimensions,
+            camera_position,
+            Point3::origin(),
+            DEPTH_TEST_CONFIGURATION,
+        );
+        let near_splat_image = near_splat_renderer.render_to_image();
+
+        let edge_pixel_x = (32..64)
+            .find(|x| (5..40).contains(&near_splat_image.get_pixel(*x, 32).0[0]))
+            .expect("near splat should have a faint edge");
+
+
+        // Place a small far splat onto that pixel (the render is square, so the horizontal
+        // field of view equals the vertical one).
+        let far_splat_distance = 3.5;
+        let half_field_of_view = near_splat_renderer
+            .configuration
+            .camera
+            .vertical_field_of_view_in_degrees
+            .to_radians()
+            / 2.0;
+        let far_splat_x =
+            (edge_pixel_x as f32 - 31.5) / 31.5 * far_splat_distance * half_field_of_view.tan();
+
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![
+                near_splat,
+                get_opaque_splat(
+                    Vector3::new(far_splat_x, 0.0, 3.0 - far_splat_distance),
+                    0.03,
+                    [0, 255, 0],
+                ),
+            ]),
+            render_dimensions,
+            camera_position,
+            Point3::origin(),
+            DEPTH_TEST_CONFIGURATION,
+        );
+
+        let edge_pixel = renderer.render_to_image().get_pixel(edge_pixel_x, 32).0;
+
+        assert!(
+            edge_pixel[1] > edge_pixel[0],
+            "pixel at the faint edge is {:?}",
+            edge_pixel
+        );
+    }
+
+    #[test]
+    fn full_precision_frame_holds_linear_colors() {
+        // Blending in sRGB space (the default) must not leak into the full-precision frame.
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_opaque_splat(
+                Vector3::zeros(),
+                0.3,
+                [128, 128, 128],
+            )]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "screenshot_format = \"exr\"\n",
+        );
+
+        let hdr_image = renderer
+            .render_to_hdr_image()
+            .expect("EXR screenshots should keep the full-precision frame");
+        let center_pixel = hdr_image.get_pixel(32, 32).0;
+
+        let expected_component = srgb_to_linear(128.0 / 255.0);
+        for component in center_pixel {
+            assert!(
+                (component - expected_component).abs() < 0.005,
+                "center pixel is {:?}, expected {}",
+                center_pixel,
+                expected_component
+            );
+        }
+    }
+
+    /// Renders ten faint premultiplied white splats stacked at the center (which add up to
+    /// a linear value well above 1.0) with the given `tone_mapping`, returning the center pixel
+    /// of the 8-bit and of the full-precision frame.
+    fn render_over_composited_pixel(tone_mapping: &str) -> ([u8; 4], [f32; 3]) {
+        let faint_splats = (0..10)
+            .map(|splat_index| {
+                Splat::new(
+                    Vector3::new(0.0, 0.0, splat_index as f32 * 0.01),
+                    Vector3::new(0.3, 0.3, 0.3),
+                    Vector4::new(255, 255, 255, 40),
+                    Vector4::new(1.0, 0.0, 0.0, 0.0),
+                )
+            })
+            .collect();
+
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(faint_splats),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            &format!(
+                "screenshot_format = \"exr\"\n\
+                 [rendering]\n\
+                 alpha_mode = \"premultiplied\"\n\
+                 linear_blending = true\n\
+                 tone_mapping = \"{}\"\n",
+                tone_mapping
+            ),
+        );
+
+        let pixel = renderer.render_to_image().get_pixel(32, 32).0;
+        let hdr_pixel = renderer
+            .render_to_hdr_image()
+            .expect("EXR screenshots should keep the full-precision frame")
+            .get_pixel(32, 32)
+            .0;
+
+        (pixel, hdr_pixel)
+    }
+
+    #[test]
+    fn reinhard_tone_mapping_reduces_over_composited_pixels() {
+        let (untone_mapped_pixel, untone_mapped_hdr_pixel) = render_over_composited_pixel("none");
+        let (tone_mapped_pixel, tone_mapped_hdr_pixel) = render_over_composited_pixel("reinhard");
+
+        // Without tone mapping, the full-precision value is kept and the 8-bit frame clips.
+        assert!(
+            untone_mapped_hdr_pixel[0] > 1.5,
+            "untone-mapped full-precision pixel is {:?}",
+            untone_mapped_hdr_pixel
+        );
+        assert_eq!(untone_mapped_pixel[..3], [255, 255, 255]);
+
+        for (tone_mapped_component, untone_mapped_component) in tone_mapped_hdr_pixel
+            .iter()
+            .zip(untone_mapped_hdr_pixel.iter())
+        {
+            let expected_component = untone_mapped_component / (1.0 + untone_mapped_component);
+            assert!(
+                (tone_mapped_component - expected_component).abs() < 1e-4,
+                "tone-mapped full-precision pixel is {:?}, expected {}",
+                tone_mapped_hdr_pixel,
+                expected_component
+            );
+        }
+
+        let expected_component =
+            (linear_to_srgb(tone_mapped_hdr_pixel[0]) * u8::MAX as f32).round() as u8;
+        assert!(expected_component < 255);
+        assert_eq!(
+            tone_mapped_pixel,
+            [
+                expected_component,
+                expected_component,
+                expected_component,
+                untone_mapped_pixel[3]
+            ]
+        );
+    }
+
+    #[test]
+    fn billboard_size_models_at_sample_distances() {
+        let size_at = |size_model, distance_from_camera| {
+            billboard_size_for_distance(size_model, 1.0, distance_from_camera, 10.0)
+        };
+
+        assert_eq!(
+            size_at(BillboardSizeModel::InverseDistance, 2.0),
+            1.0
+        );
+        assert_eq!(
+            size_at(BillboardSizeModel::InverseDistance, 4.0),
+            0.5
+        );
+
+        assert_eq!(
+            size_at(BillboardSizeModel::InverseSquareDistance, 2.0),
+            0.5
+        );
+        assert_eq!(
+            size_at(BillboardSizeModel::InverseSquareDistance, 4.0),
+            0.125
+        );
+
+        assert!((size_at(BillboardSizeModel::ClampedLinear, 2.0) - 1.6).abs() < 1e-6);
+        assert!((size_at(BillboardSizeModel::ClampedLinear, 5.0) - 1.0).abs() < 1e-6);
+        // Beyond the far plane, the size is clamped to zero.
+        assert_eq!(
+            size_at(BillboardSizeModel::ClampedLinear, 12.0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn billboard_size_model_scales_projected_footprints() {
+        let get_rendered_width = |size_model: &str| {
+            let renderer = SplatRenderer::new_for_tests(
+                Splats::from_entries(vec![get_opaque_splat(
+                    Vector3::zeros(),
+                    0.1,
+                    [255, 255, 255],
+                )]),
+                (64, 64),
+                Point3::new(0.0, 0.0, 3.0),
+                Point3::origin(),
+                &format!(
+                    "[rendering]\nbillboard_size_model = \"{}\"\n",
+                    size_model
+                ),
+            );
+
+            let (min_x, _, max_x, _) = get_bright_pixel_bounds(&renderer.render_to_image(), 20)
+                .expect("splat should be visible");
+            max_x - min_x + 1
+        };
+
+        let inverse_distance_width = get_rendered_width("inverse-distance");
+        let inverse_square_distance_width = get_rendered_width("inverse-square-distance");
+
+        // At a distance of 3, the `1 / d²` model is a third of the size of the `1 / d` model.
+        assert!(
+            inverse_square_distance_width * 2 < inverse_distance_width,
+            "inverse-distance width is {}, inverse-square-distance width is {}",
+            inverse_distance_width,
+            inverse_square_distance_width
+        );
+    }
+
+    #[test]
+    fn render_at_uses_the_requested_dimensions() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_white_splat(Vector3::zeros())]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        );
+
+        assert_eq!(
+            renderer.render_at(200, 120).dimensions(),
+            (200, 120)
+        );
+
+        // The live frame keeps its own resolution.
+        assert_eq!(renderer.render_to_image().dimensions(), (64, 64));
+    }
+
+    #[test]
+    fn full_precision_frame_is_rendered_at_the_requested_dimensions() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_white_splat(Vector3::zeros())]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "screenshot_format = \"exr\"\n",
+        );
+
+        let (image, hdr_image) = renderer.render_detached_at(200, 120);
+
+        assert_eq!(image.dimensions(), (200, 120));
+        assert_eq!(
+            hdr_image
+                .expect("EXR screenshots should keep the full-precision frame")
+                .dimensions(),
+            (200, 120)
+        );
+    }
+
+    #[test]
+    fn pick_returns_the_splat_at_the_center_pixel() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![
+                get_opaque_splat(Vector3::new(0.0, 0.0, -0.5), 0.1, [0, 255, 0]),
+                get_opaque_splat(Vector3::new(0.0, 0.0, 0.5), 0.1, [255, 0, 0]),
+            ]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        );
+        renderer.render_in_place();
+
+        // The nearer of the two overlapping splats is picked.
+        assert_eq!(renderer.pick(32, 32), Some(1));
+        assert_eq!(renderer.pick(0, 0), None);
+        assert_eq!(renderer.pick(64, 32), None);
+    }
+
+    #[test]
+    fn try_pick_does_not_wait_for_a_render_in_progress() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_opaque_splat(
+                Vector3::zeros(),
+                0.1,
+                [255, 255, 255],
+            )]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        );
+        renderer.render_in_place();
+
+        assert_eq!(renderer.try_pick(32, 32), Some(Some(0)));
+
+        // Renders hold the buffers locked, so picking is skipped while they are.
+        let _render_buffers = renderer.render_buffers.lock();
+        assert_eq!(renderer.try_pick(32, 32), None);
+    }
+
+    #[test]
+    fn normalized_device_coordinates_only_divide_by_w() {
+        let coordinates =
+            get_normalized_device_coordinates(Vector4::new(0.5, -0.25, 0.3, 2.0)).unwrap();
+
+        assert_eq!(coordinates, Vector2::new(0.25, -0.125));
+    }
+
+    #[test]
+    fn positions_behind_the_camera_have_no_normalized_device_coordinates() {
+        assert!(get_normalized_device_coordinates(Vector4::new(0.5, 0.5, -0.3, -1.0)).is_none());
+        assert!(get_normalized_device_coordinates(Vector4::new(0.5, 0.5, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn ring_of_splats_stays_circular_at_a_wide_aspect_ratio() {
+        let ring_splats = (0..64)
+            .map(|index| {
+                let angle = index as f32 / 64.0 * std::f32::consts::TAU;
+                get_white_splat(Vector3::new(
+                    angle.cos() * 0.5,
+                    angle.sin() * 0.5,
+                    0.0,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(ring_splats),
+            (320, 160),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        );
+
+        let (min_x, min_y, max_x, max_y) = get_bright_pixel_bounds(&renderer.render_to_image(), 64)
+            .expect("ring should be visible");
+
+        let horizontal_extent = (max_x - min_x) as i64;
+        let vertical_extent = (max_y - min_y) as i64;
+
+        assert!(
+            (horizontal_extent - vertical_extent).abs() <= 2,
+            "ring is {}x{} pixels",
+            horizontal_extent,
+            vertical_extent
+        );
+    }
+
+    /// Returns the number of pixels with any RGB component brighter than `threshold`.
+    fn count_bright_pixels(image: &RgbaImage, threshold: u8) -> usize {
+        image
+            .pixels()
+            .filter(|pixel| pixel.0[..3].iter().any(|component| *component > threshold))
+            .count()
+    }
+
+    /// Renders a single white splat with the given `scale` at the origin, seen from a distance of 3.
+    fn render_scaled_splat(scale: Vector3<f32>) -> RgbaImage {
+        render_rotated_splat(scale, Vector4::new(1.0, 0.0, 0.0, 0.0))
+    }
+
+    /// Renders a single white splat with the given `scale` and `rotation` (a quaternion,
+    /// real part first) at the origin, seen from a distance of 3.
+    fn render_rotated_splat(scale: Vector3<f32>, rotation: Vector4<f32>) -> RgbaImage {
+        SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![Splat::new(
+                Vector3::zeros(),
+                scale,
+                Vector4::new(255, 255, 255, 255),
+                rotation,
+            )]),
+            (128, 128),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        )
+        .render_to_image()
+    }
+
+    #[test]
+    fn doubling_the_splat_scale_quadruples_the_covered_pixels() {
+        let covered_pixels = count_bright_pixels(&render_scaled_splat(Vector3::repeat(0.05)), 0);
+        let doubled_covered_pixels =
+            count_bright_pixels(&render_scaled_splat(Vector3::repeat(0.1)), 0);
+
+        let ratio = doubled_covered_pixels as f32 / covered_pixels as f32;
+        assert!(
+            (3.5..4.5).contains(&ratio),
+            "splat covers {} pixels, the doubled splat covers {}",
+            covered_pixels,
+            doubled_covered_pixels
+        );
+    }
+
+    #[test]
+    fn non_uniform_splat_scale_elongates_the_footprint() {
+        let (min_x, min_y, max_x, max_y) = get_bright_pixel_bounds(
+            &render_scaled_splat(Vector3::new(0.15, 0.025, 0.025)),
+            0,
+        )
+        .expect("splat should be visible");
+
+        let width = max_x - min_x + 1;
+        let height = max_y - min_y + 1;
+        assert!(
+            width > height * 3,
+            "footprint is {}x{} pixels",
+            width,
+            height
+        );
+    }
+
+    #[test]
+    fn rotating_an_elongated_splat_by_90_degrees_transposes_its_footprint() {
+        // Six times longer along x than along y and z.
+        let scale = Vector3::new(0.15, 0.025, 0.025);
+        let half_angle = std::f32::consts::FRAC_PI_4;
+
+        let get_footprint_size = |rotation: Vector4<f32>| {
+            let (min_x, min_y, max_x, max_y) =
+                get_bright_pixel_bounds(&render_rotated_splat(scale, rotation), 0)
+                    .expect("splat should be visible");
+
+            (max_x - min_x + 1, max_y - min_y + 1)
+        };
+
+        let (width, height) = get_footprint_size(Vector4::new(1.0, 0.0, 0.0, 0.0));
+        let (rotated_width, rotated_height) = get_footprint_size(Vector4::new(
+            half_angle.cos(),
+            0.0,
+            0.0,
+            half_angle.sin(),
+        ));
+
+        assert!(
+            width > height * 3,
+            "footprint is {}x{} pixels",
+            width,
+            height
+        );
+        assert_eq!(
+            (rotated_width, rotated_height),
+            (height, width),
+            "rotated footprint is {}x{} pixels, unrotated is {}x{} pixels",
+            rotated_width,
+            rotated_height,
+            width,
+            height
+        );
+    }
+
+    #[test]
+    fn degenerate_rotation_falls_back_to_the_square_footprint() {
+        let splat = Splat::new(
+            Vector3::zeros(),
+            Vector3::new(0.15, 0.025, 0.025),
+            Vector4::new(255, 255, 255, 255),
+            Vector4::zeros(),
+        );
+
+        assert_eq!(
+            get_billboard_footprint(
+                &splat,
+                &Vector3::new(0.0, 0.0, -3.0),
+                &Matrix3::identity(),
+                (100.0, 100.0),
+                1.0,
+                12,
+                BillboardShape::Square,
+            ),
+            BillboardFootprint::Square {
+                size_in_pixels: 12,
+                shape: BillboardShape::Square,
+            }
+        );
+
+        // The rendered footprint is square, even though the splat's scale is not uniform.
+        let (min_x, min_y, max_x, max_y) = get_bright_pixel_bounds(
+            &render_rotated_splat(splat.scale, splat.rotation),
+            0,
+        )
+        .expect("splat should be visible");
+        assert_eq!(max_x - min_x, max_y - min_y);
+    }
+
+    #[test]
+    fn gaussian_falloff_decays_from_the_center() {
+        let footprint = BillboardFootprint::Square {
+            size_in_pixels: 20,
+            shape: BillboardShape::Square,
+        };
+        let falloff_at = |x: f32| get_gaussian_falloff(Vector2::new(x, 0.0), &footprint, 0.5);
+
+        assert_eq!(falloff_at(0.0), 1.0);
+        assert!(falloff_at(2.0) > falloff_at(5.0));
+        assert!(falloff_at(5.0) > falloff_at(10.0));
+        // The edge of the footprint lies at two standard deviations.
+        assert!((falloff_at(10.0) - (-2.0f32).exp()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gaussian_falloff_is_cut_off_after_three_sigmas() {
+        let footprint = BillboardFootprint::Square {
+            size_in_pixels: 20,
+            shape: BillboardShape::Square,
+        };
+
+        // With a sigma of 0.5, three standard deviations lie 15 pixels away from the center.
+        assert!(get_gaussian_falloff(Vector2::new(14.9, 0.0), &footprint, 0.5) > 0.0);
+        assert_eq!(
+            get_gaussian_falloff(Vector2::new(15.1, 0.0), &footprint, 0.5),
+            0.0
+        );
+    }
+
+    #[test]
+    fn splats_render_with_soft_edges() {
+        let image = render_scaled_splat(Vector3::repeat(0.1));
+
+        let center_row = (0..128)
+            .map(|x| image.get_pixel(x, 64).0[0])
+            .collect::<Vec<_>>();
+
+        // The brightness rises monotonically towards the center, through intermediate values.
+        assert!(center_row[64] > 250);
+        assert!(center_row[64..].windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(
+            center_row
+                .iter()
+                .filter(|component| (20..235).contains(*component))
+                .count()
+                >= 4,
+            "center row is {:?}",
+            center_row
+        );
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn movement_follows_the_view_direction() {
+        let camera_position = Point3::new(2.0, 0.0, 2.0);
+        let camera_look_target = Point3::new(0.0, 1.0, 0.0);
+
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            camera_position,
+            camera_look_target,
+            "",
+        );
+        let forward_vector = (camera_look_target - camera_position).normalize();
+
+        renderer.handle_camera_key_action(KeyAction::MoveForward, "w");
+        let moved_by = renderer.camera_position() - camera_position;
+
+        assert!(
+            (moved_by - forward_vector * renderer.camera_movement_speed).norm() < 1e-5,
+            "camera moved by {:?}, forward is {:?}",
+            moved_by,
+            forward_vector
+        );
+
+
+        // Sideways movement stays perpendicular to the view direction.
+        renderer.handle_camera_key_action(KeyAction::MoveRight, "d");
+        let moved_sideways_by = renderer.camera_position() - camera_position - moved_by;
+
+        assert!(moved_sideways_by.dot(&forward_vector).abs() < 1e-5);
+        assert!((moved_sideways_by.norm() - renderer.camera_movement_speed).abs() < 1e-5);
+    }
+
+    #[test]
+    fn empty_scene_renders_the_background_color() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(Vec::new()),
+            (32, 24),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "[rendering]\nbackground_color = \"#3366CC\"\n",
+        );
+
+        let image = renderer.render_to_image();
+
+        assert!(
+            image
+                .pixels()
+                .all(|pixel| pixel.0 == [0x33, 0x66, 0xCC, 0xFF]),
+            "rendered pixels do not all match the background color"
+        );
+    }
+
+    #[test]
+    fn positions_behind_the_camera_are_outside_the_depth_frustum() {
+        assert!(is_clip_space_position_inside_depth_frustum(
+            &Vector4::new(0.0, 0.0, 0.5, 1.0)
+        ));
+
+        assert!(!is_clip_space_position_inside_depth_frustum(
+            &Vector4::new(0.0, 0.0, 0.5, -1.0)
+        ));
+        assert!(!is_clip_space_position_inside_depth_frustum(
+            &Vector4::new(0.0, 0.0, 0.0, 0.0)
+        ));
+        // Beyond the far plane.
+        assert!(!is_clip_space_position_inside_depth_frustum(
+            &Vector4::new(0.0, 0.0, 1.5, 1.0)
+        ));
+        assert!(!is_clip_space_position_inside_depth_frustum(
+            &Vector4::new(0.0, 0.0, f32::NAN, 1.0)
+        ));
+    }
+
+    #[test]
+    fn splat_directly_behind_the_camera_is_culled() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![
+                get_opaque_splat(Vector3::zeros(), 0.1, [255, 255, 255]),
+                get_opaque_splat(Vector3::new(0.0, 0.0, 5.0), 0.1, [255, 0, 0]),
+            ]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        );
+
+        let render_stats = renderer.render_in_place();
+
+        assert_eq!(render_stats.visible_splats, 1);
+        assert_eq!(render_stats.culled_splats, 1);
+
+        // Only the splat in front of the camera is drawn.
+        let image = renderer.render_to_image();
+        assert!(image
+            .pixels()
+            .all(|pixel| pixel.0[0] == pixel.0[1] && pixel.0[1] == pixel.0[2]));
+    }
+
+    #[test]
+    fn degenerate_projected_coordinates_map_to_no_pixel() {
+        let pixel_for = |projected_position| {
+            get_pixel_coordinates_from_projected_coordinates(projected_position, 64, 64, true)
+                .pixel()
+        };
+
+        assert_eq!(pixel_for(Vector4::new(0.5, 0.5, 0.5, 0.0)), None);
+        assert_eq!(pixel_for(Vector4::new(0.5, 0.5, 0.0, 0.0)), None);
+        assert_eq!(pixel_for(Vector4::new(0.5, 0.5, 0.5, 1e-8)), None);
+        assert_eq!(
+            pixel_for(Vector4::new(f32::NAN, 0.5, 0.5, 1.0)),
+            None
+        );
+        assert_eq!(
+            pixel_for(Vector4::new(f32::INFINITY, 0.5, 0.5, 1.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn offscreen_projected_coordinates_stay_inside_the_viewport() {
+        let far_offscreen_position = Vector4::new(1e30, -1e30, 0.5, 1.0);
+
+        assert_eq!(
+            get_pixel_coordinates_from_projected_coordinates(far_offscreen_position, 64, 48, true)
+                .pixel(),
+            Some((63, 0))
+        );
+        assert_eq!(
+            get_pixel_coordinates_from_projected_coordinates(far_offscreen_position, 64, 48, false),
+            ProjectedPixelCoordinates::Outside
+        );
+    }
+
+    #[test]
+    fn projection_uses_the_configured_field_of_view() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "[camera]\nvertical_field_of_view = 60.0\n",
+        );
+
+        // The projection scales y by `1 / tan(fov / 2)`.
+        let implied_field_of_view_in_degrees =
+            (2.0 * (1.0 / renderer.projection_matrix()[(1, 1)]).atan()).to_degrees();
+
+        assert!(
+            (implied_field_of_view_in_degrees - 60.0).abs() < 1e-3,
+            "implied vertical field of view is {} degrees",
+            implied_field_of_view_in_degrees
+        );
+    }
+
+    #[test]
+    fn tiled_compositing_matches_serial_compositing() {
+        let render_dimensions = (96, 80);
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            render_dimensions,
+            Point3::new(0.15, 0.12, 0.2),
+            Point3::origin(),
+            "",
+        );
+
+        let tiled_image = renderer.render_to_image();
+
+
+        // Composite the same splats onto the whole frame at once, as a single tile.
+        let mut prepared_splats = renderer.project_splats_with_current_camera();
+        renderer.sort_projected_splats(&mut prepared_splats);
+
+        let pixel_count = (render_dimensions.0 * render_dimensions.1) as usize;
+        let mut serial_frame = [0, 0, 0, 255].repeat(pixel_count);
+        let mut serial_depth_buffer = vec![f32::INFINITY; pixel_count];
+
+        let rendering_configuration = &renderer.configuration.rendering;
+        composite_splats_onto_tile(
+            FrameTile {
+                rows: 0..render_dimensions.1,
+                frame: &mut serial_frame,
+                depth_buffer: &mut serial_depth_buffer,
+                hdr_frame: None,
+            },
+            &prepared_splats,
+            &(0..prepared_splats.len() as u32).collect::<Vec<_>>(),
+            &CompositingParameters {
+                viewport_size: render_dimensions,
+                render_mode: rendering_configuration.render_mode,
+                falloff_sigma: rendering_configuration.gaussian_falloff_sigma,
+                depth_test_enabled: false,
+                linear_blending: rendering_configuration.blends_in_linear_color_space(),
+                linear_input_colors: false,
+                alpha_mode: rendering_configuration.alpha_mode,
+            },
+        );
+
+        // The splats must span several tiles for the comparison to be meaningful.
+        let (_, min_y, _, max_y) =
+            get_bright_pixel_bounds(&tiled_image, 0).expect("scene should be visible");
+        assert!(max_y - min_y > COMPOSITING_TILE_HEIGHT_IN_ROWS * 2);
+
+        assert!(
+            tiled_image.as_raw() == &serial_frame,
+            "tiled and serial compositing produced different frames"
+        );
+    }
+
+    #[test]
+    fn projection_is_reused_while_the_camera_is_untouched() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+
+        let first_render_stats = renderer.render_in_place();
+        assert!(first_render_stats.timings.preparation > Duration::ZERO);
+        assert!(!renderer.inner.read().pending_reprojection);
+
+
+        // Changing only the background color needs a new frame, but not a new projection.
+        {
+            let mut inner_locked = renderer.inner.write();
+            inner_locked.background_color = [0, 0, 255, 255];
+            inner_locked.pending_rerender = true;
+        }
+
+        let recomposited_render_stats = renderer.render_in_place();
+
+        assert_eq!(
+            recomposited_render_stats.timings.preparation,
+            Duration::ZERO
+        );
+        assert_eq!(
+            recomposited_render_stats.timings.sorting,
+            Duration::ZERO
+        );
+        assert_eq!(
+            recomposited_render_stats.visible_splats,
+            first_render_stats.visible_splats
+        );
+        assert_eq!(
+            renderer.render_to_image().get_pixel(0, 0).0,
+            [0, 0, 255, 255]
+        );
+
+
+        // Moving the camera invalidates the cached projection.
+        renderer.set_camera_pose(Point3::new(0.35, 0.24, 0.4), Point3::origin());
+        assert!(renderer.inner.read().pending_reprojection);
+        assert!(renderer.render_in_place().timings.preparation > Duration::ZERO);
+    }
+
+    #[test]
+    fn srgb_conversions_are_inverse() {
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+        assert!((srgb_to_linear(0.5) - 0.214).abs() < 1e-3);
+
+        for component in [0.01, 0.2, 0.5, 0.8] {
+            assert!((linear_to_srgb(srgb_to_linear(component)) - component).abs() < 1e-5);
+        }
+    }
+
+    /// Renders a half-transparent green splat in front of an opaque red one
+    /// and returns the center pixel.
+    fn render_half_transparent_blend(linear_blending: bool) -> [u8; 4] {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![
+                get_opaque_splat(Vector3::new(0.0, 0.0, -0.5), 0.3, [255, 0, 0]),
+                Splat::new(
+                    Vector3::new(0.0, 0.0, 0.5),
+                    Vector3::new(0.3, 0.3, 0.3),
+                    Vector4::new(0, 255, 0, 128),
+                    Vector4::new(1.0, 0.0, 0.0, 0.0),
+                ),
+            ]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            &format!(
+                "[rendering]\nlinear_blending = {}\n",
+                linear_blending
+            ),
+        );
+
+        renderer.render_to_image().get_pixel(32, 32).0
+    }
+
+    #[test]
+    fn linear_blending_changes_half_transparent_blends() {
+        let srgb_blend = render_half_transparent_blend(false);
+        let linear_blend = render_half_transparent_blend(true);
+
+        assert_ne!(srgb_blend, linear_blend);
+
+        // Blending halfway in linear space is brighter once encoded back into sRGB.
+        let expected_linear_component = (linear_to_srgb(0.5) * 255.0).round();
+        for component in [linear_blend[0], linear_blend[1]] {
+            assert!(
+                (component as f32 - expected_linear_component).abs() <= 3.0,
+                "linear blend is {:?}",
+                linear_blend
+            );
+        }
+        for component in [srgb_blend[0], srgb_blend[1]] {
+            assert!(
+                (component as f32 - 127.5).abs() <= 3.0,
+                "sRGB blend is {:?}",
+                srgb_blend
+            );
+        }
+    }
+
+    /// Renders a half-transparent grey splat over a black background with the given
+    /// `input_color_space` and returns the center pixel.
+    fn render_half_transparent_splat_in_color_space(input_color_space: &str) -> [u8; 4] {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![Splat::new(
+                Vector3::zeros(),
+                Vector3::new(0.3, 0.3, 0.3),
+                Vector4::new(128, 128, 128, 128),
+                Vector4::new(1.0, 0.0, 0.0, 0.0),
+            )]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            &format!(
+                "[rendering]\nbackground_color = \"#000000\"\ninput_color_space = \"{}\"\n",
+                input_color_space
+            ),
+        );
+
+        renderer.render_to_image().get_pixel(32, 32).0
+    }
+
+    #[test]
+    fn linear_input_colors_are_srgb_encoded() {
+        let srgb_pixel = render_half_transparent_splat_in_color_space("srgb");
+        let linear_pixel = render_half_transparent_splat_in_color_space("linear");
+
+        assert_ne!(srgb_pixel, linear_pixel);
+
+        let coverage = 128.0 / 255.0;
+        let composited_component = 128.0 / 255.0 * coverage;
+
+        // sRGB input colors are composited as they are.
+        let expected_srgb_component = composited_component * 255.0;
+        // Linear input colors are composited, then sRGB-encoded (which brightens them).
+        let expected_linear_component = linear_to_srgb(composited_component) * 255.0;
+
+        for component in &srgb_pixel[..3] {
+            assert!(
+                (*component as f32 - expected_srgb_component).abs() <= 3.0,
+                "sRGB input pixel is {:?}, expected {}",
+                srgb_pixel,
+                expected_srgb_component
+            );
+        }
+        for component in &linear_pixel[..3] {
+            assert!(
+                (*component as f32 - expected_linear_component).abs() <= 3.0,
+                "linear input pixel is {:?}, expected {}",
+                linear_pixel,
+                expected_linear_component
+            );
+        }
+    }
+
+    #[test]
+    fn zero_length_file_renders_the_background() {
+        let temporary_directory_path = std::env::temp_dir().join("nrg-dn1-tests");
+        std::fs::create_dir_all(&temporary_directory_path).unwrap();
+        let empty_file_path = temporary_directory_path.join("empty.splat");
+        std::fs::write(&empty_file_path, []).unwrap();
+
+        let splats = Splats::load_from_file(&empty_file_path, DecodeOptions::default()).unwrap();
+        assert!(splats.is_empty());
+
+        // Without splats, the camera looks at the origin.
+        let renderer = SplatRenderer::new(
+            Configuration::load_for_tests(""),
+            32,
+            32,
+            splats,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(renderer.camera_look_target(), Point3::origin());
+
+        let render_stats = renderer.render_in_place();
+        assert_eq!(render_stats.visible_splats, 0);
+        assert_eq!(render_stats.culled_splats, 0);
+
+        assert!(renderer
+            .render_to_image()
+            .pixels()
+            .all(|pixel| pixel.0 == [0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn camera_fitting_an_empty_scene_looks_at_the_origin() {
+        let (camera_position, camera_look_target) = get_camera_pose_fitting_splats(
+            &Splats::from_entries(Vec::new()),
+            45.0,
+            64,
+            64,
+            0.1,
+        );
+
+        assert_eq!(camera_look_target, Point3::origin());
+        assert!(camera_position
+            .iter()
+            .all(|coordinate| coordinate.is_finite()));
+        assert_ne!(camera_position, camera_look_target);
+    }
+
+    /// Returns the camera position, look target and orthonormal basis (forward, side and up vectors)
+    /// of the renderer's current state.
+    fn get_camera_state(renderer: &SplatRenderer) -> [Vector3<f32>; 5] {
+        let inner_locked = renderer.inner.read();
+
+        [
+            inner_locked.camera_position.coords,
+            inner_locked.camera_look_target.coords,
+            inner_locked.forward_vector,
+            inner_locked.side_vector,
+            inner_locked.up_vector,
+        ]
+    }
+
+    #[test]
+    fn resetting_the_camera_restores_the_initial_state() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+        let initial_camera_state = get_camera_state(&renderer);
+
+        renderer.set_camera(
+            Point3::new(-1.0, 2.0, 0.5),
+            Point3::new(0.1, 0.1, 0.1),
+            Vector3::new(1.0, 0.0, 0.0),
+        );
+        renderer.render_in_place();
+        assert_ne!(get_camera_state(&renderer), initial_camera_state);
+
+        renderer.reset_camera();
+
+        assert_eq!(get_camera_state(&renderer), initial_camera_state);
+        assert!(renderer.inner.read().pending_rerender);
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn resetting_the_camera_after_moving_restores_the_initial_state() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+        let initial_camera_state = get_camera_state(&renderer);
+
+        for key_action in [
+            KeyAction::MoveForward,
+            KeyAction::PanLeft,
+            KeyAction::RollRight,
+            KeyAction::ZoomOut,
+        ] {
+            renderer.handle_camera_key_action(key_action, "");
+        }
+        assert_ne!(get_camera_state(&renderer), initial_camera_state);
+
+        renderer.reset_camera();
+
+        assert_eq!(get_camera_state(&renderer), initial_camera_state);
+    }
+
+    #[test]
+    fn translucent_splats_accumulate_alpha() {
+        let half_transparent_splats = (0..3)
+            .map(|splat_index| {
+                Splat::new(
+                    Vector3::new(0.0, 0.0, splat_index as f32 * 0.1),
+                    Vector3::new(0.3, 0.3, 0.3),
+                    Vector4::new(255, 255, 255, 128),
+                    Vector4::new(1.0, 0.0, 0.0, 0.0),
+                )
+            })
+            .collect();
+
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(half_transparent_splats),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "preserve_alpha = true\n\
+             [rendering]\n\
+             background_color = \"0,0,0,0\"\n",
+        );
+
+        let image = renderer.render_to_image();
+
+        // Over-compositing three splats leaves `(1 - alpha)^3` of the (transparent) background.
+        let splat_alpha = 128.0f32 / 255.0;
+        let expected_alpha = (1.0 - (1.0 - splat_alpha).powi(3)) * 255.0;
+        let center_alpha = image.get_pixel(32, 32).0[3];
+
+        assert!(
+            (center_alpha as f32 - expected_alpha).abs() <= 1.0,
+            "center alpha is {}, expected {}",
+            center_alpha,
+            expected_alpha
+        );
+        assert_eq!(image.get_pixel(0, 0).0[3], 0);
+    }
+
+    #[test]
+    fn splat_right_in_front_of_the_camera_is_clamped_in_size() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_opaque_splat(
+                Vector3::new(0.0, 0.0, 2.8),
+                0.5,
+                [255, 255, 255],
+            )]),
+            (256, 256),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "[rendering]\nmax_billboard_size_pixels = 40\n",
+        );
+
+        let prepared_splats = renderer.project_splats_with_current_camera();
+        assert_eq!(prepared_splats.len(), 1);
+
+        let (half_extent_x, half_extent_y) = prepared_splats[0].footprint.half_extents();
+        assert!(
+            half_extent_x <= 20 && half_extent_y <= 20,
+            "footprint half extents are {}x{}",
+            half_extent_x,
+            half_extent_y
+        );
+    }
+
+    #[test]
+    fn footprints_are_clamped_to_the_size_limits() {
+        let ellipse =
+            BillboardFootprint::from_covariance(Matrix2::new(400.0, 0.0, 0.0, 100.0)).unwrap();
+
+        // The larger side of the bounding box is clamped, keeping the aspect ratio.
+        let (half_extent_x, half_extent_y) = ellipse.clamped_to_size(1, 20).half_extents();
+        assert_eq!(half_extent_x, 10);
+        assert!((5..=6).contains(&half_extent_y));
+
+        let (half_extent_x, half_extent_y) = ellipse.clamped_to_size(80, 200).half_extents();
+        assert_eq!(half_extent_x, 40);
+        assert!((20..=21).contains(&half_extent_y));
+
+        let square = BillboardFootprint::Square {
+            size_in_pixels: 500,
+            shape: BillboardShape::Square,
+        };
+        assert_eq!(
+            square.clamped_to_size(2, 64).half_extents(),
+            (32, 32)
+        );
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn splat_scaling_factor_keys_scale_by_a_step() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+        renderer.render_in_place();
+        let initial_splat_scaling_factor = renderer.inner.read().splat_scaling_factor;
+
+        renderer.handle_camera_key_action(KeyAction::IncreaseSplatScale, "]");
+
+        assert!(
+            (renderer.inner.read().splat_scaling_factor
+                - initial_splat_scaling_factor * SPLAT_SCALING_FACTOR_STEP)
+                .abs()
+                < 1e-6
+        );
+        assert!(renderer.needs_render());
+
+        renderer.render_in_place();
+        renderer.handle_camera_key_action(KeyAction::DecreaseSplatScale, "[");
+        renderer.handle_camera_key_action(KeyAction::DecreaseSplatScale, "[");
+
+        assert!(
+            (renderer.inner.read().splat_scaling_factor
+                - initial_splat_scaling_factor / SPLAT_SCALING_FACTOR_STEP)
+                .abs()
+                < 1e-6
+        );
+        assert!(renderer.needs_render());
+    }
+
+
+    #[test]
+    fn saves_with_a_fixed_screenshot_name_write_to_the_same_path() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_white_splat(Vector3::zeros())]),
+            (32, 32),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "screenshot_name = \"fixed-name-test.png\"\n",
+        );
+
+        let screenshot_path = renderer
+            .configuration
+            .screenshot
+            .screenshot_path("fixed-name-test.png");
+        let _ = std::fs::remove_file(&screenshot_path);
+
+        assert_eq!(
+            renderer.get_screenshot_file_stem(),
+            "fixed-name-test"
+        );
+
+        renderer.save_screenshot_to_disk();
+        let first_screenshot =
+            std::fs::read(&screenshot_path).expect("first screenshot should exist");
+
+        renderer.save_screenshot_to_disk();
+        let second_screenshot =
+            std::fs::read(&screenshot_path).expect("second screenshot should exist");
+
+        assert_eq!(first_screenshot, second_screenshot);
+
+        let screenshots_with_the_name =
+            std::fs::read_dir(&renderer.configuration.screenshot.screenshot_directory_path)
+                .expect("screenshot directory should be readable")
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_string_lossy()
+                        .starts_with("fixed-name-test")
+                })
+                .count();
+
+        assert_eq!(screenshots_with_the_name, 1);
+    }
+
+
+    #[test]
+    fn downsampling_averages_the_supersampled_pixels() {
+        let supersampled_frame = [
+            255, 255, 255, 255, 0, 0, 0, 255, //
+            0, 0, 0, 255, 255, 255, 255, 255,
+        ];
+        let mut frame = [0u8; 4];
+
+        downsample_supersampled_frame(&supersampled_frame, &mut frame, 1, 2);
+
+        assert_eq!(frame, [128, 128, 128, 255]);
+    }
+
+    /// Renders a single white point (see [`RenderMode::Points`]) at 64x64 with the given `ssaa_factor`.
+    fn render_white_point(ssaa_factor: u32) -> RgbaImage {
+        SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_white_splat(Vector3::zeros())]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            &format!(
+                "[rendering]\nrender_mode = \"points\"\nssaa_factor = {}\n",
+                ssaa_factor
+            ),
+        )
+        .render_to_image()
+    }
+
+    #[test]
+    fn supersampling_produces_intermediate_edge_pixels() {
+        let is_intermediate = |pixel: &Rgba<u8>| (1..255).contains(&pixel.0[0]);
+
+        let image = render_white_point(1);
+        assert!(!image.pixels().any(is_intermediate));
+
+        let supersampled_image = render_white_point(2);
+        assert_eq!(supersampled_image.dimensions(), (64, 64));
+        assert!(supersampled_image.pixels().any(is_intermediate));
+    }
+
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn panning_translates_the_camera_and_its_look_target_together() {
+        let camera_position = Point3::new(2.0, 0.5, 2.0);
+        let camera_look_target = Point3::new(0.0, 0.5, 0.0);
+
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            camera_position,
+            camera_look_target,
+            "",
+        );
+        let [_, _, forward_vector, side_vector, up_vector] = get_camera_state(&renderer);
+
+        renderer.handle_camera_key_action(KeyAction::PanRight, "l");
+        renderer.handle_camera_key_action(KeyAction::PanUp, "i");
+
+        let [panned_position, panned_look_target, panned_forward_vector, ..] =
+            get_camera_state(&renderer);
+        let position_delta = panned_position - camera_position.coords;
+        let look_target_delta = panned_look_target - camera_look_target.coords;
+
+        let expected_delta = (side_vector + up_vector) * renderer.camera_movement_speed;
+
+        assert!((position_delta - expected_delta).norm() < 1e-5);
+        assert!((look_target_delta - expected_delta).norm() < 1e-5);
+        assert!(position_delta.dot(&forward_vector).abs() < 1e-5);
+        assert!((panned_forward_vector - forward_vector).norm() < 1e-5);
+    }
+
+
+    #[test]
+    fn position_just_outside_the_right_edge_is_clamped_or_reported_outside() {
+        let just_outside_right_edge = Vector4::new(1.02, 0.0, 0.5, 1.0);
+
+        assert_eq!(
+            get_pixel_coordinates_from_projected_coordinates(just_outside_right_edge, 64, 48, true),
+            ProjectedPixelCoordinates::Clamped((63.0, 23.5))
+        );
+        assert_eq!(
+            get_pixel_coordinates_from_projected_coordinates(just_outside_right_edge, 64, 48, false),
+            ProjectedPixelCoordinates::Outside
+        );
+        assert_eq!(
+            get_pixel_coordinates_from_projected_coordinates(
+                Vector4::new(0.98, 0.0, 0.5, 1.0),
+                64,
+                48,
+                false
+            ),
+            ProjectedPixelCoordinates::Inside((62.37, 23.5))
+        );
+        assert_eq!(
+            get_pixel_coordinates_from_projected_coordinates(
+                Vector4::new(1.02, 0.0, 0.5, -1.0),
+                64,
+                48,
+                true
+            ),
+            ProjectedPixelCoordinates::BehindCamera
+        );
+    }
+
+    #[test]
+    fn splat_just_outside_the_right_edge_is_drawn_on_the_border_when_clamped() {
+        let render_offscreen_splat = |clamp_offscreen: bool| {
+            SplatRenderer::new_for_tests(
+                Splats::from_entries(vec![get_opaque_splat(
+                    Vector3::new(1.3, 0.0, 0.0),
+                    0.02,
+                    [255, 255, 255],
+                )]),
+                (64, 64),
+                Point3::new(0.0, 0.0, 3.0),
+                Point3::origin(),
+                &format!(
+                    "[rendering]\nclamp_offscreen = {}\n",
+                    clamp_offscreen
+                ),
+            )
+            .render_to_image()
+        };
+
+        assert_eq!(
+            count_bright_pixels(&render_offscreen_splat(false), 0),
+            0
+        );
+
+        let clamped_image = render_offscreen_splat(true);
+        let (min_x, _, _, _) =
+            get_bright_pixel_bounds(&clamped_image, 0).expect("clamped splat should be drawn");
+        assert!(
+            min_x >= 60,
+            "clamped splat starts at x = {}",
+            min_x
+        );
+    }
+
+
+    #[test]
+    fn projection_dump_of_the_testing_scene_has_a_row_per_splat() {
+        let splats = get_testing_splat_scene();
+        let splat_count = splats.len();
+
+        let renderer = SplatRenderer::new_for_tests(
+            splats,
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+
+        let dump_directory_path = std::env::temp_dir().join("nrg-dn1-tests");
+        std::fs::create_dir_all(&dump_directory_path)
+            .expect("temporary directory should be creatable");
+        let dump_path = dump_directory_path.join("testing-scene-projection.csv");
+
+        renderer
+            .dump_projection_to_csv(&dump_path)
+            .expect("projection should be dumped");
+
+        let dump = std::fs::read_to_string(&dump_path).expect("projection dump should be readable");
+        let mut lines = dump.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some(
+                "splat_index,world_x,world_y,world_z,clip_x,clip_y,clip_z,clip_w,distance,pixel_x,pixel_y,status"
+            )
+        );
+
+        let rows = lines
+            .map(|line| line.split(',').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(rows.len(), splat_count);
+
+        for (splat_index, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), 12, "row {} is {:?}", splat_index, row);
+            assert_eq!(row[0], splat_index.to_string());
+
+            let has_pixel = !row[9].is_empty() && !row[10].is_empty();
+            match row[11] {
+                "inside" | "clamped" => assert!(has_pixel, "row {} is {:?}", splat_index, row),
+                "offscreen" | "behind-camera" | "depth-culled" | "plane-clipped" => {}
+                status => panic!("unexpected status {}", status),
+            }
+        }
+
+        assert!(rows.iter().any(|row| row[11] == "inside"));
+    }
+
+
+    #[test]
+    fn all_splats_project_inside_the_viewport_after_fitting_the_camera() {
+        for (render_width, render_height) in [(128, 64), (64, 128)] {
+            let splats = get_testing_splat_scene();
+
+            let (camera_position, camera_look_target) =
+                get_camera_pose_fitting_splats(&splats, 45.0, render_width, render_height, 0.1);
+
+            let renderer = SplatRenderer::new_for_tests(
+                splats,
+                (render_width, render_height),
+                camera_position,
+                camera_look_target,
+                "",
+            );
+            let view_projection_matrix = renderer.view_projection_matrix();
+
+            for splat in renderer.splat_file.iter() {
+                let normalized_device_coordinates = get_normalized_device_coordinates(
+                    view_projection_matrix * splat.position.push(1.0),
+                )
+                .expect("splat should be in front of the camera");
+
+                assert!(
+                    normalized_device_coordinates.x.abs() <= 1.0
+                        && normalized_device_coordinates.y.abs() <= 1.0,
+                    "splat at {:?} projects to {:?} at {}x{}",
+                    splat.position,
+                    normalized_device_coordinates,
+                    render_width,
+                    render_height
+                );
+            }
+        }
+    }
+
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn key_1_places_the_camera_on_the_positive_x_axis_of_the_target() {
+        let camera_look_target = Point3::new(0.5, 0.25, 0.0);
+
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.5, 1.25, 2.0),
+            camera_look_target,
+            "",
+        );
+        let [camera_position, ..] = get_camera_state(&renderer);
+        let camera_distance = (camera_position - camera_look_target.coords).norm();
+
+        let key_action = Keymap::default()
+            .find_action("1")
+            .expect("key 1 should be bound");
+        renderer.handle_camera_key_action(key_action, "1");
+
+        let [snapped_position, snapped_look_target, ..] = get_camera_state(&renderer);
+        let expected_position = camera_look_target.coords + Vector3::x() * camera_distance;
+
+        assert!(
+            (snapped_position - expected_position).norm() < 1e-5,
+            "camera snapped to {:?}, expected {:?}",
+            snapped_position,
+            expected_position
+        );
+        assert_eq!(snapped_look_target, camera_look_target.coords);
+
+        // The camera looks down the -X axis, so the look target lies straight ahead of it.
+        let look_target_in_camera_space =
+            renderer.view_matrix() * camera_look_target.to_homogeneous();
+        assert!(
+            (look_target_in_camera_space.xyz() - Vector3::new(0.0, 0.0, -camera_distance)).norm()
+                < 1e-5
+        );
+    }
+
+
+    #[test]
+    fn reduced_quality_render_draws_fewer_splats() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+
+        let full_render_stats = renderer.render_in_place_with_splat_stride(1);
+        let reduced_render_stats = renderer.render_in_place_with_splat_stride(4);
+
+        assert!(full_render_stats.visible_splats > 0);
+        assert!(reduced_render_stats.visible_splats < full_render_stats.visible_splats);
+        assert_eq!(
+            reduced_render_stats.visible_splats + reduced_render_stats.culled_splats,
+            full_render_stats.visible_splats + full_render_stats.culled_splats
+        );
+        assert!(renderer.inner.read().showing_reduced_quality_frame);
+    }
+
+    #[test]
+    fn slow_renders_are_reduced_only_while_the_camera_moves() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "[rendering]\nframe_budget_milliseconds = 10.0\n",
+        );
+
+        let mut inner_locked = renderer.inner.write();
+        inner_locked.last_full_render_duration = Some(Duration::from_millis(45));
+
+        inner_locked.mark_camera_changed();
+        assert_eq!(
+            renderer.get_interactive_splat_stride(&inner_locked),
+            5
+        );
+
+        inner_locked.last_camera_change = Instant::now() - CAMERA_SETTLE_DELAY * 2;
+        assert_eq!(
+            renderer.get_interactive_splat_stride(&inner_locked),
+            1
+        );
+
+        inner_locked.mark_camera_changed();
+        inner_locked.last_full_render_duration = Some(Duration::from_millis(5));
+        assert_eq!(
+            renderer.get_interactive_splat_stride(&inner_locked),
+            1
+        );
+    }
+
+
+    #[test]
+    fn points_mode_sets_one_pixel_per_visible_splat() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (256, 256),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "[rendering]\nrender_mode = \"points\"\nbackground_color = \"#010203\"\n",
+        );
+
+        let image = renderer.render_to_image();
+
+        let drawn_pixels = image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel.0 != [1, 2, 3, 255])
+            .map(|(x, y, _)| (x, y))
+            .collect::<HashSet<_>>();
+        let visible_splat_pixels = renderer
+            .visible_splats()
+            .map(|(_, pixel)| pixel)
+            .collect::<HashSet<_>>();
+
+        assert!(!visible_splat_pixels.is_empty());
+        assert_eq!(drawn_pixels, visible_splat_pixels);
+    }
+
+
+    #[test]
+    fn view_projection_matrix_maps_the_look_target_to_the_center() {
+        let camera_look_target = Point3::new(0.2, -0.1, 0.05);
+
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (128, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            camera_look_target,
+            "",
+        );
+
+        let normalized_device_coordinates = get_normalized_device_coordinates(
+            renderer.view_projection_matrix() * camera_look_target.to_homogeneous(),
+        )
+        .expect("look target should be in front of the camera");
+
+        assert!(
+            normalized_device_coordinates.x.abs() < 1e-5
+                && normalized_device_coordinates.y.abs() < 1e-5,
+            "look target projects to {:?}",
+            normalized_device_coordinates
+        );
+        assert_eq!(
+            renderer.view_projection_matrix(),
+            renderer.projection_matrix() * renderer.view_matrix()
+        );
+    }
+
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn camera_can_be_updated_while_a_render_is_in_flight() {
+        let camera_position = Point3::new(0.3, 0.24, 0.4);
+
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            camera_position,
+            Point3::origin(),
+            "",
+        );
+        renderer.render();
+        assert!(!renderer.needs_render());
+
+        std::thread::scope(|scope| {
+            // A render holds the render buffers until it completes.
+            let render_buffers_guard = renderer.render_buffers.lock();
+
+            renderer.handle_camera_key_action(KeyAction::MoveForward, "w");
+            let background_render = scope.spawn(|| renderer.render());
+
+            // The last completed frame can still be drawn, and the camera moved again.
+            let mut frame = vec![0u8; 64 * 64 * 4];
+            renderer.draw(&mut frame);
+            assert!(frame.iter().any(|component| *component != 0));
+
+            renderer.handle_camera_key_action(KeyAction::MoveForward, "w");
+            assert!(renderer.needs_render());
+
+            drop(render_buffers_guard);
+            background_render
+                .join()
+                .expect("background render should not panic");
+        });
+
+        let moved_by = (renderer.camera_position() - camera_position).norm();
+        assert!((moved_by - 2.0 * renderer.camera_movement_speed).abs() < 1e-5);
+        assert!(!renderer.needs_render());
+    }
+
+
+    /// Renders a half-transparent green splat with color `(0, 128, 0, 128)` over an opaque red one
+    /// with the given `alpha_mode`, returning the center pixel.
+    fn render_premultiplied_green_over_red(alpha_mode: &str) -> [u8; 4] {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![
+                get_opaque_splat(Vector3::new(0.0, 0.0, -0.5), 0.3, [255, 0, 0]),
+                Splat::new(
+                    Vector3::new(0.0, 0.0, 0.5),
+                    Vector3::new(0.3, 0.3, 0.3),
+                    Vector4::new(0, 128, 0, 128),
+                    Vector4::new(1.0, 0.0, 0.0, 0.0),
+                ),
+            ]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            &format!("[rendering]\nalpha_mode = \"{}\"\n", alpha_mode),
+        );
+
+        renderer.render_to_image().get_pixel(32, 32).0
+    }
+
+    #[test]
+    fn premultiplied_colors_are_not_multiplied_by_alpha_again() {
+        let straight_pixel = render_premultiplied_green_over_red("straight");
+        let premultiplied_pixel = render_premultiplied_green_over_red("premultiplied");
+
+        let assert_close_to = |pixel: [u8; 4], expected: [u8; 3]| {
+            assert!(
+                pixel[..3]
+                    .iter()
+                    .zip(expected.iter())
+                    .all(|(component, expected)| component.abs_diff(*expected) <= 3),
+                "pixel is {:?}, expected about {:?}",
+                pixel,
+                expected
+            );
+        };
+
+        // The red splat behind shows through by half in both modes, but only a straight color
+        // is multiplied by its alpha: `out = src + (1 - src_a) * dst` for premultiplied colors.
+        assert_close_to(straight_pixel, [127, 64, 0]);
+        assert_close_to(premultiplied_pixel, [127, 128, 0]);
+    }
+
+
+    #[test]
+    fn looking_straight_down_with_a_vertical_up_vector_gives_a_valid_basis() {
+        let renderer = SplatRenderer::new(
+            Configuration::load_for_tests(""),
+            64,
+            64,
+            Splats::from_entries(vec![get_opaque_splat(
+                Vector3::zeros(),
+                0.3,
+                [255, 255, 255],
+            )]),
+            None,
+            None,
+            None,
+            Some(Point3::new(0.0, 3.0, 0.0)),
+            Some(Point3::origin()),
+            Some(Vector3::new(0.0, 1.0, 0.0)),
+        );
+
+        let [_, _, forward_vector, side_vector, up_vector] = get_camera_state(&renderer);
+
+        for basis_vector in [forward_vector, side_vector, up_vector] {
+            assert!(
+                basis_vector.iter().all(|component| component.is_finite()),
+                "basis is {:?}, {:?}, {:?}",
+                forward_vector,
+                side_vector,
+                up_vector
+            );
+            assert!((basis_vector.norm() - 1.0).abs() < 1e-5);
+        }
+        assert!(forward_vector.dot(&side_vector).abs() < 1e-5);
+        assert!(forward_vector.dot(&up_vector).abs() < 1e-5);
+        assert!(side_vector.dot(&up_vector).abs() < 1e-5);
+
+        assert!(count_bright_pixels(&renderer.render_to_image(), 128) > 0);
+    }
+
+    #[test]
+    fn invalid_up_vector_hint_falls_back_to_a_world_axis() {
+        let forward_vector = Vector3::new(0.0, 0.0, -1.0);
+
+        for up_vector_hint in [Vector3::zeros(), Vector3::new(0.0, 0.0, 2.0)] {
+            let (side_vector, up_vector) =
+                get_camera_side_and_up_vectors(&forward_vector, &up_vector_hint);
+
+            assert!((side_vector.norm() - 1.0).abs() < 1e-5);
+            assert!((up_vector.norm() - 1.0).abs() < 1e-5);
+            assert!(up_vector.dot(&forward_vector).abs() < 1e-5);
+        }
+    }
+
+
+    /// Renders a translucent green splat in front of an opaque red one,
+    /// sorted by the given `sort_key`, returning the center pixel.
+    fn render_translucent_splat_in_front_of_opaque_one(sort_key: &str) -> [u8; 4] {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![
+                get_opaque_splat(Vector3::new(0.0, 0.0, -0.5), 0.3, [255, 0, 0]),
+                Splat::new(
+                    Vector3::new(0.0, 0.0, 0.5),
+                    Vector3::new(0.3, 0.3, 0.3),
+                    Vector4::new(0, 255, 0, 128),
+                    Vector4::new(1.0, 0.0, 0.0, 0.0),
+                ),
+            ]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            &format!("[rendering]\nsort_key = \"{}\"\n", sort_key),
+        );
+
+        renderer.render_to_image().get_pixel(32, 32).0
+    }
+
+    #[test]
+    fn sorting_by_alpha_draws_opaque_splats_last() {
+        // Sorted back to front, the translucent splat in front is blended over the opaque one.
+        let depth_sorted_pixel = render_translucent_splat_in_front_of_opaque_one("depth");
+        assert!(
+            depth_sorted_pixel[1] > 100 && depth_sorted_pixel[0] > 100,
+            "depth-sorted pixel is {:?}",
+            depth_sorted_pixel
+        );
+
+        // Sorted by alpha, the opaque splat is drawn last and covers the translucent one.
+        let alpha_sorted_pixel = render_translucent_splat_in_front_of_opaque_one("alpha");
+        assert!(
+            alpha_sorted_pixel[0] > 250 && alpha_sorted_pixel[1] < 5,
+            "alpha-sorted pixel is {:?}",
+            alpha_sorted_pixel
+        );
+    }
+
+
+    #[test]
+    fn visible_splats_of_the_testing_scene_lie_within_the_viewport() {
+        let splats = get_testing_splat_scene();
+        let splat_count = splats.len();
+
+        let renderer = SplatRenderer::new_for_tests(
+            splats,
+            (96, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+
+        let visible_splats = renderer.visible_splats().collect::<Vec<_>>();
+
+        assert!(!visible_splats.is_empty());
+        for (splat_index, (pixel_x, pixel_y)) in &visible_splats {
+            assert!(*splat_index < splat_count);
+            assert!(
+                *pixel_x < 96 && *pixel_y < 64,
+                "splat {} is centered on ({}, {})",
+                splat_index,
+                pixel_x,
+                pixel_y
+            );
+        }
+
+        // Original indices are preserved, so each splat appears at most once.
+        let unique_splat_indices = visible_splats
+            .iter()
+            .map(|(splat_index, _)| *splat_index)
+            .collect::<HashSet<_>>();
+        assert_eq!(unique_splat_indices.len(), visible_splats.len());
+
+        assert_eq!(
+            visible_splats.len(),
+            renderer.render_in_place().visible_splats
+        );
+    }
+
+
+    /// Renders two half-transparent splats at the same position (red and blue, in the given order),
+    /// returning the center pixel.
+    fn render_equal_depth_splats(red_first: bool) -> [u8; 4] {
+        let get_half_transparent_splat = |color: [u8; 3]| {
+            Splat::new(
+                Vector3::zeros(),
+                Vector3::new(0.3, 0.3, 0.3),
+                Vector4::new(color[0], color[1], color[2], 128),
+                Vector4::new(1.0, 0.0, 0.0, 0.0),
+            )
+        };
+
+        let mut splats = vec![
+            get_half_transparent_splat([255, 0, 0]),
+            get_half_transparent_splat([0, 0, 255]),
+        ];
+        if !red_first {
+            splats.reverse();
+        }
+
+        SplatRenderer::new_for_tests(
+            Splats::from_entries(splats),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        )
+        .render_to_image()
+        .get_pixel(32, 32)
+        .0
+    }
+
+    #[test]
+    fn equal_depth_splats_are_composited_in_file_order() {
+        let red_first_pixel = render_equal_depth_splats(true);
+
+        for _ in 0..8 {
+            assert_eq!(render_equal_depth_splats(true), red_first_pixel);
+        }
+
+        // The splat that comes later in the file is drawn on top.
+        assert!(
+            red_first_pixel[2] > red_first_pixel[0],
+            "pixel is {:?}",
+            red_first_pixel
+        );
+
+        let blue_first_pixel = render_equal_depth_splats(false);
+        assert!(
+            blue_first_pixel[0] > blue_first_pixel[2],
+            "pixel is {:?}",
+            blue_first_pixel
+        );
+    }
+
+
+    #[cfg(feature = "egui")]
+    #[test]
+    fn changing_a_control_panel_setting_schedules_a_rerender() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+        renderer.render_in_place();
+
+        let render_settings = renderer.control_panel_state().render_settings;
+
+        renderer.apply_render_settings(render_settings);
+        assert!(!renderer.inner.read().pending_rerender);
+
+        renderer.apply_render_settings(RenderSettings {
+            background_color: [10, 20, 30, 255],
+            ..render_settings
+        });
+        assert!(renderer.inner.read().pending_rerender);
+
+        renderer.render_in_place();
+        assert!(!renderer.inner.read().pending_rerender);
+
+        renderer.apply_render_settings(RenderSettings {
+            vertical_field_of_view_in_degrees: 60.0,
+            ..render_settings
+        });
+        assert!(renderer.inner.read().pending_rerender);
+        assert!(renderer.inner.read().pending_reprojection);
+    }
+
+
+    #[test]
+    fn checker_backdrop_alternates_tile_colors() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(Vec::new()),
+            (64, 48),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "[rendering]\nbackdrop = \"checker\"\nchecker_tile_size = 8\n",
+        );
+
+        let image = renderer.render_to_image();
+        let [light_color, dark_color] = CHECKER_TILE_COLORS;
+
+        assert_eq!(image.get_pixel(0, 0).0, light_color);
+        assert_eq!(image.get_pixel(7, 7).0, light_color);
+        assert_eq!(image.get_pixel(8, 0).0, dark_color);
+        assert_eq!(image.get_pixel(0, 8).0, dark_color);
+        assert_eq!(image.get_pixel(8, 8).0, light_color);
+        assert_eq!(image.get_pixel(63, 47).0, light_color);
+    }
+
+
+    #[test]
+    fn clip_plane_keeps_the_non_negative_half_space() {
+        let clip_plane = ClipPlane::new(Vector3::new(1.0, 0.0, 0.0), -0.25);
+
+        assert!(clip_plane.contains(&Vector3::new(0.5, 3.0, -2.0)));
+        assert!(clip_plane.contains(&Vector3::new(0.25, 0.0, 0.0)));
+        assert!(!clip_plane.contains(&Vector3::new(0.0, 0.0, 0.0)));
+        assert!(!clip_plane.contains(&Vector3::new(-1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn splats_on_the_negative_side_of_a_clip_plane_are_culled() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(
+                [-0.6, -0.2, 0.2, 0.6]
+                    .into_iter()
+                    .map(|x| get_white_splat(Vector3::new(x, 0.0, 0.0)))
+                    .collect(),
+            ),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        );
+
+        let get_visible_splat_indices = || {
+            renderer
+                .visible_splats()
+                .map(|(splat_index, _)| splat_index)
+                .collect::<HashSet<_>>()
+        };
+
+        assert_eq!(
+            get_visible_splat_indices(),
+            HashSet::from([0, 1, 2, 3])
+        );
+
+        renderer.set_clip_planes(vec![ClipPlane::new(
+            Vector3::new(1.0, 0.0, 0.0),
+            0.0,
+        )]);
+        assert_eq!(get_visible_splat_indices(), HashSet::from([2, 3]));
+    }
+
+
+    /// Saves a screenshot of a single splat with the given PNG `bit_depth` (under a fixed name)
+    /// and returns the color type of the saved file.
+    fn save_png_screenshot_with_bit_depth(bit_depth: u8) -> image::ColorType {
+        let screenshot_name = format!("bit-depth-{}-test.png", bit_depth);
+
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_opaque_splat(
+                Vector3::zeros(),
+                0.3,
+                [255, 128, 0],
+            )]),
+            (32, 32),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            &format!(
+                "screenshot_name = \"{}\"\nscreenshot_bit_depth = {}\n",
+                screenshot_name, bit_depth
+            ),
+        );
+
+        let screenshot_path = renderer
+            .configuration
+            .screenshot
+            .screenshot_path(&screenshot_name);
+        let _ = std::fs::remove_file(&screenshot_path);
+
+        renderer.save_screenshot_to_disk();
+
+        image::open(&screenshot_path)
+            .expect("screenshot should be readable")
+            .color()
+    }
+
+    #[test]
+    fn png_screenshots_have_the_configured_bit_depth() {
+        assert_eq!(
+            save_png_screenshot_with_bit_depth(8),
+            image::ColorType::Rgba8
+        );
+        assert_eq!(
+            save_png_screenshot_with_bit_depth(16),
+            image::ColorType::Rgba16
+        );
+    }
+
+
+    #[test]
+    fn nearly_identical_camera_positions_give_nearly_identical_composites() {
+        let render_from = |camera_x: f32| {
+            SplatRenderer::new_for_tests(
+                Splats::from_entries(vec![get_opaque_splat(
+                    Vector3::zeros(),
+                    0.1,
+                    [255, 255, 255],
+                )]),
+                (64, 64),
+                Point3::new(camera_x, 0.0, 3.0),
+                Point3::new(camera_x, 0.0, 0.0),
+                "",
+            )
+            .render_to_image()
+        };
+
+        let image = render_from(0.0);
+        // About a tenth of a pixel at this distance and resolution,
+        // so a center rounded to the nearest pixel would not move.
+        let shifted_image = render_from(0.004);
+
+        let maximum_difference = image
+            .as_raw()
+            .iter()
+            .zip(shifted_image.as_raw().iter())
+            .map(|(component, shifted_component)| component.abs_diff(*shifted_component))
+            .max()
+            .unwrap_or(0);
+
+        assert!(
+            image != shifted_image,
+            "subpixel camera motion should change the composite"
+        );
+        assert!(
+            maximum_difference <= 32,
+            "composites differ by up to {}",
+            maximum_difference
+        );
+    }
+
+
+    #[test]
+    fn set_camera_round_trips_through_the_getters() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (32, 32),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "",
+        );
+
+        let camera_position = Point3::new(1.0, 2.0, 3.0);
+        let camera_look_target = Point3::new(0.5, -0.5, 0.0);
+        renderer.set_camera(camera_position, camera_look_target, Vector3::y());
+
+        assert_eq!(renderer.camera_position(), camera_position);
+        assert_eq!(renderer.camera_look_target(), camera_look_target);
+
+        // The up vector is the closest one to the requested up vector that is perpendicular
+        // to the viewing direction.
+        let forward_vector = (camera_look_target - camera_position).normalize();
+        let up_vector = renderer.camera_up_vector();
+        assert!((up_vector.norm() - 1.0).abs() < 1e-5);
+        assert!(up_vector.dot(&forward_vector).abs() < 1e-5);
+        assert!(up_vector.y > 0.0);
+
+        // An up vector that is already perpendicular to the viewing direction is kept as is.
+        renderer.set_camera(
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            Vector3::x(),
+        );
+        assert!((renderer.camera_up_vector() - Vector3::x()).norm() < 1e-5);
+    }
+
+
+    #[test]
+    fn billboard_shapes_cover_the_expected_number_of_pixels() {
+        let count_covered_pixels = |shape: BillboardShape| {
+            BillboardCoordinatesIterator::from_center_and_footprint(
+                (64, 64),
+                (32.0, 32.0),
+                BillboardFootprint::Square {
+                    size_in_pixels: 20,
+                    shape,
+                },
+            )
+            .count()
+        };
+
+        // The pixels at most 10 pixels away from the center, in the shape's distance.
+        assert_eq!(
+            count_covered_pixels(BillboardShape::Square),
+            21 * 21
+        );
+        assert_eq!(count_covered_pixels(BillboardShape::Circle), 317);
+        assert_eq!(
+            count_covered_pixels(BillboardShape::Diamond),
+            2 * 10 * 11 + 1
+        );
+    }
+
+    #[test]
+    fn render_stats_account_for_every_splat_of_the_testing_scene() {
+        let total_splats = get_testing_splat_scene().len();
+
+        let render_from = |camera_position: Point3<f32>| {
+            SplatRenderer::new_for_tests(
+                get_testing_splat_scene(),
+                (64, 64),
+                camera_position,
+                Point3::origin(),
+                "",
+            )
+            .render_in_place()
+        };
+
+        // From afar every splat is visible, from close by some lie outside the viewport.
+        let distant_render_stats = render_from(Point3::new(0.3, 0.24, 0.4));
+        assert_eq!(distant_render_stats.visible_splats, total_splats);
+        assert_eq!(distant_render_stats.culled_splats, 0);
+
+        let close_render_stats = render_from(Point3::new(0.05, 0.05, 0.1));
+        assert!(close_render_stats.culled_splats > 0);
+        assert_eq!(
+            close_render_stats.visible_splats + close_render_stats.culled_splats,
+            total_splats
+        );
+    }
+
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn rolling_rotates_the_up_vector_around_the_forward_vector() {
+        let forward_vector = Vector3::new(0.0, 0.0, -1.0);
+        let up_vector = Vector3::y();
+
+        let rolled_up_vector = get_rolled_up_vector(&forward_vector, &up_vector, 30f32.to_radians());
+
+        assert!((rolled_up_vector.norm() - 1.0).abs() < 1e-5);
+        assert!(rolled_up_vector.dot(&forward_vector).abs() < 1e-5);
+        assert!((rolled_up_vector.angle(&up_vector) - 30f32.to_radians()).abs() < 1e-5);
+
+        // Rolling to the right tilts the up vector towards the camera's right side (+X here).
+        assert!(rolled_up_vector.x > 0.0);
+
+        // Rolling back restores the original up vector.
+        let restored_up_vector = get_rolled_up_vector(
+            &forward_vector,
+            &rolled_up_vector,
+            -30f32.to_radians(),
+        );
+        assert!((restored_up_vector - up_vector).norm() < 1e-5);
+    }
+
+
+    #[test]
+    fn explicit_opacity_is_used_instead_of_the_color_alpha() {
+        let render_center_pixel = |splat: Splat| {
+            let image = SplatRenderer::new_for_tests(
+                Splats::from_entries(vec![splat]),
+                (32, 32),
+                Point3::new(0.0, 0.0, 3.0),
+                Point3::origin(),
+                "",
+            )
+            .render_to_image();
+
+            image.get_pixel(16, 16).0
+        };
+
+        let background_pixel = render_center_pixel(
+            get_opaque_splat(Vector3::zeros(), 0.3, [255, 255, 255]).with_opacity(0.0),
+        );
+        let opaque_pixel = render_center_pixel(get_opaque_splat(
+            Vector3::zeros(),
+            0.3,
+            [255, 255, 255],
+        ));
+
+        // A fully transparent byte alpha is overridden by an explicit opacity of one.
+        let mut transparent_splat = get_opaque_splat(Vector3::zeros(), 0.3, [255, 255, 255]);
+        transparent_splat.color.w = 0;
+        let overridden_pixel = render_center_pixel(transparent_splat.with_opacity(1.0));
+
+        assert!(
+            opaque_pixel[0] > background_pixel[0] + 100,
+            "opaque pixel is {:?}, background pixel is {:?}",
+            opaque_pixel,
+            background_pixel
+        );
+        assert_eq!(overridden_pixel, opaque_pixel);
+    }
+
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn field_of_view_is_clamped_to_a_sane_range() {
+        assert_eq!(get_clamped_field_of_view(45.0), 45.0);
+        assert_eq!(get_clamped_field_of_view(5.0), 10.0);
+        assert_eq!(get_clamped_field_of_view(150.0), 120.0);
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn field_of_view_keys_change_the_projection() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "[camera]\nvertical_field_of_view = 115.0\n",
+        );
+
+        // The projection scales y by `1 / tan(fov / 2)`, with the field of view in radians.
+        let get_projection_scale_for = |vertical_field_of_view_in_degrees: f32| {
+            1.0 / (vertical_field_of_view_in_degrees.to_radians() / 2.0).tan()
+        };
+        assert!(
+            (renderer.projection_matrix()[(1, 1)] - get_projection_scale_for(115.0)).abs() < 1e-5
+        );
+
+        // Widening past the largest field of view stops at it.
+        renderer.handle_camera_key_action(KeyAction::WidenFieldOfView, "=");
+        renderer.handle_camera_key_action(KeyAction::WidenFieldOfView, "=");
+        assert!(
+            (renderer.projection_matrix()[(1, 1)] - get_projection_scale_for(120.0)).abs() < 1e-5
+        );
+
+        renderer.handle_camera_key_action(KeyAction::NarrowFieldOfView, "-");
+        assert!(
+            (renderer.projection_matrix()[(1, 1)] - get_projection_scale_for(115.0)).abs() < 1e-5
+        );
+    }
+
+
+    #[test]
+    fn render_dimensions_are_scaled_down_to_the_maximum() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(Vec::new()),
+            (32, 32),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "[rendering]\nmax_render_dimension = 300\n",
+        );
+        let clamp = |render_width, render_height| {
+            renderer.get_clamped_render_dimensions(render_width, render_height)
+        };
+
+        assert_eq!(clamp(200, 100), (200, 100));
+        assert_eq!(clamp(0, 100), (1, 100));
+        assert_eq!(clamp(600, 300), (300, 150));
+        assert_eq!(clamp(1000, 3000), (100, 300));
+        // Very thin sizes keep at least one pixel.
+        assert_eq!(clamp(10000, 1), (300, 1));
+    }
+
+    #[test]
+    fn frame_buffer_follows_a_resize() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (32, 32),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "",
+        );
+        renderer.render_in_place();
+
+        assert_eq!(renderer.set_render_dimensions(48, 40), (48, 40));
+        renderer.render_in_place();
+
+        let inner_locked = renderer.inner.read();
+        assert_eq!(inner_locked.frame_dimensions, (48, 40));
+        assert_eq!(inner_locked.frame.len(), 48 * 40 * 4);
+    }
+
+    #[test]
+    fn resizing_beyond_the_maximum_render_dimension_is_scaled_down() {
+        let renderer = SplatRenderer::new_for_tests(
+            get_testing_splat_scene(),
+            (32, 32),
+            Point3::new(0.3, 0.24, 0.4),
+            Point3::origin(),
+            "[rendering]\nmax_render_dimension = 64\nssaa_factor = 2\n",
+        );
+
+        // The aspect ratio of the requested size is kept.
+        assert_eq!(renderer.set_render_dimensions(400, 200), (64, 32));
+        renderer.render_in_place();
+
+        assert_eq!(renderer.inner.read().frame.len(), 64 * 32 * 4);
+
+        // Supersampling multiplies the clamped render resolution.
+        let render_buffers = renderer.render_buffers.lock();
+        let supersampled_buffers = render_buffers
+            .supersampled_buffers
+            .as_ref()
+            .expect("supersampled buffers should be allocated");
+        assert_eq!(supersampled_buffers.frame.len(), 128 * 64 * 4);
+    }
+
+
+    #[test]
+    fn points_with_linear_input_colors_are_srgb_encoded() {
+        let renderer = SplatRenderer::new_for_tests(
+            Splats::from_entries(vec![get_opaque_splat(
+                Vector3::zeros(),
+                0.3,
+                [128, 128, 128],
+            )]),
+            (64, 64),
+            Point3::new(0.0, 0.0, 3.0),
+            Point3::origin(),
+            "screenshot_format = \"exr\"\n\
+             [rendering]\n\
+             render_mode = \"points\"\n\
+             background_color = \"#000000\"\n\
+             input_color_space = \"linear\"\n",
+        );
+
+        let image = renderer.render_to_image();
+        let (x, y, ..) = get_bright_pixel_bounds(&image, 0).expect("point should be visible");
+        let pixel = image.get_pixel(x, y).0;
+
+        let hdr_image = renderer
+            .render_to_hdr_image()
+            .expect("EXR screenshots should keep the full-precision frame");
+        let hdr_pixel = hdr_image.get_pixel(x, y).0;
+
+        // The 8-bit and the full-precision frame agree on the color of the point.
+        for (component, hdr_component) in pixel[..3].iter().zip(hdr_pixel.iter()) {
+            assert!((hdr_component - 128.0 / 255.0).abs() < 1e-5);
+            assert_eq!(
+                *component,
+                (linear_to_srgb(*hdr_component) * 255.0).round() as u8,
+                "8-bit pixel is {:?}, full-precision pixel is {:?}",
+                pixel,
+                hdr_pixel
+            );
+        }
     }
 }
@@ -0,0 +1,518 @@
+//! Keyboard shortcuts of the interactive window.
+//!
+//! The default shortcuts are defined in [`KEY_BINDINGS`]. Their keys can be remapped with the
+//! `[keymap]` configuration table; the resulting [`Keymap`] is used both to dispatch key presses
+//! and to list the shortcuts (in the help overlay and with the `--list-keys` flag).
+
+use std::collections::HashMap;
+
+use miette::{miette, Result};
+#[cfg(feature = "ui")]
+use winit::keyboard::Key;
+
+
+/// An action that can be triggered with a keyboard shortcut.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyAction {
+    Quit,
+    SaveScreenshot,
+
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+
+    ZoomIn,
+    ZoomOut,
+
+    RollLeft,
+    RollRight,
+
+    WidenFieldOfView,
+    NarrowFieldOfView,
+
+    /// Snaps the camera to the axis-aligned view with the given index
+    /// (`+X`, `-X`, `+Y`, `-Y`, `+Z` and `-Z`, in that order).
+    SnapToAxisAlignedView(usize),
+    ResetCamera,
+
+    IncreaseSplatScale,
+    DecreaseSplatScale,
+
+    ToggleStatisticsOverlay,
+    ToggleWorldAxes,
+    ToggleHelpOverlay,
+}
+
+
+/// A single keyboard shortcut.
+pub struct KeyBinding {
+    /// The key, as reported by `winit`: the (lowercase) character for character keys,
+    /// the key name for named keys (e.g. `Home`), prefixed with `Ctrl+` if Ctrl must be held.
+    pub key: &'static str,
+
+    /// Name of the action in the `[keymap]` configuration table
+    /// (bindings of the same action share it).
+    pub name: &'static str,
+
+    pub action: KeyAction,
+
+    /// Short description (shown in the help overlay, which only supports a limited character set).
+    pub description: &'static str,
+}
+
+impl KeyBinding {
+    const fn new(
+        key: &'static str,
+        name: &'static str,
+        action: KeyAction,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            key,
+            name,
+            action,
+            description,
+        }
+    }
+}
+
+
+/// All keyboard shortcuts of the interactive window. Each key appears at most once.
+pub const KEY_BINDINGS: [KeyBinding; 32] = [
+    KeyBinding::new("q", "quit", KeyAction::Quit, "Quit"),
+    KeyBinding::new(
+        "Ctrl+s",
+        "save_screenshot",
+        KeyAction::SaveScreenshot,
+        "Save screenshot",
+    ),
+    KeyBinding::new(
+        "w",
+        "move_forward",
+        KeyAction::MoveForward,
+        "Move forward",
+    ),
+    KeyBinding::new(
+        "s",
+        "move_backward",
+        KeyAction::MoveBackward,
+        "Move backward",
+    ),
+    KeyBinding::new("a", "move_left", KeyAction::MoveLeft, "Move left"),
+    KeyBinding::new(
+        "d",
+        "move_right",
+        KeyAction::MoveRight,
+        "Move right",
+    ),
+    KeyBinding::new("e", "move_up", KeyAction::MoveUp, "Move up"),
+    KeyBinding::new("f", "move_down", KeyAction::MoveDown, "Move down"),
+    KeyBinding::new("j", "pan_left", KeyAction::PanLeft, "Pan left"),
+    KeyBinding::new("l", "pan_right", KeyAction::PanRight, "Pan right"),
+    KeyBinding::new("i", "pan_up", KeyAction::PanUp, "Pan up"),
+    KeyBinding::new("k", "pan_down", KeyAction::PanDown, "Pan down"),
+    KeyBinding::new(
+        "g",
+        "zoom_in",
+        KeyAction::ZoomIn,
+        "Zoom in (towards look target)",
+    ),
+    KeyBinding::new(
+        "t",
+        "zoom_out",
+        KeyAction::ZoomOut,
+        "Zoom out (from look target)",
+    ),
+    KeyBinding::new(
+        ",",
+        "roll_left",
+        KeyAction::RollLeft,
+        "Roll camera left",
+    ),
+    KeyBinding::new(
+        ".",
+        "roll_right",
+        KeyAction::RollRight,
+        "Roll camera right",
+    ),
+    KeyBinding::new(
+        "=",
+        "widen_field_of_view",
+        KeyAction::WidenFieldOfView,
+        "Widen field of view",
+    ),
+    KeyBinding::new(
+        "-",
+        "narrow_field_of_view",
+        KeyAction::NarrowFieldOfView,
+        "Narrow field of view",
+    ),
+    KeyBinding::new(
+        "1",
+        "view_from_positive_x",
+        KeyAction::SnapToAxisAlignedView(0),
+        "View from +X",
+    ),
+    KeyBinding::new(
+        "2",
+        "view_from_negative_x",
+        KeyAction::SnapToAxisAlignedView(1),
+        "View from -X",
+    ),
+    KeyBinding::new(
+        "3",
+        "view_from_positive_y",
+        KeyAction::SnapToAxisAlignedView(2),
+        "View from +Y",
+    ),
+    KeyBinding::new(
+        "4",
+        "view_from_negative_y",
+        KeyAction::SnapToAxisAlignedView(3),
+        "View from -Y",
+    ),
+    KeyBinding::new(
+        "5",
+        "view_from_positive_z",
+        KeyAction::SnapToAxisAlignedView(4),
+        "View from +Z",
+    ),
+    KeyBinding::new(
+        "6",
+        "view_from_negative_z",
+        KeyAction::SnapToAxisAlignedView(5),
+        "View from -Z",
+    ),
+    KeyBinding::new(
+        "0",
+        "reset_camera",
+        KeyAction::ResetCamera,
+        "Reset camera",
+    ),
+    KeyBinding::new(
+        "Home",
+        "reset_camera",
+        KeyAction::ResetCamera,
+        "Reset camera",
+    ),
+    KeyBinding::new(
+        "]",
+        "increase_splat_scale",
+        KeyAction::IncreaseSplatScale,
+        "Increase splat size",
+    ),
+    KeyBinding::new(
+        "[",
+        "decrease_splat_scale",
+        KeyAction::DecreaseSplatScale,
+        "Decrease splat size",
+    ),
+    KeyBinding::new(
+        "p",
+        "toggle_statistics_overlay",
+        KeyAction::ToggleStatisticsOverlay,
+        "Toggle render statistics",
+    ),
+    KeyBinding::new(
+        "x",
+        "toggle_world_axes",
+        KeyAction::ToggleWorldAxes,
+        "Toggle world axes",
+    ),
+    KeyBinding::new(
+        "h",
+        "toggle_help_overlay",
+        KeyAction::ToggleHelpOverlay,
+        "Toggle this help",
+    ),
+    KeyBinding::new(
+        "F1",
+        "toggle_help_overlay",
+        KeyAction::ToggleHelpOverlay,
+        "Toggle this help",
+    ),
+];
+
+
+/// Returns the name of a pressed key in the format of [`KeyBinding::key`] (without the `Ctrl+` prefix):
+/// the lowercase character for character keys and the `winit` name for named keys
+/// (e.g. `Escape` or `F10`), or `None` for keys that can't be bound.
+#[cfg(feature = "ui")]
+pub fn get_key_name(logical_key: &Key) -> Option<String> {
+    match logical_key {
+        Key::Character(character) => Some(character.to_lowercase()),
+        Key::Named(named_key) => Some(format!("{:?}", named_key)),
+        _ => None,
+    }
+}
+
+/// Normalizes a key from the `[keymap]` configuration table to the format of [`KeyBinding::key`]:
+/// surrounding whitespace is removed and single characters are lowercased
+/// (named keys, e.g. `Escape`, are kept as they are).
+fn normalize_configured_key(key: &str) -> String {
+    let key = key.trim();
+    let (control_prefix, key_name) = match key.strip_prefix("Ctrl+") {
+        Some(key_name) => ("Ctrl+", key_name),
+        None => ("", key),
+    };
+
+    if key_name.chars().count() == 1 {
+        format!("{}{}", control_prefix, key_name.to_lowercase())
+    } else {
+        key.to_string()
+    }
+}
+
+/// Returns the name of `action` in the `[keymap]` configuration table.
+fn get_action_name(action: KeyAction) -> &'static str {
+    KEY_BINDINGS
+        .iter()
+        .find(|key_binding| key_binding.action == action)
+        .map(|key_binding| key_binding.name)
+        .expect("every action should have a default key binding")
+}
+
+/// Keyboard shortcuts of the interactive window: the [`KEY_BINDINGS`],
+/// with keys optionally remapped by the `[keymap]` configuration table.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    /// Key, action and description of each shortcut (in the order of [`KEY_BINDINGS`]).
+    bindings: Vec<(String, KeyAction, &'static str)>,
+
+    #[cfg_attr(not(feature = "ui"), allow(dead_code))]
+    actions_by_key: HashMap<String, KeyAction>,
+}
+
+impl Keymap {
+    /// Builds the keymap from the default [`KEY_BINDINGS`], replacing the keys of the actions
+    /// in `remapped_keys` (action name to key). An action that has several default keys
+    /// is bound only to the given key once remapped.
+    pub fn new(remapped_keys: &HashMap<String, String>) -> Result<Self> {
+        for (action_name, key) in remapped_keys {
+            if !KEY_BINDINGS
+                .iter()
+                .any(|key_binding| key_binding.name == action_name)
+            {
+                let mut known_action_names = KEY_BINDINGS
+                    .iter()
+                    .map(|key_binding| key_binding.name)
+                    .collect::<Vec<_>>();
+                known_action_names.dedup();
+
+                return Err(miette!(
+                    "Unknown action \"{}\" (expected one of: {}).",
+                    action_name,
+                    known_action_names.join(", ")
+                ));
+            }
+
+            if key.trim().is_empty() {
+                return Err(miette!(
+                    "Key of action \"{}\" must not be empty.",
+                    action_name
+                ));
+            }
+        }
+
+
+        let mut bindings = Vec::with_capacity(KEY_BINDINGS.len());
+        for key_binding in &KEY_BINDINGS {
+            match remapped_keys.get(key_binding.name) {
+                Some(remapped_key) => {
+                    // Only the first of the action's bindings is kept (with the new key).
+                    if bindings
+                        .iter()
+                        .all(|(_, action, _)| *action != key_binding.action)
+                    {
+                        bindings.push((
+                            normalize_configured_key(remapped_key),
+                            key_binding.action,
+                            key_binding.description,
+                        ));
+                    }
+                }
+                None => bindings.push((
+                    key_binding.key.to_string(),
+                    key_binding.action,
+                    key_binding.description,
+                )),
+            }
+        }
+
+        let mut actions_by_key = HashMap::with_capacity(bindings.len());
+        for (key, action, _) in &bindings {
+            if let Some(existing_action) = actions_by_key.insert(key.clone(), *action) {
+                if existing_action != *action {
+                    return Err(miette!(
+                        "Key \"{}\" is bound to more than one action ({} and {}).",
+                        key,
+                        get_action_name(existing_action),
+                        get_action_name(*action)
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            bindings,
+            actions_by_key,
+        })
+    }
+
+    /// Returns the action bound to `key` (see [`KeyBinding::key`] for the format), if any.
+    #[cfg(feature = "ui")]
+    pub fn find_action(&self, key: &str) -> Option<KeyAction> {
+        self.actions_by_key.get(key).copied()
+    }
+
+    /// Returns the key bindings as text, one line (the key, followed by its description) per binding.
+    pub fn format(&self) -> String {
+        let longest_key_length = self
+            .bindings
+            .iter()
+            .map(|(key, _, _)| key.len())
+            .max()
+            .unwrap_or(0);
+
+        self.bindings
+            .iter()
+            .map(|(key, _, description)| {
+                format!(
+                    "{:width$}  {}",
+                    key,
+                    description,
+                    width = longest_key_length
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&HashMap::new()).expect("default key bindings should not conflict")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    /// Builds a keymap with the given (action name, key) pairs remapped.
+    fn get_remapped_keymap(remapped_keys: &[(&str, &str)]) -> Result<Keymap> {
+        Keymap::new(
+            &remapped_keys
+                .iter()
+                .map(|(action_name, key)| (action_name.to_string(), key.to_string()))
+                .collect(),
+        )
+    }
+
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn remapped_key_triggers_its_action() {
+        let keymap = get_remapped_keymap(&[("move_forward", "Z")]).unwrap();
+
+        assert_eq!(
+            keymap.find_action("z"),
+            Some(KeyAction::MoveForward)
+        );
+        assert_eq!(keymap.find_action("w"), None);
+        // Other actions keep their default keys.
+        assert_eq!(
+            keymap.find_action("s"),
+            Some(KeyAction::MoveBackward)
+        );
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn named_keys_can_be_bound() {
+        use winit::keyboard::NamedKey;
+
+        let keymap = get_remapped_keymap(&[("quit", "Escape"), ("save_screenshot", "F10")]).unwrap();
+
+        let escape_key_name = get_key_name(&Key::Named(NamedKey::Escape)).unwrap();
+        let f10_key_name = get_key_name(&Key::Named(NamedKey::F10)).unwrap();
+
+        assert_eq!(
+            keymap.find_action(&escape_key_name),
+            Some(KeyAction::Quit)
+        );
+        assert_eq!(
+            keymap.find_action(&f10_key_name),
+            Some(KeyAction::SaveScreenshot)
+        );
+        assert_eq!(keymap.find_action("q"), None);
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn character_key_names_are_lowercase() {
+        assert_eq!(
+            get_key_name(&Key::Character("W".into())).as_deref(),
+            Some("w")
+        );
+    }
+
+    #[test]
+    fn key_bound_to_two_actions_is_rejected() {
+        let error = get_remapped_keymap(&[("quit", "w")]).unwrap_err();
+
+        assert!(
+            error.to_string().contains("bound to more than one action"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn unknown_action_is_rejected() {
+        let error = get_remapped_keymap(&[("teleport", "z")]).unwrap_err();
+
+        assert!(
+            error.to_string().contains("Unknown action \"teleport\""),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+
+    #[test]
+    fn key_bindings_are_non_empty_and_unique() {
+        assert!(!KEY_BINDINGS.is_empty());
+
+        let mut seen_keys = HashSet::new();
+        for binding in KEY_BINDINGS.iter() {
+            assert!(
+                seen_keys.insert(binding.key),
+                "key {} is bound more than once",
+                binding.key
+            );
+            assert!(!binding.description.is_empty());
+        }
+    }
+
+    #[test]
+    fn bindings_sharing_a_name_share_the_action() {
+        for binding in KEY_BINDINGS.iter() {
+            assert!(KEY_BINDINGS
+                .iter()
+                .filter(|other_binding| other_binding.name == binding.name)
+                .all(|other_binding| other_binding.action == binding.action));
+        }
+    }
+}
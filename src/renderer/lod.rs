@@ -0,0 +1,266 @@
+//! Distance-based level of detail for large scenes.
+//!
+//! At load, the splats are partitioned into a uniform spatial grid. When rendering,
+//! each cell only contributes a representative subset of its splats, which gets smaller
+//! the further the cell is from the camera (see [`LodGrid::selected_splat_indices`]).
+
+use std::ops::Range;
+
+use nalgebra::{Point3, Vector3};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::splat_decoder::Splats;
+
+
+/// Average number of splats per cell the grid resolution is chosen for
+/// (assuming the splats fill the cube around their bounding box).
+const LOD_TARGET_SPLATS_PER_CELL: usize = 512;
+
+/// Largest number of grid cells along the longest side of the scene's bounding box.
+const LOD_MAX_CELLS_ALONG_LONGEST_SIDE: usize = 128;
+
+/// Cells closer to the camera than this many cell sizes are always drawn in full.
+/// Further away, only every `n`-th splat of a cell is drawn, where `n` grows linearly
+/// with the distance (`n` is 2 at twice this distance, 3 at three times, and so on).
+const LOD_FULL_DETAIL_DISTANCE_IN_CELLS: f32 = 2.0;
+
+
+/// A non-empty cell of the [`LodGrid`].
+struct LodCell {
+    /// Center of the cell (in world coordinates).
+    center: Point3<f32>,
+
+    /// Range of [`LodGrid::splat_indices`] containing the indices of the splats in this cell.
+    splat_index_range: Range<usize>,
+}
+
+/// Splats partitioned into a uniform spatial grid (only non-empty cells are kept).
+pub struct LodGrid {
+    cells: Vec<LodCell>,
+
+    /// Indices of the splats, grouped by cell (within a cell, they remain in their original order).
+    splat_indices: Vec<usize>,
+
+    /// Length of each side of a (cubic) cell, in world units.
+    cell_size: f32,
+}
+
+impl LodGrid {
+    /// Partitions the `splats` into a grid of cubic cells. The number of cells along the longest side
+    /// of their bounding box depends on the splat count (see [`LOD_TARGET_SPLATS_PER_CELL`]).
+    pub fn build(splats: &Splats) -> Self {
+        let Some((minimum, maximum)) = splats.statistics().bounding_box else {
+            return Self {
+                cells: Vec::new(),
+                splat_indices: Vec::new(),
+                cell_size: 1.0,
+            };
+        };
+
+        let extent = maximum - minimum;
+
+        let cells_along_longest_side = ((splats.len() as f64 / LOD_TARGET_SPLATS_PER_CELL as f64)
+            .cbrt()
+            .round() as usize)
+            .clamp(1, LOD_MAX_CELLS_ALONG_LONGEST_SIDE);
+
+        // All splats may lie at the same point, in which case any cell size will do.
+        let cell_size = match extent.max() / cells_along_longest_side as f32 {
+            cell_size if cell_size > f32::EPSILON => cell_size,
+            _ => 1.0,
+        };
+
+        let cells_per_axis = extent.map(|axis_extent| {
+            ((axis_extent / cell_size).floor() as usize + 1).min(cells_along_longest_side)
+        });
+
+        let get_cell_coordinate = |axis_offset: f32, axis_cell_count: usize| -> usize {
+            ((axis_offset / cell_size) as usize).min(axis_cell_count - 1)
+        };
+
+
+        // Determine the (linear) cell index of each splat, then group the splat indices by cell.
+        let splat_cell_indices = splats
+            .splats
+            .par_iter()
+            .map(|splat| {
+                let offset = splat.position - minimum.coords;
+
+                get_cell_coordinate(offset.x, cells_per_axis.x)
+                    + get_cell_coordinate(offset.y, cells_per_axis.y) * cells_per_axis.x
+                    + get_cell_coordinate(offset.z, cells_per_axis.z)
+                        * cells_per_axis.x
+                        * cells_per_axis.y
+            })
+            .collect::<Vec<_>>();
+
+        let mut splats_per_cell = vec![0usize; cells_per_axis.product()];
+        for cell_index in &splat_cell_indices {
+            splats_per_cell[*cell_index] += 1;
+        }
+
+        let mut cell_start_offsets = Vec::with_capacity(splats_per_cell.len());
+        let mut next_cell_start_offset = 0;
+        for splat_count in &splats_per_cell {
+            cell_start_offsets.push(next_cell_start_offset);
+            next_cell_start_offset += splat_count;
+        }
+
+        let mut splat_indices = vec![0; splat_cell_indices.len()];
+        let mut cell_fill_offsets = cell_start_offsets.clone();
+        for (splat_index, cell_index) in splat_cell_indices.into_iter().enumerate() {
+            splat_indices[cell_fill_offsets[cell_index]] = splat_index;
+            cell_fill_offsets[cell_index] += 1;
+        }
+
+
+        let cells = splats_per_cell
+            .iter()
+            .enumerate()
+            .filter(|(_, splat_count)| **splat_count > 0)
+            .map(|(cell_index, splat_count)| {
+                let cell_coordinates = Vector3::new(
+                    cell_index % cells_per_axis.x,
+                    (cell_index / cells_per_axis.x) % cells_per_axis.y,
+                    cell_index / (cells_per_axis.x * cells_per_axis.y),
+                );
+
+                let start_offset = cell_start_offsets[cell_index];
+
+                LodCell {
+                    center: minimum
+                        + cell_coordinates.map(|coordinate| (coordinate as f32 + 0.5) * cell_size),
+                    splat_index_range: start_offset..start_offset + splat_count,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            cells,
+            splat_indices,
+            cell_size,
+        }
+    }
+
+    /// Returns the number of non-empty cells.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns how many of the splats in a cell at `distance_from_camera` are skipped
+    /// for each one that is drawn (`1` means the cell is drawn in full).
+    fn get_cell_splat_stride(&self, distance_from_camera: f32) -> usize {
+        let full_detail_distance = LOD_FULL_DETAIL_DISTANCE_IN_CELLS * self.cell_size;
+
+        ((distance_from_camera / full_detail_distance) as usize).max(1)
+    }
+
+    /// Returns the indices of the splats to draw when looking from `camera_position`.
+    ///
+    /// Every cell contributes at least one of its splats, and cells further away contribute fewer
+    /// (evenly spread out over the cell's splats). Additionally, only every `splat_stride`-th
+    /// of the selected splats of each cell is kept (see the interactive frame budget).
+    pub fn selected_splat_indices(
+        &self,
+        camera_position: &Point3<f32>,
+        splat_stride: usize,
+    ) -> Vec<usize> {
+        self.cells
+            .par_iter()
+            .flat_map_iter(|cell| {
+                let cell_splat_stride =
+                    self.get_cell_splat_stride((cell.center - camera_position).norm());
+
+                self.splat_indices[cell.splat_index_range.clone()]
+                    .iter()
+                    .step_by(cell_splat_stride * splat_stride)
+                    .copied()
+            })
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector4;
+
+    use super::*;
+    use crate::splat_decoder::Splat;
+
+    /// Returns splats on a uniform 20x20x20 grid filling the cube from the origin to `(100, 100, 100)`.
+    fn get_uniform_grid_of_splats() -> Splats {
+        let grid_spacing = 100.0 / 19.0;
+
+        (0..20 * 20 * 20)
+            .map(|splat_index| {
+                let grid_coordinates = Vector3::new(
+                    splat_index % 20,
+                    (splat_index / 20) % 20,
+                    splat_index / (20 * 20),
+                );
+
+                Splat::new(
+                    grid_coordinates.map(|coordinate| coordinate as f32 * grid_spacing),
+                    Vector3::repeat(0.1),
+                    Vector4::new(255, 255, 255, 255),
+                    Vector4::new(1.0, 0.0, 0.0, 0.0),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn distant_region_contributes_fewer_splats_than_a_near_one() {
+        let splats = get_uniform_grid_of_splats();
+        let lod_grid = LodGrid::build(&splats);
+
+        let selected_splat_indices = lod_grid.selected_splat_indices(&Point3::origin(), 1);
+
+        // Both regions are cubes of 7x7x7 splats, in the corners nearest to and furthest from the camera.
+        let count_selected_splats_in_region = |is_in_region: fn(f32) -> bool| {
+            selected_splat_indices
+                .iter()
+                .filter(|splat_index| {
+                    splats.splats[**splat_index]
+                        .position
+                        .iter()
+                        .all(|coordinate| is_in_region(*coordinate))
+                })
+                .count()
+        };
+
+        let near_region_splats = count_selected_splats_in_region(|coordinate| coordinate < 33.0);
+        let far_region_splats = count_selected_splats_in_region(|coordinate| coordinate > 67.0);
+
+        assert_eq!(near_region_splats, 7 * 7 * 7);
+        assert!(
+            far_region_splats > 0 && far_region_splats < near_region_splats,
+            "near region contributes {} splats, far region {}",
+            near_region_splats,
+            far_region_splats
+        );
+    }
+
+    #[test]
+    fn every_cell_contributes_at_least_one_splat() {
+        let splats = get_uniform_grid_of_splats();
+        let lod_grid = LodGrid::build(&splats);
+
+        let selected_splat_indices =
+            lod_grid.selected_splat_indices(&Point3::new(-10_000.0, 0.0, 0.0), 1);
+
+        assert!(lod_grid.cell_count() > 1);
+        assert!(selected_splat_indices.len() >= lod_grid.cell_count());
+    }
+
+    #[test]
+    fn empty_scene_has_no_cells() {
+        let lod_grid = LodGrid::build(&Splats::from_entries(Vec::new()));
+
+        assert_eq!(lod_grid.cell_count(), 0);
+        assert!(lod_grid
+            .selected_splat_indices(&Point3::origin(), 1)
+            .is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+//! A minimal bitmap-font text blitter used for on-screen overlays.
+
+/// Width of a single glyph (in font pixels).
+const GLYPH_WIDTH: u32 = 5;
+
+/// Height of a single glyph (in font pixels).
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Horizontal distance between the starts of two consecutive glyphs (in font pixels).
+const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+
+/// Vertical distance between the starts of two consecutive lines (in font pixels).
+const LINE_ADVANCE: u32 = GLYPH_HEIGHT + 2;
+
+/// Padding around the text, filled with the background color (in font pixels).
+const BACKGROUND_PADDING: u32 = 2;
+
+
+/// Returns the 5x7 bitmap of the given character.
+///
+/// Each element is one row (top to bottom), where the lowest five bits represent
+/// the pixels of that row (the most significant of them being the leftmost pixel).
+/// Lowercase letters are drawn as uppercase, and unsupported characters as `?`.
+#[rustfmt::skip]
+fn glyph_for_character(character: char) -> [u8; 7] {
+    match character.to_ascii_uppercase() {
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        ' ' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        '/' => [0b00001, 0b00010, 0b00010, 0b00100, 0b01000, 0b01000, 0b10000],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        '+' => [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],
+        '=' => [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000],
+        '_' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        '[' => [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110],
+        ']' => [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100],
+    }
+}
+
+
+/// Alpha-blends `color` over the pixel at (`x`, `y`). Out-of-bounds pixels are ignored.
+#[inline]
+fn blend_pixel(frame: &mut [u8], frame_size: (u32, u32), x: u32, y: u32, color: [u8; 4]) {
+    let (frame_width, frame_height) = frame_size;
+    if x >= frame_width || y >= frame_height {
+        return;
+    }
+
+    let pixel_index = ((y * frame_width + x) * 4) as usize;
+    let Some(pixel) = frame.get_mut(pixel_index..pixel_index + 4) else {
+        return;
+    };
+
+    let alpha = color[3] as f32 / u8::MAX as f32;
+    for (component, color_component) in pixel[..3].iter_mut().zip(color) {
+        *component =
+            ((1.0 - alpha) * (*component as f32) + alpha * (color_component as f32)).round() as u8;
+    }
+
+    // Accumulate the alpha as well, so overlays remain visible on translucent backgrounds.
+    pixel[3] = (alpha * u8::MAX as f32 + (1.0 - alpha) * pixel[3] as f32).round() as u8;
+}
+
+
+/// Returns the size (in pixels) of the box [`draw_text_with_background`] draws for `text`
+/// at the given `scale`, or `(0, 0)` if there is nothing to draw.
+pub(super) fn get_text_size_with_background(text: &str, scale: u32) -> (u32, u32) {
+    let scale = scale.max(1);
+
+    let line_count = text.lines().count() as u32;
+    let longest_line_length = text
+        .lines()
+        .map(|line| line.chars().count() as u32)
+        .max()
+        .unwrap_or(0);
+
+    if line_count == 0 || longest_line_length == 0 {
+        return (0, 0);
+    }
+
+    (
+        (longest_line_length * GLYPH_ADVANCE - 1 + BACKGROUND_PADDING * 2) * scale,
+        (line_count * LINE_ADVANCE - 2 + BACKGROUND_PADDING * 2) * scale,
+    )
+}
+
+
+/// Draws (possibly multi-line) `text` into an RGBA `frame` of size `frame_size`,
+/// starting at the `top_left` pixel, on top of a filled `background_color` box.
+///
+/// Every font pixel is drawn as a `scale`x`scale` square. Text that does not fit
+/// into the frame is clipped.
+pub(super) fn draw_text_with_background(
+    frame: &mut [u8],
+    frame_size: (u32, u32),
+    top_left: (u32, u32),
+    text: &str,
+    text_color: [u8; 4],
+    background_color: [u8; 4],
+    scale: u32,
+) {
+    let scale = scale.max(1);
+
+    let (background_width, background_height) = get_text_size_with_background(text, scale);
+    if background_width == 0 || background_height == 0 {
+        return;
+    }
+
+
+    // Draw the background box.
+
+    for y in top_left.1..top_left.1.saturating_add(background_height) {
+        for x in top_left.0..top_left.0.saturating_add(background_width) {
+            blend_pixel(frame, frame_size, x, y, background_color);
+        }
+    }
+
+
+    // Draw the glyphs.
+    let text_origin_x = top_left.0 + BACKGROUND_PADDING * scale;
+    let text_origin_y = top_left.1 + BACKGROUND_PADDING * scale;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_y = text_origin_y + line_index as u32 * LINE_ADVANCE * scale;
+
+        for (character_index, character) in line.chars().enumerate() {
+            let glyph_x = text_origin_x + character_index as u32 * GLYPH_ADVANCE * scale;
+            let glyph = glyph_for_character(character);
+
+            for (row_index, row_bits) in glyph.iter().enumerate() {
+                for column_index in 0..GLYPH_WIDTH {
+                    let is_set = (row_bits >> (GLYPH_WIDTH - 1 - column_index)) & 1 == 1;
+                    if !is_set {
+                        continue;
+                    }
+
+                    let font_pixel_x = glyph_x + column_index * scale;
+                    let font_pixel_y = line_y + row_index as u32 * scale;
+
+                    for y in font_pixel_y..font_pixel_y + scale {
+                        for x in font_pixel_x..font_pixel_x + scale {
+                            blend_pixel(frame, frame_size, x, y, text_color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+/// Draws a one pixel wide line from `start` to `end` (in fractional pixel coordinates)
+/// into an RGBA `frame` of size `frame_size`. The line is clipped to the frame.
+pub(super) fn draw_line(
+    frame: &mut [u8],
+    frame_size: (u32, u32),
+    start: (f32, f32),
+    end: (f32, f32),
+    color: [u8; 4],
+) {
+    let (frame_width, frame_height) = frame_size;
+    if frame_width == 0 || frame_height == 0 {
+        return;
+    }
+
+    let x_max = (frame_width - 1) as f32;
+    let y_max = (frame_height - 1) as f32;
+
+    // Clip the line to the frame (Liang-Barsky).
+    let delta_x = end.0 - start.0;
+    let delta_y = end.1 - start.1;
+
+    let mut t_start = 0f32;
+    let mut t_end = 1f32;
+
+    for (p, q) in [
+        (-delta_x, start.0),
+        (delta_x, x_max - start.0),
+        (-delta_y, start.1),
+        (delta_y, y_max - start.1),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return;
+            }
+
+            continue;
+        }
+
+        let t = q / p;
+        if p < 0.0 {
+            t_start = t_start.max(t);
+        } else {
+            t_end = t_end.min(t);
+        }
+    }
+
+    if t_start > t_end || t_start.is_nan() || t_end.is_nan() {
+        return;
+    }
+
+
+    // Rasterize the clipped line by stepping once per pixel along its major axis.
+    let clipped_start = (
+        start.0 + t_start * delta_x,
+        start.1 + t_start * delta_y,
+    );
+    let clipped_end = (
+        start.0 + t_end * delta_x,
+        start.1 + t_end * delta_y,
+    );
+
+    let step_count = (clipped_end.0 - clipped_start.0)
+        .abs()
+        .max((clipped_end.1 - clipped_start.1).abs())
+        .ceil()
+        .max(1.0) as u32;
+
+    for step in 0..=step_count {
+        let t = step as f32 / step_count as f32;
+
+        let x = clipped_start.0 + t * (clipped_end.0 - clipped_start.0);
+        let y = clipped_start.1 + t * (clipped_end.1 - clipped_start.1);
+
+        blend_pixel(
+            frame,
+            frame_size,
+            x.round() as u32,
+            y.round() as u32,
+            color,
+        );
+    }
+}
@@ -1,12 +1,260 @@
-use std::{path::Path, time::Instant};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use miette::{miette, Context, IntoDiagnostic, Result};
-use nalgebra::{Vector3, Vector4};
-use rayon::iter::{ParallelBridge, ParallelIterator};
-use tracing::debug;
+use nalgebra::{Matrix3, Matrix4, Point3, Quaternion, UnitQuaternion, Vector3, Vector4};
+use rayon::{
+    iter::{IntoParallelRefMutIterator, ParallelBridge, ParallelIterator},
+    slice::ParallelSlice,
+};
+use tracing::{debug, info, warn};
 
-use crate::REORDER_SPLATS_TO_FILE_ORDER;
+mod json;
+mod ply;
+mod spherical_harmonics;
+
+pub use spherical_harmonics::evaluate_spherical_harmonics;
+
+
+/// Largest difference in any RGB component for two coincident splats
+/// to be merged by [`Splats::deduplicate`].
+const DEDUPLICATION_COLOR_TOLERANCE: u8 = 8;
+
+/// Number of splats that are read into memory and parsed at once
+/// by [`Splats::load_from_file_streaming`] (32 bytes each, i.e. 2 MiB per batch).
+const STREAMING_BATCH_SIZE_IN_SPLATS: usize = 65536;
+
+/// How often (in parsed splats) [`Splats::load_from_file_with_progress`]
+/// reports progress while parsing raw `.splat` data.
+const PROGRESS_REPORT_INTERVAL_IN_SPLATS: usize = 65536;
+
+
+/// Normalizes a rotation quaternion. An all-zero quaternion can't be normalized
+/// and is returned as-is (it is considered degenerate).
+fn normalize_rotation_quaternion(quaternion: Vector4<f32>) -> Vector4<f32> {
+    let quaternion_norm = quaternion.norm();
+
+    if quaternion_norm > f32::EPSILON {
+        quaternion / quaternion_norm
+    } else {
+        quaternion
+    }
+}
+
+
+/// Magic bytes at the start of a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Magic bytes at the start of a zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+
+/// Decompresses `file_contents` in memory if they start with a known compression magic number,
+/// otherwise returns them unchanged.
+fn decompress_if_compressed(file_contents: Bytes) -> Result<Bytes> {
+    if file_contents.starts_with(&GZIP_MAGIC) {
+        decompress_gzip(&file_contents)
+    } else if file_contents.starts_with(&ZSTD_MAGIC) {
+        decompress_zstd(&file_contents)
+    } else {
+        Ok(file_contents)
+    }
+}
+
+/// Decompresses gzip-compressed `file_contents` (which may contain multiple gzip members).
+fn decompress_gzip(file_contents: &[u8]) -> Result<Bytes> {
+    #[cfg(feature = "gzip")]
+    {
+        use flate2::read::MultiGzDecoder;
+
+        let time_before_decompression = Instant::now();
+
+        let mut decompressed_contents = Vec::with_capacity(file_contents.len() * 2);
+        MultiGzDecoder::new(file_contents)
+            .read_to_end(&mut decompressed_contents)
+            .into_diagnostic()
+            .wrap_err("Failed to decompress gzip-compressed input.")?;
+
+        debug!(
+            "Decompressing gzip input ({} -> {} bytes) took {} milliseconds.",
+            file_contents.len(),
+            decompressed_contents.len(),
+            time_before_decompression.elapsed().as_secs_f64() * 1000f64
+        );
+
+        Ok(Bytes::from(decompressed_contents))
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    {
+        let _ = file_contents;
+
+        Err(miette!(
+            "Input is gzip-compressed, but this build does not include gzip support \
+            (enable the \"gzip\" feature)."
+        ))
+    }
+}
+
+/// Decompresses zstd-compressed `file_contents` (which may contain multiple zstd frames).
+fn decompress_zstd(file_contents: &[u8]) -> Result<Bytes> {
+    #[cfg(feature = "zstd")]
+    {
+        let time_before_decompression = Instant::now();
+
+        let mut decompressed_contents = Vec::with_capacity(file_contents.len() * 2);
+        zstd::stream::read::Decoder::new(file_contents)
+            .and_then(|mut decoder| decoder.read_to_end(&mut decompressed_contents))
+            .into_diagnostic()
+            .wrap_err("Failed to decompress zstd-compressed input.")?;
+
+        debug!(
+            "Decompressing zstd input ({} -> {} bytes) took {} milliseconds.",
+            file_contents.len(),
+            decompressed_contents.len(),
+            time_before_decompression.elapsed().as_secs_f64() * 1000f64
+        );
+
+        Ok(Bytes::from(decompressed_contents))
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    {
+        let _ = file_contents;
+
+        Err(miette!(
+            "Input is zstd-compressed, but this build does not include zstd support \
+            (enable the \"zstd\" feature)."
+        ))
+    }
+}
+
+
+/// Format of splat data, as detected from its contents by [`detect_format`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplatFormat {
+    /// Raw `.splat` data (32 bytes per splat, no header).
+    Raw,
+
+    /// A PLY file (starting with the `ply` magic line).
+    Ply,
+
+    /// A JSON scene description (an array or object).
+    Json,
+
+    /// A gzip-compressed stream of any of the other formats.
+    Gzip,
+
+    /// A zstd-compressed stream of any of the other formats.
+    Zstd,
+}
+
+/// Detects the format of splat data by sniffing its leading (and, for JSON, trailing) bytes.
+///
+/// JSON is only detected if the first and last non-whitespace bytes are matching brackets
+/// (`[...]` or `{...}`), since raw `.splat` data may start with either by chance.
+/// Anything that is not recognized is assumed to be raw `.splat` data.
+pub fn detect_format(bytes: &[u8]) -> SplatFormat {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        return SplatFormat::Gzip;
+    }
+
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        return SplatFormat::Zstd;
+    }
+
+    if ply::has_ply_magic(bytes) {
+        return SplatFormat::Ply;
+    }
+
+
+    let first_byte = bytes.iter().find(|byte| !byte.is_ascii_whitespace());
+    let last_byte = bytes.iter().rfind(|byte| !byte.is_ascii_whitespace());
+
+    match (first_byte, last_byte) {
+        (Some(b'['), Some(b']')) | (Some(b'{'), Some(b'}')) => SplatFormat::Json,
+        _ => SplatFormat::Raw,
+    }
+}
+
+/// Returns the format implied by the extension of `file_path`, ignoring a trailing
+/// compression extension (e.g. `scene.ply.gz` is a PLY file), or `None` for unknown extensions.
+fn get_format_from_file_extension(file_path: &Path) -> Option<SplatFormat> {
+    let uncompressed_file_path = match file_path.extension() {
+        Some(extension)
+            if extension.eq_ignore_ascii_case("gz") || extension.eq_ignore_ascii_case("zst") =>
+        {
+            Path::new(file_path.file_stem().unwrap_or_default())
+        }
+        _ => file_path,
+    };
+
+    let extension = uncompressed_file_path.extension()?;
+
+    if extension.eq_ignore_ascii_case("ply") {
+        Some(SplatFormat::Ply)
+    } else if extension.eq_ignore_ascii_case("json") {
+        Some(SplatFormat::Json)
+    } else if extension.eq_ignore_ascii_case("splat") {
+        Some(SplatFormat::Raw)
+    } else {
+        None
+    }
+}
+
+
+/// Builds the error for raw `.splat` data whose length of `file_length` bytes is not
+/// a multiple of the 32-byte splat size. `leading_bytes` (the start of the data) are used
+/// to hint at the actual format if the data looks like a PLY file or a JSON scene description.
+fn get_invalid_raw_splat_data_length_error(
+    file_length: u64,
+    leading_bytes: &[u8],
+) -> miette::Report {
+    let format_hint = if ply::has_ply_magic(leading_bytes) {
+        "The data starts with a PLY header: load it as a PLY file (e.g. with a .ply extension)."
+    } else if leading_bytes
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|first_byte| matches!(first_byte, b'[' | b'{'))
+    {
+        "The data looks like a JSON scene description: load it as a JSON file \
+         (e.g. with a .json extension)."
+    } else {
+        "Check whether the file has a header or is in a different format than raw .splat."
+    };
+
+    miette!(
+        "Invalid file: length of {} bytes is not divisible by 32 bytes (the size of a single splat). \
+         That would be {} splats with {} bytes left over. {}",
+        file_length,
+        file_length / 32,
+        file_length % 32,
+        format_hint
+    )
+}
+
+
+/// Options that control how decoded splats are validated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DecodeOptions {
+    /// If `true`, any splat with non-finite position, scale or rotation components
+    /// causes loading to fail. Otherwise such splats are skipped with a warning.
+    pub strict: bool,
+
+    /// If `true`, splats with zero alpha (which are invisible) are filtered out.
+    pub skip_transparent_splats: bool,
+
+    /// If `true`, parsed splats are always reordered back to their file order.
+    /// Raw `.splat` data is parsed in parallel, so the order is otherwise not deterministic.
+    pub deterministic: bool,
+}
 
 
 #[derive(Clone, PartialEq, Debug)]
@@ -14,7 +262,26 @@ pub struct Splat {
     pub position: Vector3<f32>,
     pub scale: Vector3<f32>,
     pub color: Vector4<u8>,
+
+    /// Rotation quaternion, stored as `(w, x, y, z)`.
+    ///
+    /// Quaternions decoded from files are normalized. An all-zero quaternion is
+    /// considered degenerate (see [`Self::rotation_matrix`]).
     pub rotation: Vector4<f32>,
+
+    /// Optional spherical harmonic coefficients for view-dependent color
+    /// (one RGB triplet per basis function, DC first; see [`evaluate_spherical_harmonics`]).
+    ///
+    /// If present, these take precedence over the RGB components of `color`
+    /// (the alpha component is always taken from `color`).
+    pub sh_coefficients: Option<Vec<Vector3<f32>>>,
+
+    /// Optional full-precision opacity (in `[0, 1]`), carried separately from the color
+    /// (e.g. the sigmoid-activated `opacity` of PLY files).
+    ///
+    /// If present, this takes precedence over the alpha component of `color` when compositing
+    /// (see [`Self::opacity`]). It is not stored in the 32-byte `.splat` format.
+    pub opacity: Option<f32>,
 }
 
 impl Splat {
@@ -29,7 +296,67 @@ impl Splat {
             scale,
             color,
             rotation,
+            sh_coefficients: None,
+            opacity: None,
+        }
+    }
+
+    /// Returns this splat with the given spherical harmonic coefficients for view-dependent color.
+    pub fn with_sh_coefficients(mut self, sh_coefficients: Vec<Vector3<f32>>) -> Self {
+        self.sh_coefficients = Some(sh_coefficients);
+        self
+    }
+
+    /// Returns this splat with the given full-precision opacity (clamped to `[0, 1]`).
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = Some(opacity.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Returns the opacity of this splat (in `[0, 1]`): the separate [`Self::opacity`]
+    /// if present, the alpha component of `color` otherwise.
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+            .unwrap_or_else(|| self.color.w as f32 / u8::MAX as f32)
+    }
+
+    /// Returns the color of this splat as seen from the normalized `view_direction`
+    /// (from the camera towards the splat).
+    ///
+    /// Splats without spherical harmonic coefficients always have the same (flat) color.
+    pub fn color_for_view_direction(&self, view_direction: &Vector3<f32>) -> Vector4<u8> {
+        let Some(sh_coefficients) = self.sh_coefficients.as_ref() else {
+            return self.color;
+        };
+
+        let rgb = evaluate_spherical_harmonics(sh_coefficients, view_direction)
+            .map(|component| (component * u8::MAX as f32).round() as u8);
+
+        Vector4::new(rgb.x, rgb.y, rgb.z, self.color.w)
+    }
+
+    /// Returns `true` if the position, scale and rotation of this splat are all finite.
+    pub fn has_finite_components(&self) -> bool {
+        self.position.iter().all(|value| value.is_finite())
+            && self.scale.iter().all(|value| value.is_finite())
+            && self.rotation.iter().all(|value| value.is_finite())
+    }
+
+    /// Returns the rotation matrix described by this splat's rotation quaternion,
+    /// or `None` if the quaternion is degenerate (i.e. all components are zero).
+    pub fn rotation_matrix(&self) -> Option<Matrix3<f32>> {
+        if self.rotation.norm() <= f32::EPSILON {
+            return None;
         }
+
+        let unit_quaternion = UnitQuaternion::from_quaternion(Quaternion::new(
+            self.rotation.x,
+            self.rotation.y,
+            self.rotation.z,
+            self.rotation.w,
+        ));
+
+        Some(unit_quaternion.to_rotation_matrix().into_inner())
     }
 
     fn from_raw_splat_file_data(mut bytes: Bytes) -> Result<Self> {
@@ -82,21 +409,126 @@ impl Splat {
             let third_decoded = (third_raw as i32 - 128i32) as f32 / 128f32;
             let fourth_decoded = (fourth_raw as i32 - 128i32) as f32 / 128f32;
 
-            Vector4::new(
+            // The components are heavily quantized, so we normalize the quaternion again.
+            normalize_rotation_quaternion(Vector4::new(
                 first_decoded,
                 second_decoded,
                 third_decoded,
                 fourth_decoded,
-            )
+            ))
         };
 
 
-        Ok(Self {
-            position,
-            scale,
-            color,
-            rotation,
-        })
+        Ok(Self::new(position, scale, color, rotation))
+    }
+
+    /// Encodes this splat into the 32-byte `.splat` file structure
+    /// (the inverse of [`Self::from_raw_splat_file_data`]).
+    ///
+    /// Note that the rotation quaternion is quantized to 8 bits per component,
+    /// so decoding the result again is lossy. Spherical harmonic coefficients are not stored.
+    #[allow(dead_code)]
+    pub fn to_raw_splat_file_data(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(32);
+
+        bytes.put_f32_le(self.position.x);
+        bytes.put_f32_le(self.position.y);
+        bytes.put_f32_le(self.position.z);
+
+        bytes.put_f32_le(self.scale.x);
+        bytes.put_f32_le(self.scale.y);
+        bytes.put_f32_le(self.scale.z);
+
+        bytes.put_u8(self.color.x);
+        bytes.put_u8(self.color.y);
+        bytes.put_u8(self.color.z);
+        bytes.put_u8(self.color.w);
+
+        for component in self.rotation.iter() {
+            bytes.put_u8((component * 128.0 + 128.0).round().clamp(0.0, 255.0) as u8);
+        }
+
+        bytes.freeze()
+    }
+}
+
+
+/// Number of equally-sized buckets in the alpha histogram of [`SplatStatistics`].
+pub const ALPHA_HISTOGRAM_BUCKET_COUNT: usize = 8;
+
+/// Summary statistics of a set of splats (see [`Splats::statistics`]).
+#[derive(Clone, PartialEq, Debug)]
+pub struct SplatStatistics {
+    pub splat_count: usize,
+
+    /// Component-wise minimum and maximum splat position (`None` if there are no splats).
+    pub bounding_box: Option<(Point3<f32>, Point3<f32>)>,
+
+    /// Average splat position (`None` if there are no splats).
+    pub centroid: Option<Point3<f32>>,
+
+    /// Number of splats with alpha in each of the equally-sized buckets over `0..=255`.
+    pub alpha_histogram: [usize; ALPHA_HISTOGRAM_BUCKET_COUNT],
+}
+
+
+/// How [`Splats::downsample`] chooses which splats to keep.
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DownsampleStrategy {
+    /// Keep the splats nearest to the centroid of the scene.
+    #[default]
+    Nearest,
+
+    /// Keep a pseudo-random (but deterministic) subset of the splats.
+    Random,
+}
+
+/// A coordinate axis (see [`Splats::flip_axis`]).
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// Returns the index of the axis in a vector (`0` for X, `1` for Y, `2` for Z).
+    pub const fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
+/// Default seed of the pseudo-random generator used by randomized operations
+/// (e.g. [`DownsampleStrategy::Random`]). Can be changed with `--seed` or the configuration file.
+pub const DEFAULT_RANDOM_SEED: u64 = 0x5EED_5A1A_7000_0001;
+
+/// A minimal SplitMix64 pseudo-random number generator.
+/// Used instead of an external crate since we only need reproducible index shuffling.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Returns a pseudo-random index in `0..upper_bound` (`upper_bound` must be non-zero).
+    fn next_index(&mut self, upper_bound: usize) -> usize {
+        (self.next_u64() % upper_bound as u64) as usize
     }
 }
 
@@ -111,89 +543,1613 @@ impl Splats {
         Self { splats }
     }
 
-    #[allow(dead_code)]
-    pub fn load_from_file<P>(input_file_path: P) -> Result<Self>
-    where
-        P: AsRef<Path>,
-    {
-        let time_before_file_read = Instant::now();
+    /// Returns the number of splats.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.splats.len()
+    }
 
+    /// Returns `true` if there are no splats.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.splats.is_empty()
+    }
 
-        let file_contents = {
-            let bytes_vec = std::fs::read(input_file_path.as_ref())
-                .into_diagnostic()
-                .wrap_err("Failed to read input file.")?;
+    /// Returns an iterator over the splats (in their current order).
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, Splat> {
+        self.splats.iter()
+    }
 
-            Bytes::from(bytes_vec)
-        };
+    /// Appends all splats of `other` to these splats (e.g. to view multiple files together).
+    pub fn merge(&mut self, other: Splats) {
+        self.extend(other);
+    }
 
-        if file_contents.len() % 32 != 0 {
-            return Err(miette!(
-                "Invalid file: not divisible by 32 bytes!"
-            ));
+    /// Transforms all splats by `transform`, which must be a similarity transform
+    /// (a combination of translation, rotation and uniform scaling).
+    ///
+    /// Positions are transformed, rotations are rotated and scales are scaled accordingly.
+    /// Spherical harmonic coefficients are left as-is (they are not rotated).
+    pub fn apply_transform(&mut self, transform: &Matrix4<f32>) {
+        let linear_part: Matrix3<f32> = transform.fixed_view::<3, 3>(0, 0).into_owned();
+
+        // For a similarity transform, every column of the linear part has the length of the scale.
+        let uniform_scale = linear_part.column(0).norm();
+        let rotation = UnitQuaternion::from_matrix(&(linear_part / uniform_scale));
+
+        self.splats.par_iter_mut().for_each(|splat| {
+            splat.position = transform
+                .transform_point(&Point3::from(splat.position))
+                .coords;
+            splat.scale *= uniform_scale;
+
+            // Degenerate (all-zero) rotations are kept as they are.
+            if splat.rotation.norm() > f32::EPSILON {
+                let splat_rotation = Quaternion::new(
+                    splat.rotation.x,
+                    splat.rotation.y,
+                    splat.rotation.z,
+                    splat.rotation.w,
+                );
+
+                let rotated_splat_rotation = rotation.quaternion() * splat_rotation;
+
+                splat.rotation = Vector4::new(
+                    rotated_splat_rotation.w,
+                    rotated_splat_rotation.i,
+                    rotated_splat_rotation.j,
+                    rotated_splat_rotation.k,
+                );
+            }
+        });
+    }
+
+    /// Mirrors all splats along `axis` (e.g. to convert data from a left-handed coordinate system).
+    ///
+    /// The position component along `axis` is negated. The orientation is mirrored as well:
+    /// reflecting the covariance `R S² Rᵀ` gives `(M R M) S² (M R M)ᵀ` (`M` being the reflection),
+    /// so scales are kept and the rotation quaternion's imaginary components along the other
+    /// two axes are negated. Spherical harmonic coefficients are left as-is (they are not mirrored).
+    pub fn flip_axis(&mut self, axis: Axis) {
+        let axis_index = axis.index();
+
+        self.splats.par_iter_mut().for_each(|splat| {
+            splat.position[axis_index] = -splat.position[axis_index];
+
+            // The rotation is stored as `(w, x, y, z)`, so the imaginary components start at index 1.
+            for imaginary_index in (0..3).filter(|index| *index != axis_index) {
+                splat.rotation[imaginary_index + 1] = -splat.rotation[imaginary_index + 1];
+            }
+        });
+    }
+
+    /// Validates freshly decoded splats according to the provided [`DecodeOptions`].
+    fn from_decoded_splats(decoded_splats: Vec<Splat>, options: DecodeOptions) -> Result<Self> {
+        if options.strict {
+            if let Some(invalid_splat_index) = decoded_splats
+                .iter()
+                .position(|splat| !splat.has_finite_components())
+            {
+                return Err(miette!(
+                    "Invalid splat at index {}: position, scale or rotation is not finite.",
+                    invalid_splat_index
+                ));
+            }
         }
 
+        let total_splats = decoded_splats.len();
 
-        debug!(
-            "Reading the input file took {} milliseconds.",
-            time_before_file_read.elapsed().as_secs_f64() * 1000f64
-        );
+        let mut non_finite_splats: usize = 0;
+        let mut transparent_splats: usize = 0;
 
-        let time_before_splats_parse = Instant::now();
+        let splats = decoded_splats
+            .into_iter()
+            .filter(|splat| {
+                if !splat.has_finite_components() {
+                    non_finite_splats += 1;
+                    return false;
+                }
 
-        let parsed_splats = if REORDER_SPLATS_TO_FILE_ORDER {
-            let mut enumerated_parsed_splats = file_contents
-                .chunks(32)
-                .enumerate()
-                .par_bridge()
-                .map(|(chunk_index, chunk)| {
-                    let splat = Splat::from_raw_splat_file_data(Bytes::copy_from_slice(chunk))?;
+                if options.skip_transparent_splats && splat.color.w == 0 {
+                    transparent_splats += 1;
+                    return false;
+                }
 
-                    Ok((chunk_index, splat))
-                })
-                .collect::<Result<Vec<_>>>()?;
+                true
+            })
+            .collect::<Vec<_>>();
 
 
-            debug!(
-                "Parsing splats from raw data took {} milliseconds.",
-                time_before_splats_parse.elapsed().as_secs_f64() * 1000f64
+        if non_finite_splats > 0 {
+            warn!(
+                "Skipped {} of {} splats with non-finite position, scale or rotation.",
+                non_finite_splats, total_splats
             );
+        }
 
-            let time_before_splats_reordered = Instant::now();
+        if transparent_splats > 0 {
+            info!(
+                "Skipped {} of {} fully transparent splats.",
+                transparent_splats, total_splats
+            );
+        }
 
+        let splats = Self { splats };
+        splats.log_statistics();
 
-            enumerated_parsed_splats.sort_unstable_by_key(|(chunk_index, _)| *chunk_index);
+        Ok(splats)
+    }
 
-            let parsed_splats = enumerated_parsed_splats
-                .into_iter()
-                .map(|(_, splat)| splat)
-                .collect::<Vec<_>>();
+    /// Discards all splats with an opacity (`color.w`) below `alpha_threshold`.
+    /// The kept splats remain in their original relative order.
+    ///
+    /// Returns the number of splats that were dropped.
+    pub fn filter_by_alpha(&mut self, alpha_threshold: u8) -> usize {
+        let total_splats = self.splats.len();
 
+        self.splats.retain(|splat| splat.color.w >= alpha_threshold);
 
-            debug!(
-                "Reordering splats to the original file order took {} milliseconds.",
-                time_before_splats_reordered.elapsed().as_secs_f64() * 1000f64
-            );
+        total_splats - self.splats.len()
+    }
 
-            parsed_splats
-        } else {
-            let parsed_splats = file_contents
-                .chunks(32)
-                .par_bridge()
-                .map(|chunk| Splat::from_raw_splat_file_data(Bytes::copy_from_slice(chunk)))
-                .collect::<Result<Vec<_>>>()?;
+    /// Merges splats that lie within `position_epsilon` (in world units) of each other
+    /// and have a similar color (see [`DEDUPLICATION_COLOR_TOLERANCE`]) into one.
+    ///
+    /// Each group is merged into its first splat, which keeps its position, scale and rotation.
+    /// Its opacity becomes that of all the merged splats composited over each other,
+    /// and its color their average (weighted by opacity). Nearby splats are found with a spatial
+    /// hash grid of `position_epsilon`-sized cells. The kept splats remain in their original
+    /// relative order. `position_epsilon` must be positive.
+    ///
+    /// Returns the number of splats that were merged away.
+    pub fn deduplicate(&mut self, position_epsilon: f32) -> usize {
+        let total_splats = self.splats.len();
 
-            debug!(
-                "Parsing splats from raw data took {} milliseconds.",
-                time_before_splats_parse.elapsed().as_secs_f64() * 1000f64
-            );
+        let get_cell = |position: &Vector3<f32>| -> (i64, i64, i64) {
+            let cell = (position / position_epsilon).map(|coordinate| coordinate.floor() as i64);
+            (cell.x, cell.y, cell.z)
+        };
 
-            parsed_splats
+        let are_colors_similar = |first: &Vector4<u8>, second: &Vector4<u8>| {
+            first
+                .xyz()
+                .iter()
+                .zip(second.xyz().iter())
+                .all(|(first, second)| first.abs_diff(*second) <= DEDUPLICATION_COLOR_TOLERANCE)
         };
 
 
-        Ok(Self {
-            splats: parsed_splats,
-        })
+        // Indices (into `kept_splats`) of the kept splats in each cell of the grid.
+        let mut kept_splats_by_cell: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+
+        let mut kept_splats: Vec<Splat> = Vec::with_capacity(total_splats);
+        // Sum of the opacities of the splats merged into each kept splat (the weight of its color).
+        let mut kept_splat_color_weights: Vec<f32> = Vec::with_capacity(total_splats);
+
+        for splat in std::mem::take(&mut self.splats) {
+            let (cell_x, cell_y, cell_z) = get_cell(&splat.position);
+
+            let coincident_splat_index = (-1..=1)
+                .flat_map(|offset_x| {
+                    (-1..=1).flat_map(move |offset_y| {
+                        (-1..=1).map(move |offset_z| {
+                            (
+                                cell_x + offset_x,
+                                cell_y + offset_y,
+                                cell_z + offset_z,
+                            )
+                        })
+                    })
+                })
+                .filter_map(|cell| kept_splats_by_cell.get(&cell))
+                .flatten()
+                .copied()
+                .find(|kept_splat_index| {
+                    let kept_splat = &kept_splats[*kept_splat_index];
+
+                    (kept_splat.position - splat.position).norm() <= position_epsilon
+                        && are_colors_similar(&kept_splat.color, &splat.color)
+                });
+
+            let Some(coincident_splat_index) = coincident_splat_index else {
+                kept_splats_by_cell
+                    .entry((cell_x, cell_y, cell_z))
+                    .or_default()
+                    .push(kept_splats.len());
+
+                kept_splat_color_weights.push(splat.opacity());
+                kept_splats.push(splat);
+                continue;
+            };
+
+
+            let kept_splat = &mut kept_splats[coincident_splat_index];
+            let kept_splat_color_weight = &mut kept_splat_color_weights[coincident_splat_index];
+
+            let splat_opacity = splat.opacity();
+            let merged_color_weight = *kept_splat_color_weight + splat_opacity;
+
+            if merged_color_weight > 0.0 {
+                let merged_rgb = (kept_splat.color.xyz().map(f32::from) * *kept_splat_color_weight
+                    + splat.color.xyz().map(f32::from) * splat_opacity)
+                    / merged_color_weight;
+
+                kept_splat.color.x = merged_rgb.x.round() as u8;
+                kept_splat.color.y = merged_rgb.y.round() as u8;
+                kept_splat.color.z = merged_rgb.z.round() as u8;
+            }
+
+            *kept_splat_color_weight = merged_color_weight;
+
+            // Composite the two opacities ("over" operator).
+            let merged_opacity = 1.0 - (1.0 - kept_splat.opacity()) * (1.0 - splat_opacity);
+
+            kept_splat.color.w = (merged_opacity * u8::MAX as f32).round() as u8;
+            if kept_splat.opacity.is_some() || splat.opacity.is_some() {
+                kept_splat.opacity = Some(merged_opacity);
+            }
+        }
+
+        self.splats = kept_splats;
+
+        total_splats - self.splats.len()
+    }
+
+    /// Reduces the number of splats to at most `max_splats`, choosing which ones to keep
+    /// according to `strategy`. The kept splats remain in their original relative order.
+    /// `random_seed` seeds the pseudo-random generator of [`DownsampleStrategy::Random`],
+    /// so the same seed always keeps the same subset of splats.
+    ///
+    /// Returns the number of splats that were dropped.
+    pub fn downsample(
+        &mut self,
+        max_splats: usize,
+        strategy: DownsampleStrategy,
+        random_seed: u64,
+    ) -> usize {
+        let total_splats = self.splats.len();
+        if total_splats <= max_splats {
+            return 0;
+        }
+
+
+        let mut candidate_indices = (0..total_splats).collect::<Vec<_>>();
+
+        match strategy {
+            DownsampleStrategy::Nearest => {
+                let centroid = self
+                    .statistics()
+                    .centroid
+                    .unwrap_or_else(Point3::origin)
+                    .coords;
+
+                let distances_squared = self
+                    .splats
+                    .iter()
+                    .map(|splat| (splat.position - centroid).norm_squared())
+                    .collect::<Vec<_>>();
+
+                if max_splats > 0 {
+                    candidate_indices.select_nth_unstable_by(max_splats - 1, |first, second| {
+                        distances_squared[*first].total_cmp(&distances_squared[*second])
+                    });
+                }
+            }
+            DownsampleStrategy::Random => {
+                // Partial Fisher-Yates shuffle: the first `max_splats` indices end up being a random subset.
+                let mut random_generator = SplitMix64::new(random_seed);
+
+                for index in 0..max_splats {
+                    let swap_with = index + random_generator.next_index(total_splats - index);
+                    candidate_indices.swap(index, swap_with);
+                }
+            }
+        }
+
+
+        let mut kept_indices = candidate_indices;
+        kept_indices.truncate(max_splats);
+        kept_indices.sort_unstable();
+
+        let mut is_kept = vec![false; total_splats];
+        for index in kept_indices {
+            is_kept[index] = true;
+        }
+
+        let mut splat_index = 0;
+        self.splats.retain(|_| {
+            let keep = is_kept[splat_index];
+            splat_index += 1;
+            keep
+        });
+
+        total_splats - self.splats.len()
+    }
+
+    /// Computes the splat count, bounding box, centroid and alpha histogram.
+    pub fn statistics(&self) -> SplatStatistics {
+        let mut bounding_box: Option<(Point3<f32>, Point3<f32>)> = None;
+        let mut total_position = Vector3::new(0f32, 0f32, 0f32);
+        let mut alpha_histogram = [0; ALPHA_HISTOGRAM_BUCKET_COUNT];
+
+        let alpha_bucket_size = (u8::MAX as usize + 1) / ALPHA_HISTOGRAM_BUCKET_COUNT;
+
+        for splat in &self.splats {
+            let position = Point3::from(splat.position);
+
+            bounding_box = Some(match bounding_box {
+                Some((minimum, maximum)) => (minimum.inf(&position), maximum.sup(&position)),
+                None => (position, position),
+            });
+
+            total_position += splat.position;
+            alpha_histogram[splat.color.w as usize / alpha_bucket_size] += 1;
+        }
+
+
+        let centroid = (!self.splats.is_empty())
+            .then(|| Point3::from(total_position / self.splats.len() as f32));
+
+        SplatStatistics {
+            splat_count: self.splats.len(),
+            bounding_box,
+            centroid,
+            alpha_histogram,
+        }
+    }
+
+    fn log_statistics(&self) {
+        let statistics = self.statistics();
+
+        info!("Loaded {} splats.", statistics.splat_count);
+
+        if statistics.splat_count == 0 {
+            warn!("No splats were loaded, the scene will only contain the background.");
+        }
+
+        if let (Some((minimum, maximum)), Some(centroid)) =
+            (statistics.bounding_box, statistics.centroid)
+        {
+            info!(
+                "Splat bounding box: min ({}, {}, {}), max ({}, {}, {}), centroid ({}, {}, {}).",
+                minimum.x,
+                minimum.y,
+                minimum.z,
+                maximum.x,
+                maximum.y,
+                maximum.z,
+                centroid.x,
+                centroid.y,
+                centroid.z
+            );
+        }
+
+        info!(
+            "Splat alpha histogram ({} buckets over 0-255): {:?}",
+            ALPHA_HISTOGRAM_BUCKET_COUNT, statistics.alpha_histogram
+        );
+    }
+
+    /// Save the splats to a raw `.splat` file (32 bytes per splat, in order).
+    #[allow(dead_code)]
+    pub fn save_to_file<P>(&self, output_file_path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut file_contents = BytesMut::with_capacity(self.splats.len() * 32);
+
+        for splat in &self.splats {
+            file_contents.put(splat.to_raw_splat_file_data());
+        }
+
+        std::fs::write(output_file_path.as_ref(), file_contents)
+            .into_diagnostic()
+            .wrap_err("Failed to write output file.")
+    }
+
+    fn read_file_contents(input_file_path: &Path) -> Result<Bytes> {
+        let time_before_file_read = Instant::now();
+
+        let bytes_vec = std::fs::read(input_file_path)
+            .into_diagnostic()
+            .wrap_err("Failed to read input file.")?;
+
+        debug!(
+            "Reading the input file took {} milliseconds.",
+            time_before_file_read.elapsed().as_secs_f64() * 1000f64
+        );
+
+        Ok(Bytes::from(bytes_vec))
+    }
+
+    /// Load splats from a raw `.splat` file without reading the entire file into memory first.
+    ///
+    /// The file is read in batches of [`STREAMING_BATCH_SIZE_IN_SPLATS`] splats, each of which
+    /// is parsed in parallel. Peak memory usage is therefore the parsed splats plus a single
+    /// batch of raw bytes, instead of the parsed splats plus the entire raw file
+    /// (as with [`Self::load_from_file`]). The tradeoff is that parallelism is limited
+    /// to a single batch at a time, so this can be somewhat slower.
+    ///
+    /// Splats are always returned in file order. Only the raw `.splat` format is supported.
+    #[allow(dead_code)]
+    pub fn load_from_file_streaming<P>(input_file_path: P, options: DecodeOptions) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let time_before_splats_parse = Instant::now();
+
+        let mut input_file = File::open(input_file_path.as_ref())
+            .into_diagnostic()
+            .wrap_err("Failed to open input file.")?;
+
+        let file_length = input_file
+            .metadata()
+            .into_diagnostic()
+            .wrap_err("Failed to read input file metadata.")?
+            .len();
+
+        if file_length % 32 != 0 {
+            let mut leading_bytes = Vec::with_capacity(64);
+            input_file
+                .by_ref()
+                .take(64)
+                .read_to_end(&mut leading_bytes)
+                .into_diagnostic()
+                .wrap_err("Failed to read input file.")?;
+
+            return Err(get_invalid_raw_splat_data_length_error(
+                file_length,
+                &leading_bytes,
+            ));
+        }
+
+
+        let total_splats = (file_length / 32) as usize;
+        let mut parsed_splats = Vec::with_capacity(total_splats);
+
+        let mut batch_buffer = vec![0u8; STREAMING_BATCH_SIZE_IN_SPLATS.min(total_splats) * 32];
+        let mut remaining_splats = total_splats;
+
+        while remaining_splats > 0 {
+            let batch_size_in_splats = remaining_splats.min(STREAMING_BATCH_SIZE_IN_SPLATS);
+            let batch_bytes = &mut batch_buffer[..batch_size_in_splats * 32];
+
+            input_file
+                .read_exact(batch_bytes)
+                .into_diagnostic()
+                .wrap_err("Failed to read input file.")?;
+
+            let parsed_batch = batch_bytes
+                .par_chunks(32)
+                .map(|chunk| Splat::from_raw_splat_file_data(Bytes::copy_from_slice(chunk)))
+                .collect::<Result<Vec<_>>>()?;
+
+            parsed_splats.extend(parsed_batch);
+            remaining_splats -= batch_size_in_splats;
+        }
+
+
+        debug!(
+            "Streaming and parsing splats from raw data took {} milliseconds.",
+            time_before_splats_parse.elapsed().as_secs_f64() * 1000f64
+        );
+
+        Self::from_decoded_splats(parsed_splats, options)
+    }
+
+    /// Load splats from a PLY file (as exported by most Gaussian splatting tools).
+    pub fn load_from_ply<P>(input_file_path: P, options: DecodeOptions) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file_contents = Self::read_file_contents(input_file_path.as_ref())?;
+
+        Self::from_ply_data(file_contents, options)
+    }
+
+    fn from_ply_data(file_contents: Bytes, options: DecodeOptions) -> Result<Self> {
+        let time_before_splats_parse = Instant::now();
+
+        let parsed_splats =
+            ply::parse_splats_from_ply_data(file_contents).wrap_err("Failed to parse PLY file.")?;
+
+        debug!(
+            "Parsing splats from PLY data took {} milliseconds.",
+            time_before_splats_parse.elapsed().as_secs_f64() * 1000f64
+        );
+
+        Self::from_decoded_splats(parsed_splats, options)
+    }
+
+    /// Load splats from a JSON scene description (an array of objects with
+    /// `position`, `scale`, `color` and `rotation` fields).
+    #[allow(dead_code)]
+    pub fn load_from_json<P>(input_file_path: P, options: DecodeOptions) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file_contents = Self::read_file_contents(input_file_path.as_ref())?;
+
+        Self::from_json_data(decompress_if_compressed(file_contents)?, options)
+    }
+
+    fn from_json_data(file_contents: Bytes, options: DecodeOptions) -> Result<Self> {
+        let time_before_splats_parse = Instant::now();
+
+        let parsed_splats = json::parse_splats_from_json_data(file_contents)
+            .wrap_err("Failed to parse JSON file.")?;
+
+        debug!(
+            "Parsing splats from JSON data took {} milliseconds.",
+            time_before_splats_parse.elapsed().as_secs_f64() * 1000f64
+        );
+
+        Self::from_decoded_splats(parsed_splats, options)
+    }
+
+    /// Load splats from a file.
+    ///
+    /// The format is detected from the file contents (see [`detect_format`]), not its extension:
+    /// files with a PLY header are parsed as PLY files, bracketed JSON as JSON scene descriptions,
+    /// and everything else as a raw `.splat` file (32 bytes per splat).
+    /// Gzip- or zstd-compressed files (e.g. `scene.splat.gz`) are decompressed in memory first.
+    /// A warning is logged if the detected format does not match the file extension.
+    #[allow(dead_code)]
+    pub fn load_from_file<P>(input_file_path: P, options: DecodeOptions) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::load_from_file_with_progress(input_file_path, options, |_, _| {})
+    }
+
+    /// Load splats from a file (see [`Self::load_from_file`]), periodically calling
+    /// `progress_callback` with the number of parsed splats and the total number of splats.
+    ///
+    /// Progress is reported every [`PROGRESS_REPORT_INTERVAL_IN_SPLATS`] splats while parsing
+    /// raw `.splat` data (possibly from multiple threads, and not necessarily in increasing order).
+    /// PLY and JSON files only report progress once they have been fully parsed.
+    pub fn load_from_file_with_progress<P, F>(
+        input_file_path: P,
+        options: DecodeOptions,
+        progress_callback: F,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        F: Fn(usize, usize) + Sync,
+    {
+        let extension_format = get_format_from_file_extension(input_file_path.as_ref());
+        let file_contents = Self::read_file_contents(input_file_path.as_ref())?;
+
+        Self::from_file_contents(
+            file_contents,
+            extension_format,
+            options,
+            &progress_callback,
+        )
+    }
+
+    /// Load splats from any reader (e.g. standard input), reading it to the end.
+    ///
+    /// The format is detected the same way as in [`Self::load_from_file`]
+    /// (there is no file extension to compare it with).
+    pub fn load_from_reader<R>(mut reader: R, options: DecodeOptions) -> Result<Self>
+    where
+        R: Read,
+    {
+        let mut reader_contents = Vec::new();
+        reader
+            .read_to_end(&mut reader_contents)
+            .into_diagnostic()
+            .wrap_err("Failed to read input.")?;
+
+        Self::from_file_contents(
+            Bytes::from(reader_contents),
+            None,
+            options,
+            &|_, _| {},
+        )
+    }
+
+    /// Decodes splats from in-memory file contents, routing them to the right decoder
+    /// based on [`detect_format`]. The `extension_format` (the format implied by the file
+    /// extension, if any) is used to warn about mismatched extensions, and to parse
+    /// unrecognized contents of `.json` files as JSON.
+    fn from_file_contents<F>(
+        file_contents: Bytes,
+        extension_format: Option<SplatFormat>,
+        options: DecodeOptions,
+        progress_callback: &F,
+    ) -> Result<Self>
+    where
+        F: Fn(usize, usize) + Sync,
+    {
+        let file_contents = decompress_if_compressed(file_contents)?;
+        let detected_format = match (detect_format(&file_contents), extension_format) {
+            // Raw data is only the fallback for unrecognized contents, so a `.json` file
+            // that doesn't look like JSON (e.g. a truncated one) is still parsed as JSON,
+            // which reports a more helpful error.
+            (SplatFormat::Raw, Some(SplatFormat::Json)) => SplatFormat::Json,
+            (detected_format, _) => detected_format,
+        };
+
+        if let Some(extension_format) = extension_format {
+            if extension_format != detected_format {
+                warn!(
+                    "File extension suggests {:?} data, but the contents look like {:?} data. \
+                     Loading it as {:?}.",
+                    extension_format, detected_format, detected_format
+                );
+            }
+        }
+
+        let splats = match detected_format {
+            SplatFormat::Ply => Self::from_ply_data(file_contents, options)?,
+            SplatFormat::Json => Self::from_json_data(file_contents, options)?,
+            // Data that is still compressed after decompressing once (nested streams)
+            // is not decompressed again, the raw decoder reports it as invalid instead.
+            SplatFormat::Raw | SplatFormat::Gzip | SplatFormat::Zstd => {
+                return Self::from_raw_splat_data(file_contents, options, progress_callback);
+            }
+        };
+
+        progress_callback(splats.len(), splats.len());
+
+        Ok(splats)
+    }
+
+    /// Decodes raw `.splat` data (32 bytes per splat), in parallel.
+    fn from_raw_splat_data<F>(
+        file_contents: Bytes,
+        options: DecodeOptions,
+        progress_callback: &F,
+    ) -> Result<Self>
+    where
+        F: Fn(usize, usize) + Sync,
+    {
+        if file_contents.len() % 32 != 0 {
+            return Err(get_invalid_raw_splat_data_length_error(
+                file_contents.len() as u64,
+                &file_contents,
+            ));
+        }
+
+
+        let time_before_splats_parse = Instant::now();
+
+        // Counts parsed splats across all parsing threads and reports progress every
+        // `PROGRESS_REPORT_INTERVAL_IN_SPLATS` splats (and once parsing has finished).
+        let total_splats = file_contents.len() / 32;
+        let parsed_splat_count = AtomicUsize::new(0);
+
+        let report_parsed_splat = || {
+            let parsed_splats = parsed_splat_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+            if parsed_splats % PROGRESS_REPORT_INTERVAL_IN_SPLATS == 0
+                && parsed_splats < total_splats
+            {
+                progress_callback(parsed_splats, total_splats);
+            }
+        };
+
+        let parsed_splats = if options.deterministic {
+            let mut enumerated_parsed_splats = file_contents
+                .chunks(32)
+                .enumerate()
+                .par_bridge()
+                .map(|(chunk_index, chunk)| {
+                    let splat = Splat::from_raw_splat_file_data(Bytes::copy_from_slice(chunk))?;
+                    report_parsed_splat();
+
+                    Ok((chunk_index, splat))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+
+            debug!(
+                "Parsing splats from raw data took {} milliseconds.",
+                time_before_splats_parse.elapsed().as_secs_f64() * 1000f64
+            );
+
+            let time_before_splats_reordered = Instant::now();
+
+
+            enumerated_parsed_splats.sort_unstable_by_key(|(chunk_index, _)| *chunk_index);
+
+            let parsed_splats = enumerated_parsed_splats
+                .into_iter()
+                .map(|(_, splat)| splat)
+                .collect::<Vec<_>>();
+
+
+            debug!(
+                "Reordering splats to the original file order took {} milliseconds.",
+                time_before_splats_reordered.elapsed().as_secs_f64() * 1000f64
+            );
+
+            parsed_splats
+        } else {
+            let parsed_splats = file_contents
+                .chunks(32)
+                .par_bridge()
+                .map(|chunk| {
+                    let splat = Splat::from_raw_splat_file_data(Bytes::copy_from_slice(chunk))?;
+                    report_parsed_splat();
+
+                    Ok(splat)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            debug!(
+                "Parsing splats from raw data took {} milliseconds.",
+                time_before_splats_parse.elapsed().as_secs_f64() * 1000f64
+            );
+
+            parsed_splats
+        };
+
+        progress_callback(total_splats, total_splats);
+
+
+        Self::from_decoded_splats(parsed_splats, options)
+    }
+}
+
+impl IntoIterator for Splats {
+    type Item = Splat;
+    type IntoIter = std::vec::IntoIter<Splat>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.splats.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Splats {
+    type Item = &'a Splat;
+    type IntoIter = std::slice::Iter<'a, Splat>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.splats.iter()
+    }
+}
+
+impl FromIterator<Splat> for Splats {
+    fn from_iter<I: IntoIterator<Item = Splat>>(iterator: I) -> Self {
+        Self::from_entries(iterator.into_iter().collect())
+    }
+}
+
+impl Extend<Splat> for Splats {
+    fn extend<I: IntoIterator<Item = Splat>>(&mut self, iterator: I) {
+        self.splats.extend(iterator);
+    }
+}
+
+
+/// Construct and return [`Splats`] containing a simple 5-point splatting testing scene.
+pub fn get_testing_splat_scene() -> Splats {
+    Splats::from_entries(vec![
+        Splat::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.01, 0.01, 0.01),
+            Vector4::new(244, 130, 80, 220),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        ),
+        Splat::new(
+            Vector3::new(0.1, 0.0, 0.0),
+            Vector3::new(0.03, 0.01, 0.01),
+            Vector4::new(200, 22, 1, 123),
+            // Rotated by 45 degrees around the z axis.
+            Vector4::new(0.9238795, 0.0, 0.0, 0.38268343),
+        ),
+        Splat::new(
+            Vector3::new(0.0, 0.1, 0.0),
+            Vector3::new(0.01, 0.01, 0.01),
+            Vector4::new(200, 255, 255, 22),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        ),
+        Splat::new(
+            Vector3::new(0.0, 0.0, 0.1),
+            Vector3::new(0.01, 0.01, 0.01),
+            Vector4::new(22, 255, 255, 90),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        ),
+        Splat::new(
+            Vector3::new(0.0, -0.1, 0.0),
+            Vector3::new(0.01, 0.01, 0.01),
+            Vector4::new(22, 2, 255, 100),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        ),
+    ])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Returns `splat_count` small, opaque, white splats placed along the X axis
+    /// (at `x = 0, 1, 2, ...`).
+    fn get_splats_along_x_axis(splat_count: usize) -> Splats {
+        (0..splat_count)
+            .map(|splat_index| {
+                Splat::new(
+                    Vector3::new(splat_index as f32, 0.0, 0.0),
+                    Vector3::new(0.01, 0.01, 0.01),
+                    Vector4::new(255, 255, 255, 255),
+                    Vector4::new(1.0, 0.0, 0.0, 0.0),
+                )
+            })
+            .collect()
+    }
+
+
+    /// Options that keep the parsed splats in file order, so they can be compared.
+    const DETERMINISTIC_DECODE_OPTIONS: DecodeOptions = DecodeOptions {
+        strict: true,
+        skip_transparent_splats: false,
+        deterministic: true,
+    };
+
+    /// Returns the raw `.splat` file contents of `splats`.
+    fn get_raw_splat_file_data(splats: &Splats) -> Vec<u8> {
+        splats
+            .iter()
+            .flat_map(|splat| splat.to_raw_splat_file_data())
+            .collect()
+    }
+
+
+    /// Returns the path of a file in the `references/fixtures` directory.
+    fn get_fixture_path(file_name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("references/fixtures")
+            .join(file_name)
+    }
+
+    /// Returns the path of a file in the temporary directory used by tests (creating the directory).
+    fn get_temporary_file_path(file_name: &str) -> PathBuf {
+        let temporary_directory_path = std::env::temp_dir().join("nrg-dn1-tests");
+        std::fs::create_dir_all(&temporary_directory_path).unwrap();
+
+        temporary_directory_path.join(file_name)
+    }
+
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_compressed_input_loads_the_same_splats() {
+        use std::io::Write;
+
+        use flate2::{write::GzEncoder, Compression};
+
+        let raw_data = get_raw_splat_file_data(&get_splats_along_x_axis(100));
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw_data).unwrap();
+        let compressed_data = encoder.finish().unwrap();
+
+        assert_eq!(
+            Splats::load_from_reader(
+                compressed_data.as_slice(),
+                DETERMINISTIC_DECODE_OPTIONS
+            )
+            .unwrap(),
+            Splats::load_from_reader(raw_data.as_slice(), DETERMINISTIC_DECODE_OPTIONS).unwrap()
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_compressed_input_loads_the_same_splats() {
+        let raw_data = get_raw_splat_file_data(&get_splats_along_x_axis(100));
+        let compressed_data = zstd::encode_all(raw_data.as_slice(), 0).unwrap();
+
+        assert_eq!(
+            Splats::load_from_reader(
+                compressed_data.as_slice(),
+                DETERMINISTIC_DECODE_OPTIONS
+            )
+            .unwrap(),
+            Splats::load_from_reader(raw_data.as_slice(), DETERMINISTIC_DECODE_OPTIONS).unwrap()
+        );
+    }
+
+    #[test]
+    fn random_downsampling_with_the_same_seed_keeps_the_same_splats() {
+        let mut first_splats = get_splats_along_x_axis(100);
+        let mut second_splats = get_splats_along_x_axis(100);
+
+        first_splats.downsample(10, DownsampleStrategy::Random, 42);
+        second_splats.downsample(10, DownsampleStrategy::Random, 42);
+
+        assert_eq!(first_splats.len(), 10);
+        assert_eq!(first_splats, second_splats);
+
+
+        let mut differently_seeded_splats = get_splats_along_x_axis(100);
+        differently_seeded_splats.downsample(10, DownsampleStrategy::Random, 43);
+
+        assert_ne!(first_splats, differently_seeded_splats);
+    }
+
+    #[test]
+    fn ply_files_are_loaded_by_extension() {
+        let splats = Splats::load_from_file(
+            get_fixture_path("two-splats.ply"),
+            DETERMINISTIC_DECODE_OPTIONS,
+        )
+        .unwrap();
+
+        assert_eq!(splats.len(), 2);
+        assert_eq!(
+            splats.iter().next().unwrap().position,
+            Vector3::new(0.5, -1.25, 2.0)
+        );
+    }
+
+    #[test]
+    fn saved_splats_match_the_loaded_fixture() {
+        let fixture_path = get_fixture_path("rotated-splats.splat");
+        let fixture_data = std::fs::read(&fixture_path).unwrap();
+
+        let splats = Splats::load_from_file(&fixture_path, DETERMINISTIC_DECODE_OPTIONS).unwrap();
+
+        let output_file_path = get_temporary_file_path("rotated-splats-round-trip.splat");
+
+        splats.save_to_file(&output_file_path).unwrap();
+        let saved_data = std::fs::read(&output_file_path).unwrap();
+
+        assert_eq!(saved_data.len(), fixture_data.len());
+
+        for (saved_splat_data, fixture_splat_data) in
+            saved_data.chunks(32).zip(fixture_data.chunks(32))
+        {
+            // Position, scale and color are stored losslessly.
+            assert_eq!(saved_splat_data[..28], fixture_splat_data[..28]);
+
+            // The quaternion is normalized after decoding, which may shift
+            // each quantized component by one.
+            for (saved_component, fixture_component) in saved_splat_data[28..]
+                .iter()
+                .zip(fixture_splat_data[28..].iter())
+            {
+                assert!(
+                    saved_component.abs_diff(*fixture_component) <= 1,
+                    "saved rotation {:?}, fixture rotation {:?}",
+                    &saved_splat_data[28..],
+                    &fixture_splat_data[28..]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn encoded_splat_decodes_to_the_same_splat() {
+        let splat = Splat::new(
+            Vector3::new(1.5, -2.0, 0.25),
+            Vector3::new(0.1, 0.2, 0.3),
+            Vector4::new(10, 20, 30, 40),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        );
+
+        let decoded_splat = Splat::from_raw_splat_file_data(splat.to_raw_splat_file_data()).unwrap();
+
+        assert_eq!(decoded_splat, splat);
+    }
+
+    #[test]
+    fn streaming_load_equals_the_regular_load() {
+        // Spans several streaming batches, the last of which is partial.
+        let splat_count = STREAMING_BATCH_SIZE_IN_SPLATS * 2 + 1234;
+        let input_file_path = get_temporary_file_path("streaming-load.splat");
+
+        get_splats_along_x_axis(splat_count)
+            .save_to_file(&input_file_path)
+            .unwrap();
+
+        let streamed_splats =
+            Splats::load_from_file_streaming(&input_file_path, DETERMINISTIC_DECODE_OPTIONS)
+                .unwrap();
+        let loaded_splats =
+            Splats::load_from_file(&input_file_path, DETERMINISTIC_DECODE_OPTIONS).unwrap();
+
+        assert_eq!(streamed_splats.len(), splat_count);
+        assert_eq!(streamed_splats, loaded_splats);
+    }
+
+    #[test]
+    fn streaming_load_rejects_truncated_files() {
+        let input_file_path = get_temporary_file_path("streaming-load-truncated.splat");
+
+        let mut file_contents = get_raw_splat_file_data(&get_splats_along_x_axis(10));
+        file_contents.truncate(file_contents.len() - 5);
+        std::fs::write(&input_file_path, file_contents).unwrap();
+
+        let error = Splats::load_from_file_streaming(&input_file_path, DETERMINISTIC_DECODE_OPTIONS)
+            .unwrap_err();
+
+        assert!(
+            error.to_string().contains("not divisible by 32 bytes"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    /// Returns the raw data of three splats, the second of which has a NaN position component.
+    fn get_raw_splat_file_data_with_nan_record() -> Vec<u8> {
+        let mut raw_data = get_raw_splat_file_data(&get_splats_along_x_axis(3));
+        raw_data[32..36].copy_from_slice(&f32::NAN.to_le_bytes());
+
+        raw_data
+    }
+
+    #[test]
+    fn strict_decoding_rejects_non_finite_splats() {
+        let error = Splats::load_from_reader(
+            get_raw_splat_file_data_with_nan_record().as_slice(),
+            DETERMINISTIC_DECODE_OPTIONS,
+        )
+        .unwrap_err();
+
+        assert!(
+            error.to_string().contains("index 1"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn lenient_decoding_skips_non_finite_splats() {
+        let splats = Splats::load_from_reader(
+            get_raw_splat_file_data_with_nan_record().as_slice(),
+            DecodeOptions {
+                strict: false,
+                ..DETERMINISTIC_DECODE_OPTIONS
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            splats
+                .iter()
+                .map(|splat| splat.position.x)
+                .collect::<Vec<_>>(),
+            vec![0.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn transparent_splats_are_optionally_skipped() {
+        let mut splats = get_splats_along_x_axis(3).into_iter().collect::<Vec<_>>();
+        splats[0].color.w = 0;
+        let raw_data = get_raw_splat_file_data(&Splats::from_entries(splats));
+
+        let loaded_splats = Splats::load_from_reader(
+            raw_data.as_slice(),
+            DecodeOptions {
+                skip_transparent_splats: true,
+                ..DETERMINISTIC_DECODE_OPTIONS
+            },
+        )
+        .unwrap();
+
+        assert_eq!(loaded_splats.len(), 2);
+        assert!(loaded_splats.iter().all(|splat| splat.color.w > 0));
+    }
+
+    #[test]
+    fn testing_scene_statistics() {
+        let statistics = get_testing_splat_scene().statistics();
+
+        assert_eq!(statistics.splat_count, 5);
+        assert_eq!(
+            statistics.bounding_box,
+            Some((
+                Point3::new(0.0, -0.1, 0.0),
+                Point3::new(0.1, 0.1, 0.1)
+            ))
+        );
+
+        let centroid = statistics.centroid.unwrap();
+        assert!((centroid - Point3::new(0.02, 0.0, 0.02)).norm() < 1e-6);
+
+        // Alphas 22, 90, 100, 123 and 220, in buckets of 32.
+        assert_eq!(
+            statistics.alpha_histogram,
+            [1, 0, 1, 2, 0, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn empty_scene_has_no_bounding_box() {
+        let statistics = Splats::from_entries(Vec::new()).statistics();
+
+        assert_eq!(statistics.splat_count, 0);
+        assert_eq!(statistics.bounding_box, None);
+        assert_eq!(statistics.centroid, None);
+    }
+
+    #[test]
+    fn nearest_downsampling_keeps_the_splats_closest_to_the_centroid() {
+        let mut splats = get_splats_along_x_axis(10);
+
+        let dropped_splats = splats.downsample(4, DownsampleStrategy::Nearest, 0);
+
+        assert_eq!(dropped_splats, 6);
+        // The centroid lies at x = 4.5.
+        assert_eq!(
+            splats
+                .iter()
+                .map(|splat| splat.position.x)
+                .collect::<Vec<_>>(),
+            vec![3.0, 4.0, 5.0, 6.0]
+        );
+    }
+
+    #[test]
+    fn random_downsampling_keeps_a_subset_in_file_order() {
+        let mut splats = get_splats_along_x_axis(100);
+
+        let dropped_splats = splats.downsample(10, DownsampleStrategy::Random, 7);
+
+        assert_eq!(dropped_splats, 90);
+        assert_eq!(splats.len(), 10);
+
+        let kept_positions = splats
+            .iter()
+            .map(|splat| splat.position.x)
+            .collect::<Vec<_>>();
+        assert!(kept_positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(kept_positions
+            .iter()
+            .all(|x| x.fract() == 0.0 && (0.0..100.0).contains(x)));
+    }
+
+    #[test]
+    fn downsampling_below_the_limit_keeps_every_splat() {
+        let mut splats = get_splats_along_x_axis(10);
+
+        for strategy in [DownsampleStrategy::Nearest, DownsampleStrategy::Random] {
+            assert_eq!(splats.downsample(10, strategy, 0), 0);
+            assert_eq!(splats, get_splats_along_x_axis(10));
+        }
+    }
+
+    #[test]
+    fn json_fixture_equals_the_hand_built_splats() {
+        let splats = Splats::load_from_file(
+            get_fixture_path("two-splats.json"),
+            DETERMINISTIC_DECODE_OPTIONS,
+        )
+        .unwrap();
+
+        let expected_splats = Splats::from_entries(vec![
+            Splat::new(
+                Vector3::new(0.5, -1.25, 2.0),
+                Vector3::new(0.01, 0.02, 0.03),
+                Vector4::new(255, 128, 0, 200),
+                Vector4::new(1.0, 0.0, 0.0, 0.0),
+            ),
+            Splat::new(
+                Vector3::new(-0.5, 0.0, 0.75),
+                Vector3::new(0.1, 0.1, 0.1),
+                Vector4::new(0, 64, 255, 32),
+                // Rotations are normalized.
+                Vector4::new(0.0, 0.0, 0.0, 1.0),
+            ),
+        ]);
+
+        assert_eq!(splats, expected_splats);
+    }
+
+    #[test]
+    fn view_dependent_color_falls_back_to_the_flat_color() {
+        let splat = Splat::new(
+            Vector3::zeros(),
+            Vector3::repeat(0.1),
+            Vector4::new(10, 20, 30, 40),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        );
+        assert_eq!(
+            splat.color_for_view_direction(&Vector3::x()),
+            Vector4::new(10, 20, 30, 40)
+        );
+
+        // Spherical harmonics take precedence over the RGB components, but not over alpha.
+        let splat = splat.with_sh_coefficients(vec![Vector3::zeros()]);
+        assert_eq!(
+            splat.color_for_view_direction(&Vector3::x()),
+            Vector4::new(128, 128, 128, 40)
+        );
+    }
+
+
+    #[test]
+    fn splats_read_from_a_reader_match_the_loaded_file() {
+        let fixture_path = get_fixture_path("rotated-splats.splat");
+        let fixture_data = std::fs::read(&fixture_path).unwrap();
+
+        let splats_from_file =
+            Splats::load_from_file(&fixture_path, DETERMINISTIC_DECODE_OPTIONS).unwrap();
+        let splats_from_reader = Splats::load_from_reader(
+            std::io::Cursor::new(fixture_data),
+            DETERMINISTIC_DECODE_OPTIONS,
+        )
+        .unwrap();
+
+        assert_eq!(splats_from_reader.len(), 6);
+        assert_eq!(
+            get_raw_splat_file_data(&splats_from_reader),
+            get_raw_splat_file_data(&splats_from_file)
+        );
+    }
+
+    #[test]
+    fn reader_with_a_truncated_splat_is_rejected() {
+        let mut fixture_data = std::fs::read(get_fixture_path("rotated-splats.splat")).unwrap();
+        fixture_data.truncate(fixture_data.len() - 5);
+
+        let error = Splats::load_from_reader(
+            std::io::Cursor::new(fixture_data),
+            DETERMINISTIC_DECODE_OPTIONS,
+        )
+        .expect_err("truncated data should be rejected");
+
+        assert!(
+            error.to_string().contains("not divisible by 32"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+
+    #[test]
+    fn merged_scenes_contain_the_splats_of_both() {
+        let first_scene = get_testing_splat_scene();
+        let second_scene = Splats::load_from_file(
+            get_fixture_path("rotated-splats.splat"),
+            DETERMINISTIC_DECODE_OPTIONS,
+        )
+        .unwrap();
+
+        let mut merged_scene = first_scene.clone();
+        merged_scene.merge(second_scene.clone());
+
+        assert_eq!(
+            merged_scene.len(),
+            first_scene.len() + second_scene.len()
+        );
+        assert!(first_scene
+            .iter()
+            .chain(second_scene.iter())
+            .all(|splat| merged_scene
+                .iter()
+                .any(|merged_splat| merged_splat == splat)));
+    }
+
+
+    #[test]
+    fn transform_moves_scales_and_rotates_splats() {
+        let mut splats = Splats::from_entries(vec![Splat::new(
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.1, 0.2, 0.3),
+            Vector4::new(255, 255, 255, 255),
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+        )]);
+
+        // Scales by 2, then rotates by 90 degrees around Z, then translates by (1, 0, 0).
+        let transform = Matrix4::new_translation(&Vector3::new(1.0, 0.0, 0.0))
+            * Matrix4::from_axis_angle(&Vector3::z_axis(), std::f32::consts::FRAC_PI_2)
+            * Matrix4::new_scaling(2.0);
+
+        splats.apply_transform(&transform);
+        let splat = &splats.splats[0];
+
+        assert!((splat.position - Vector3::new(1.0, 2.0, 0.0)).norm() < 1e-5);
+        assert!((splat.scale - Vector3::new(0.2, 0.4, 0.6)).norm() < 1e-5);
+
+        let half_angle = std::f32::consts::FRAC_PI_4;
+        assert!(
+            (splat.rotation - Vector4::new(half_angle.cos(), 0.0, 0.0, half_angle.sin())).norm()
+                < 1e-5,
+            "rotation is {:?}",
+            splat.rotation
+        );
+    }
+
+
+    #[test]
+    fn invalid_raw_data_length_error_contains_the_byte_counts() {
+        let error = get_invalid_raw_splat_data_length_error(100, &[0u8; 16]).to_string();
+
+        assert!(
+            error.contains("length of 100 bytes"),
+            "unexpected error: {}",
+            error
+        );
+        assert!(
+            error.contains("3 splats with 4 bytes left over"),
+            "unexpected error: {}",
+            error
+        );
+        assert!(
+            error.contains("has a header"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn invalid_raw_data_length_error_hints_at_the_actual_format() {
+        let ply_error =
+            get_invalid_raw_splat_data_length_error(33, b"ply\nformat ascii 1.0\n").to_string();
+        assert!(
+            ply_error.contains("PLY header"),
+            "unexpected error: {}",
+            ply_error
+        );
+
+        let json_error = get_invalid_raw_splat_data_length_error(33, b"  [\n  {").to_string();
+        assert!(
+            json_error.contains("JSON scene"),
+            "unexpected error: {}",
+            json_error
+        );
+    }
+
+
+    #[test]
+    fn splats_can_be_collected_extended_and_iterated() {
+        let mut splats = get_splats_along_x_axis(3)
+            .into_iter()
+            .filter(|splat| splat.position.x != 1.0)
+            .collect::<Splats>();
+
+        assert_eq!(splats.len(), 2);
+        assert!(!splats.is_empty());
+
+        splats.extend(get_splats_along_x_axis(5).into_iter().skip(3));
+        assert_eq!(splats.len(), 4);
+
+        let x_positions_by_reference = (&splats)
+            .into_iter()
+            .map(|splat| splat.position.x)
+            .collect::<Vec<_>>();
+        assert_eq!(x_positions_by_reference, [0.0, 2.0, 3.0, 4.0]);
+
+        assert_eq!(
+            splats.iter().map(|splat| splat.position.x).sum::<f32>(),
+            9.0
+        );
+
+        let owned_splats = splats.into_iter().collect::<Vec<_>>();
+        assert_eq!(owned_splats.len(), 4);
+    }
+
+    #[test]
+    fn empty_splats_are_empty() {
+        let splats = std::iter::empty::<Splat>().collect::<Splats>();
+
+        assert!(splats.is_empty());
+        assert_eq!(splats.len(), 0);
+        assert_eq!(splats.iter().count(), 0);
+    }
+
+
+    #[test]
+    fn flipping_x_negates_only_the_x_positions() {
+        let original_splats = get_testing_splat_scene();
+
+        let mut flipped_splats = original_splats.clone();
+        flipped_splats.flip_axis(Axis::X);
+
+        assert_eq!(flipped_splats.len(), original_splats.len());
+        for (flipped_splat, original_splat) in flipped_splats.iter().zip(original_splats.iter()) {
+            assert_eq!(
+                flipped_splat.position.x,
+                -original_splat.position.x
+            );
+            assert_eq!(
+                flipped_splat.position.y,
+                original_splat.position.y
+            );
+            assert_eq!(
+                flipped_splat.position.z,
+                original_splat.position.z
+            );
+            assert_eq!(flipped_splat.scale, original_splat.scale);
+            assert_eq!(flipped_splat.color, original_splat.color);
+        }
+    }
+
+    #[test]
+    fn flipping_an_axis_mirrors_the_rotation() {
+        let mut splats = Splats::from_entries(vec![Splat::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.1, 0.2, 0.3),
+            Vector4::new(255, 255, 255, 255),
+            Vector4::new(0.5, 0.5, 0.5, 0.5),
+        )]);
+
+        splats.flip_axis(Axis::X);
+
+        // The imaginary components along the other two axes (y and z) are negated.
+        assert_eq!(
+            splats.splats[0].rotation,
+            Vector4::new(0.5, 0.5, -0.5, -0.5)
+        );
+
+        // Flipping twice restores the original splat.
+        splats.flip_axis(Axis::X);
+        assert_eq!(
+            splats.splats[0].position,
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            splats.splats[0].rotation,
+            Vector4::new(0.5, 0.5, 0.5, 0.5)
+        );
+    }
+
+
+    #[test]
+    fn splats_below_the_alpha_threshold_are_removed() {
+        let get_splat_with_alpha = |alpha: u8| {
+            Splat::new(
+                Vector3::new(alpha as f32, 0.0, 0.0),
+                Vector3::repeat(0.1),
+                Vector4::new(255, 255, 255, alpha),
+                Vector4::new(1.0, 0.0, 0.0, 0.0),
+            )
+        };
+
+        let mut splats = Splats::from_entries(
+            [0, 5, 9, 10, 11, 255]
+                .into_iter()
+                .map(get_splat_with_alpha)
+                .collect(),
+        );
+
+        assert_eq!(splats.filter_by_alpha(10), 3);
+
+        let kept_alphas: Vec<u8> = splats.iter().map(|splat| splat.color.w).collect();
+        assert_eq!(kept_alphas, vec![10, 11, 255]);
+
+        // A threshold of zero keeps every splat.
+        assert_eq!(splats.filter_by_alpha(0), 0);
+        assert_eq!(splats.len(), 3);
+    }
+
+
+    #[test]
+    fn formats_are_detected_from_their_leading_bytes() {
+        assert_eq!(
+            detect_format(b"ply\nformat binary_little_endian 1.0\n"),
+            SplatFormat::Ply
+        );
+        assert_eq!(
+            detect_format(b"ply\r\nformat ascii 1.0\r\n"),
+            SplatFormat::Ply
+        );
+        assert_eq!(
+            detect_format(b"[{\"position\": [0, 0, 0]}]"),
+            SplatFormat::Json
+        );
+        assert_eq!(
+            detect_format(b"  {\"splats\": []}\n"),
+            SplatFormat::Json
+        );
+        assert_eq!(
+            detect_format(&[0x1f, 0x8b, 0x08, 0x00]),
+            SplatFormat::Gzip
+        );
+        assert_eq!(
+            detect_format(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            SplatFormat::Zstd
+        );
+        assert_eq!(
+            detect_format(&get_raw_splat_file_data(
+                &get_testing_splat_scene()
+            )),
+            SplatFormat::Raw
+        );
+    }
+
+    #[test]
+    fn unrecognized_bytes_fall_back_to_raw_data() {
+        assert_eq!(detect_format(&[]), SplatFormat::Raw);
+        // Raw data may start with a bracket by chance.
+        assert_eq!(detect_format(b"[\x00\x01\x02"), SplatFormat::Raw);
+        assert_eq!(detect_format(b"plyx"), SplatFormat::Raw);
+    }
+
+    #[test]
+    fn files_with_the_wrong_extension_are_loaded_by_their_contents() {
+        let ply_file_path = get_temporary_file_path("two-splats-with-the-wrong-extension.splat");
+        std::fs::copy(get_fixture_path("two-splats.ply"), &ply_file_path).unwrap();
+
+        assert_eq!(
+            Splats::load_from_file(&ply_file_path, DETERMINISTIC_DECODE_OPTIONS).unwrap(),
+            Splats::load_from_file(
+                get_fixture_path("two-splats.ply"),
+                DETERMINISTIC_DECODE_OPTIONS
+            )
+            .unwrap()
+        );
+    }
+
+
+    #[test]
+    fn raw_splat_data_has_no_separate_opacity() {
+        let splats = Splats::load_from_file(
+            get_fixture_path("rotated-splats.splat"),
+            DETERMINISTIC_DECODE_OPTIONS,
+        )
+        .unwrap();
+
+        assert!(!splats.is_empty());
+        for splat in splats.iter() {
+            assert_eq!(splat.opacity, None);
+            assert_eq!(
+                splat.opacity(),
+                splat.color.w as f32 / u8::MAX as f32
+            );
+        }
+    }
+
+
+    #[test]
+    fn coincident_splats_collapse_to_one() {
+        let get_splat = |position: Vector3<f32>, color: [u8; 3]| {
+            Splat::new(
+                position,
+                Vector3::repeat(0.1),
+                Vector4::new(color[0], color[1], color[2], 128),
+                Vector4::new(1.0, 0.0, 0.0, 0.0),
+            )
+        };
+
+        let mut splats = Splats::from_entries(vec![
+            get_splat(Vector3::new(0.0, 0.0, 0.0), [200, 100, 50]),
+            get_splat(Vector3::new(0.0004, 0.0, 0.0), [200, 100, 50]),
+            // In a different cell of the spatial hash grid, but still within the epsilon.
+            get_splat(Vector3::new(0.0, 0.0, 0.0009), [200, 100, 50]),
+            // Coincident, but of a different color.
+            get_splat(Vector3::new(0.0, 0.0, 0.0), [0, 0, 255]),
+            get_splat(Vector3::new(1.0, 0.0, 0.0), [200, 100, 50]),
+        ]);
+
+        assert_eq!(splats.deduplicate(0.001), 2);
+        assert_eq!(splats.len(), 3);
+
+        // The first splat of the group is kept, with the opacities composited over each other
+        // (up to the rounding of the 8-bit alpha after each merge).
+        let merged_splat = &splats.splats[0];
+        let single_opacity = 128.0 / u8::MAX as f32;
+        let expected_alpha = ((1.0 - (1.0 - single_opacity).powi(3)) * u8::MAX as f32).round() as u8;
+
+        assert_eq!(merged_splat.position, Vector3::zeros());
+        assert_eq!(
+            merged_splat.color.xyz(),
+            Vector3::new(200, 100, 50)
+        );
+        assert!(
+            merged_splat.color.w.abs_diff(expected_alpha) <= 1,
+            "merged alpha is {}, expected {}",
+            merged_splat.color.w,
+            expected_alpha
+        );
+
+        assert_eq!(
+            splats.splats[1].color.xyz(),
+            Vector3::new(0, 0, 255)
+        );
+        assert_eq!(
+            splats.splats[2].position,
+            Vector3::new(1.0, 0.0, 0.0)
+        );
     }
 }
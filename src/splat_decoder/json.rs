@@ -0,0 +1,73 @@
+//! Parsing of hand-authored splat scenes stored as JSON.
+//!
+//! The file must contain an array of objects, each with a `position` (`[x, y, z]`),
+//! `scale` (`[x, y, z]`), `color` (`[r, g, b, a]`, between 0 and 255) and
+//! `rotation` (`[w, x, y, z]` quaternion) field.
+
+use bytes::Bytes;
+use miette::{Context, IntoDiagnostic, Result};
+use nalgebra::{Vector3, Vector4};
+use serde::Deserialize;
+
+use super::{normalize_rotation_quaternion, Splat};
+
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct JsonSplat {
+    position: [f32; 3],
+    scale: [f32; 3],
+    color: [u8; 4],
+    rotation: [f32; 4],
+}
+
+impl From<JsonSplat> for Splat {
+    fn from(json_splat: JsonSplat) -> Self {
+        Splat::new(
+            Vector3::from(json_splat.position),
+            Vector3::from(json_splat.scale),
+            Vector4::from(json_splat.color),
+            normalize_rotation_quaternion(Vector4::from(json_splat.rotation)),
+        )
+    }
+}
+
+
+pub(super) fn parse_splats_from_json_data(file_contents: Bytes) -> Result<Vec<Splat>> {
+    let json_splats = serde_json::from_slice::<Vec<JsonSplat>>(&file_contents)
+        .into_diagnostic()
+        .wrap_err("Invalid JSON scene description.")?;
+
+    Ok(json_splats.into_iter().map(Splat::from).collect())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let data = br#"[{
+            "position": [0, 0, 0],
+            "scale": [1, 1, 1],
+            "color": [255, 255, 255, 255],
+            "rotation": [1, 0, 0, 0],
+            "opacity": 0.5
+        }]"#;
+
+        assert!(parse_splats_from_json_data(Bytes::from_static(data)).is_err());
+    }
+
+    #[test]
+    fn out_of_range_colors_are_rejected() {
+        let data = br#"[{
+            "position": [0, 0, 0],
+            "scale": [1, 1, 1],
+            "color": [256, 0, 0, 255],
+            "rotation": [1, 0, 0, 0]
+        }]"#;
+
+        assert!(parse_splats_from_json_data(Bytes::from_static(data)).is_err());
+    }
+}
@@ -0,0 +1,574 @@
+//! Parsing of Gaussian splat scenes stored in the PLY format,
+//! which is what most Gaussian splatting tools export.
+//!
+//! Both `ascii` and `binary_little_endian` PLY files are supported.
+//! The vertex element must contain the `x`, `y`, `z`, `scale_0..2`, `rot_0..3`,
+//! `f_dc_0..2` and `opacity` properties. Higher-order spherical harmonic coefficients
+//! (`f_rest_*`, up to degree 3) are loaded for view-dependent color if present.
+
+use bytes::Bytes;
+use miette::{miette, Result};
+use nalgebra::{Vector3, Vector4};
+use rayon::{iter::ParallelIterator, slice::ParallelSlice};
+
+use super::{
+    normalize_rotation_quaternion,
+    spherical_harmonics::{degree_from_coefficient_count, SH_C0},
+    Splat,
+};
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PlyScalarType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl PlyScalarType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(Self::Int8),
+            "uchar" | "uint8" => Some(Self::UInt8),
+            "short" | "int16" => Some(Self::Int16),
+            "ushort" | "uint16" => Some(Self::UInt16),
+            "int" | "int32" => Some(Self::Int32),
+            "uint" | "uint32" => Some(Self::UInt32),
+            "float" | "float32" => Some(Self::Float32),
+            "double" | "float64" => Some(Self::Float64),
+            _ => None,
+        }
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        match self {
+            Self::Int8 | Self::UInt8 => 1,
+            Self::Int16 | Self::UInt16 => 2,
+            Self::Int32 | Self::UInt32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+
+    /// Reads a little-endian value of this type from the start of `bytes`.
+    ///
+    /// The caller must ensure `bytes` is at least [`Self::size_in_bytes`] long.
+    fn read_little_endian(&self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::Int8 => bytes[0] as i8 as f32,
+            Self::UInt8 => bytes[0] as f32,
+            Self::Int16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+            Self::UInt16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+            Self::Int32 => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+            Self::UInt32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+            Self::Float32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            Self::Float64 => f64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]) as f32,
+        }
+    }
+}
+
+
+#[derive(Clone, Debug)]
+struct PlyProperty {
+    name: String,
+
+    /// `None` for list properties (which we can't read, only skip in some cases).
+    scalar_type: Option<PlyScalarType>,
+}
+
+#[derive(Clone, Debug)]
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+impl PlyElement {
+    fn has_list_properties(&self) -> bool {
+        self.properties
+            .iter()
+            .any(|property| property.scalar_type.is_none())
+    }
+
+    /// Size of a single binary instance of this element.
+    /// Returns `None` if the element has list properties (variable size).
+    fn binary_stride(&self) -> Option<usize> {
+        self.properties
+            .iter()
+            .map(|property| property.scalar_type.map(|scalar| scalar.size_in_bytes()))
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PlyHeader {
+    format: PlyFormat,
+    elements: Vec<PlyElement>,
+
+    /// Length of the header in bytes, including the `end_header` line.
+    length_in_bytes: usize,
+}
+
+
+/// Indices of the vertex properties we need in order to construct a [`Splat`].
+struct VertexPropertyIndices {
+    position: [usize; 3],
+    scale: [usize; 3],
+    rotation: [usize; 4],
+    color_dc: [usize; 3],
+    opacity: usize,
+
+    /// Indices of the `f_rest_*` properties (higher-order spherical harmonic coefficients),
+    /// ordered by their suffix. Empty if the file has none (or an unsupported count).
+    color_rest: Vec<usize>,
+}
+
+impl VertexPropertyIndices {
+    fn from_vertex_element(vertex_element: &PlyElement) -> Result<Self> {
+        let mut missing_properties: Vec<&str> = Vec::new();
+
+        let mut find = |name: &'static str| -> usize {
+            match vertex_element
+                .properties
+                .iter()
+                .position(|property| property.name == name && property.scalar_type.is_some())
+            {
+                Some(index) => index,
+                None => {
+                    missing_properties.push(name);
+                    0
+                }
+            }
+        };
+
+        let color_rest = (0..)
+            .map_while(|rest_index| {
+                let property_name = format!("f_rest_{}", rest_index);
+
+                vertex_element.properties.iter().position(|property| {
+                    property.name == property_name && property.scalar_type.is_some()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Each color channel has the same number of higher-order coefficients.
+        let color_rest = if color_rest.len() % 3 == 0
+            && degree_from_coefficient_count(color_rest.len() / 3 + 1).is_some()
+        {
+            color_rest
+        } else {
+            Vec::new()
+        };
+
+        let indices = Self {
+            position: [find("x"), find("y"), find("z")],
+            scale: [find("scale_0"), find("scale_1"), find("scale_2")],
+            rotation: [find("rot_0"), find("rot_1"), find("rot_2"), find("rot_3")],
+            color_dc: [find("f_dc_0"), find("f_dc_1"), find("f_dc_2")],
+            opacity: find("opacity"),
+            color_rest,
+        };
+
+        if !missing_properties.is_empty() {
+            return Err(miette!(
+                "PLY vertex element is missing required properties: {}.",
+                missing_properties.join(", ")
+            ));
+        }
+
+        Ok(indices)
+    }
+
+    /// Constructs a [`Splat`] from the values of a single vertex.
+    ///
+    /// Scales are stored as logarithms, colors as spherical harmonic DC coefficients
+    /// and opacity as a logit, so we apply the corresponding activations here.
+    fn splat_from_vertex_values(&self, values: &[f32]) -> Splat {
+        let position = Vector3::new(
+            values[self.position[0]],
+            values[self.position[1]],
+            values[self.position[2]],
+        );
+
+        let scale = Vector3::new(
+            values[self.scale[0]].exp(),
+            values[self.scale[1]].exp(),
+            values[self.scale[2]].exp(),
+        );
+
+        let color_component_from_dc =
+            |dc: f32| -> u8 { ((0.5 + SH_C0 * dc).clamp(0.0, 1.0) * u8::MAX as f32).round() as u8 };
+
+        let opacity = 1.0 / (1.0 + (-values[self.opacity]).exp());
+
+        let color = Vector4::new(
+            color_component_from_dc(values[self.color_dc[0]]),
+            color_component_from_dc(values[self.color_dc[1]]),
+            color_component_from_dc(values[self.color_dc[2]]),
+            (opacity * u8::MAX as f32).round() as u8,
+        );
+
+        let rotation = normalize_rotation_quaternion(Vector4::new(
+            values[self.rotation[0]],
+            values[self.rotation[1]],
+            values[self.rotation[2]],
+            values[self.rotation[3]],
+        ));
+
+        let splat = Splat::new(position, scale, color, rotation).with_opacity(opacity);
+
+        if self.color_rest.is_empty() {
+            return splat;
+        }
+
+
+        // Higher-order coefficients are stored channel by channel
+        // (all red coefficients first, then green, then blue).
+        let coefficients_per_channel = self.color_rest.len() / 3;
+
+        let mut sh_coefficients = Vec::with_capacity(coefficients_per_channel + 1);
+        sh_coefficients.push(Vector3::new(
+            values[self.color_dc[0]],
+            values[self.color_dc[1]],
+            values[self.color_dc[2]],
+        ));
+
+        for coefficient_index in 0..coefficients_per_channel {
+            sh_coefficients.push(Vector3::new(
+                values[self.color_rest[coefficient_index]],
+                values[self.color_rest[coefficients_per_channel + coefficient_index]],
+                values[self.color_rest[2 * coefficients_per_channel + coefficient_index]],
+            ));
+        }
+
+        splat.with_sh_coefficients(sh_coefficients)
+    }
+}
+
+
+/// Returns `true` if `bytes` start with the PLY magic line.
+pub(super) fn has_ply_magic(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"ply\n") || bytes.starts_with(b"ply\r\n")
+}
+
+fn parse_header(bytes: &[u8]) -> Result<PlyHeader> {
+    const END_OF_HEADER: &[u8] = b"end_header";
+
+    if !has_ply_magic(bytes) {
+        return Err(miette!(
+            "Invalid PLY file: missing \"ply\" magic line."
+        ));
+    }
+
+    let end_of_header_position = bytes
+        .windows(END_OF_HEADER.len())
+        .position(|window| window == END_OF_HEADER)
+        .ok_or_else(|| miette!("Invalid PLY file: missing end_header line."))?;
+
+    let length_in_bytes = bytes[end_of_header_position..]
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map(|newline_offset| end_of_header_position + newline_offset + 1)
+        .ok_or_else(|| miette!("Invalid PLY file: end_header line is not terminated."))?;
+
+    let header_text = std::str::from_utf8(&bytes[..end_of_header_position])
+        .map_err(|_| miette!("Invalid PLY file: header is not valid text."))?;
+
+
+    let mut format: Option<PlyFormat> = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
+
+    for line in header_text.lines().skip(1) {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+
+        match tokens.as_slice() {
+            ["format", "ascii", _] => format = Some(PlyFormat::Ascii),
+            ["format", "binary_little_endian", _] => format = Some(PlyFormat::BinaryLittleEndian),
+            ["format", other_format, ..] => {
+                return Err(miette!(
+                    "Unsupported PLY format: {}.",
+                    other_format
+                ));
+            }
+            ["element", name, count] => {
+                let count = count
+                    .parse::<usize>()
+                    .map_err(|_| miette!("Invalid PLY element count: {}.", count))?;
+
+                elements.push(PlyElement {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            ["property", "list", .., name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| miette!("Invalid PLY file: property before any element."))?;
+
+                element.properties.push(PlyProperty {
+                    name: name.to_string(),
+                    scalar_type: None,
+                });
+            }
+            ["property", type_name, name] => {
+                let scalar_type = PlyScalarType::from_name(type_name)
+                    .ok_or_else(|| miette!("Unsupported PLY property type: {}.", type_name))?;
+
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| miette!("Invalid PLY file: property before any element."))?;
+
+                element.properties.push(PlyProperty {
+                    name: name.to_string(),
+                    scalar_type: Some(scalar_type),
+                });
+            }
+            // Comments, obj_info and empty lines carry no information we need.
+            _ => {}
+        }
+    }
+
+    let format = format.ok_or_else(|| miette!("Invalid PLY file: missing format line."))?;
+
+
+    Ok(PlyHeader {
+        format,
+        elements,
+        length_in_bytes,
+    })
+}
+
+
+/// Parses the vertices of a PLY file into [`Splat`]s, preserving the file order.
+pub(super) fn parse_splats_from_ply_data(file_contents: Bytes) -> Result<Vec<Splat>> {
+    let header = parse_header(&file_contents)?;
+
+    let vertex_element_index = header
+        .elements
+        .iter()
+        .position(|element| element.name == "vertex")
+        .ok_or_else(|| miette!("Invalid PLY file: no vertex element."))?;
+    let vertex_element = &header.elements[vertex_element_index];
+
+    let property_indices = VertexPropertyIndices::from_vertex_element(vertex_element)?;
+    let body = &file_contents[header.length_in_bytes..];
+
+
+    match header.format {
+        PlyFormat::Ascii => {
+            let body = std::str::from_utf8(body)
+                .map_err(|_| miette!("Invalid ASCII PLY file: body is not valid text."))?;
+
+            // In ASCII files, each element instance is on its own line.
+            let lines_to_skip: usize = header.elements[..vertex_element_index]
+                .iter()
+                .map(|element| element.count)
+                .sum();
+
+            let vertex_lines = body
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .skip(lines_to_skip)
+                .take(vertex_element.count)
+                .collect::<Vec<_>>();
+
+            if vertex_lines.len() != vertex_element.count {
+                return Err(miette!(
+                    "Invalid ASCII PLY file: expected {} vertices, found {}.",
+                    vertex_element.count,
+                    vertex_lines.len()
+                ));
+            }
+
+            vertex_lines
+                .into_iter()
+                .enumerate()
+                .map(|(vertex_index, line)| {
+                    let values = line
+                        .split_whitespace()
+                        .take(vertex_element.properties.len())
+                        .map(|token| token.parse::<f32>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| {
+                            miette!(
+                                "Invalid ASCII PLY file: vertex {} contains an invalid number.",
+                                vertex_index
+                            )
+                        })?;
+
+                    if values.len() != vertex_element.properties.len() {
+                        return Err(miette!(
+                            "Invalid ASCII PLY file: vertex {} has too few values.",
+                            vertex_index
+                        ));
+                    }
+
+                    Ok(property_indices.splat_from_vertex_values(&values))
+                })
+                .collect::<Result<Vec<_>>>()
+        }
+        PlyFormat::BinaryLittleEndian => {
+            let mut vertex_data_offset = 0;
+            for element in &header.elements[..vertex_element_index] {
+                let element_stride = element.binary_stride().ok_or_else(|| {
+                    miette!(
+                        "Unsupported PLY file: element {} with list properties precedes vertices.",
+                        element.name
+                    )
+                })?;
+
+                vertex_data_offset += element_stride * element.count;
+            }
+
+            if vertex_element.has_list_properties() {
+                return Err(miette!(
+                    "Unsupported PLY file: vertex element has list properties."
+                ));
+            }
+
+            // PANIC SAFETY: We just checked that there are no list properties.
+            let vertex_stride = vertex_element.binary_stride().unwrap();
+            let vertex_data_length = vertex_stride * vertex_element.count;
+
+            if body.len() < vertex_data_offset + vertex_data_length {
+                return Err(miette!(
+                    "Invalid binary PLY file: expected at least {} bytes of vertex data, found {}.",
+                    vertex_data_offset + vertex_data_length,
+                    body.len().saturating_sub(vertex_data_offset)
+                ));
+            }
+
+            let vertex_data = &body[vertex_data_offset..vertex_data_offset + vertex_data_length];
+
+            let property_offsets_and_types = vertex_element
+                .properties
+                .iter()
+                .scan(0usize, |offset, property| {
+                    // PANIC SAFETY: We checked that there are no list properties.
+                    let scalar_type = property.scalar_type.unwrap();
+                    let property_offset = *offset;
+                    *offset += scalar_type.size_in_bytes();
+
+                    Some((property_offset, scalar_type))
+                })
+                .collect::<Vec<_>>();
+
+            let splats = vertex_data
+                .par_chunks(vertex_stride)
+                .map(|vertex_bytes| {
+                    let values = property_offsets_and_types
+                        .iter()
+                        .map(|(offset, scalar_type)| {
+                            scalar_type.read_little_endian(&vertex_bytes[*offset..])
+                        })
+                        .collect::<Vec<_>>();
+
+                    property_indices.splat_from_vertex_values(&values)
+                })
+                .collect::<Vec<_>>();
+
+            Ok(splats)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An ASCII PLY file with two splats.
+    const TWO_SPLATS_FIXTURE: &[u8] = include_bytes!("../../references/fixtures/two-splats.ply");
+
+
+    #[test]
+    fn ascii_fixture_is_parsed() {
+        let splats = parse_splats_from_ply_data(Bytes::from_static(TWO_SPLATS_FIXTURE)).unwrap();
+
+        assert_eq!(splats.len(), 2);
+
+        let first_splat = &splats[0];
+        assert_eq!(
+            first_splat.position,
+            Vector3::new(0.5, -1.25, 2.0)
+        );
+        // Scales are stored as logarithms.
+        assert!((first_splat.scale - Vector3::repeat(0.01)).norm() < 1e-6);
+        // The DC terms are converted to RGB and the opacity goes through a sigmoid.
+        assert_eq!(first_splat.color, Vector4::new(255, 128, 0, 128));
+        assert_eq!(first_splat.opacity, Some(0.5));
+
+        // Rotations are normalized.
+        assert_eq!(
+            splats[1].rotation,
+            Vector4::new(0.0, 0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn binary_little_endian_data_is_parsed() {
+        let vertices: [[f32; 14]; 2] = [
+            [
+                1.0, 2.0, 3.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            ],
+            [
+                -1.0, -2.0, -3.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            ],
+        ];
+
+        let mut data = b"ply\n\
+            format binary_little_endian 1.0\n\
+            element vertex 2\n"
+            .to_vec();
+        for property_name in [
+            "x", "y", "z", "scale_0", "scale_1", "scale_2", "rot_0", "rot_1", "rot_2", "rot_3",
+            "f_dc_0", "f_dc_1", "f_dc_2", "opacity",
+        ] {
+            data.extend_from_slice(format!("property float {}\n", property_name).as_bytes());
+        }
+        data.extend_from_slice(b"end_header\n");
+        for value in vertices.iter().flatten() {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let splats = parse_splats_from_ply_data(Bytes::from(data)).unwrap();
+
+        assert_eq!(splats.len(), 2);
+        assert_eq!(splats[0].position, Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(splats[1].position, Vector3::new(-1.0, -2.0, -3.0));
+        assert_eq!(splats[0].scale, Vector3::repeat(1.0));
+    }
+
+    #[test]
+    fn missing_vertex_properties_are_reported() {
+        let data = b"ply\n\
+            format ascii 1.0\n\
+            element vertex 1\n\
+            property float x\n\
+            property float y\n\
+            property float z\n\
+            end_header\n\
+            0 0 0\n";
+
+        let error = parse_splats_from_ply_data(Bytes::from_static(data)).unwrap_err();
+
+        assert!(
+            error.to_string().contains("scale_0"),
+            "unexpected error: {}",
+            error
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Evaluation of (real) spherical harmonics up to degree 3, which Gaussian splatting
+//! tools use to store view-dependent splat colors.
+//!
+//! Constants and basis ordering follow the reference 3D Gaussian splatting implementation.
+
+use nalgebra::Vector3;
+
+
+/// Zeroth-order spherical harmonic constant, used to convert DC coefficients to colors.
+pub(super) const SH_C0: f32 = 0.282_094_8;
+
+const SH_C1: f32 = 0.488_602_5;
+
+const SH_C2: [f32; 5] = [
+    1.092_548_5,
+    -1.092_548_5,
+    0.315_391_57,
+    -1.092_548_5,
+    0.546_274_24,
+];
+
+const SH_C3: [f32; 7] = [
+    -0.590_043_6,
+    2.890_611_4,
+    -0.457_045_8,
+    0.373_176_34,
+    -0.457_045_8,
+    1.445_305_7,
+    -0.590_043_6,
+];
+
+
+/// Returns the spherical harmonic degree described by `coefficient_count` coefficients
+/// (per color channel), or `None` if the count doesn't correspond to a supported degree (0 to 3).
+pub(super) fn degree_from_coefficient_count(coefficient_count: usize) -> Option<usize> {
+    match coefficient_count {
+        1 => Some(0),
+        4 => Some(1),
+        9 => Some(2),
+        16 => Some(3),
+        _ => None,
+    }
+}
+
+/// Evaluates the RGB color (clamped between 0 and 1) described by the spherical harmonic
+/// `coefficients` (one RGB triplet per basis function, DC first) in the given
+/// normalized `view_direction` (from the camera towards the splat).
+///
+/// Coefficients beyond the highest complete degree are ignored.
+pub fn evaluate_spherical_harmonics(
+    coefficients: &[Vector3<f32>],
+    view_direction: &Vector3<f32>,
+) -> Vector3<f32> {
+    let Some(first_coefficient) = coefficients.first() else {
+        return Vector3::new(0.5, 0.5, 0.5);
+    };
+
+    let mut color = SH_C0 * first_coefficient;
+
+    let (x, y, z) = (
+        view_direction.x,
+        view_direction.y,
+        view_direction.z,
+    );
+
+    if coefficients.len() >= 4 {
+        color += SH_C1 * (-y * coefficients[1] + z * coefficients[2] - x * coefficients[3]);
+    }
+
+    if coefficients.len() >= 9 {
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, yz, xz) = (x * y, y * z, x * z);
+
+        color += SH_C2[0] * xy * coefficients[4]
+            + SH_C2[1] * yz * coefficients[5]
+            + SH_C2[2] * (2.0 * zz - xx - yy) * coefficients[6]
+            + SH_C2[3] * xz * coefficients[7]
+            + SH_C2[4] * (xx - yy) * coefficients[8];
+
+        if coefficients.len() >= 16 {
+            color += SH_C3[0] * y * (3.0 * xx - yy) * coefficients[9]
+                + SH_C3[1] * xy * z * coefficients[10]
+                + SH_C3[2] * y * (4.0 * zz - xx - yy) * coefficients[11]
+                + SH_C3[3] * z * (2.0 * zz - 3.0 * xx - 3.0 * yy) * coefficients[12]
+                + SH_C3[4] * x * (4.0 * zz - xx - yy) * coefficients[13]
+                + SH_C3[5] * z * (xx - yy) * coefficients[14]
+                + SH_C3[6] * x * (xx - 3.0 * yy) * coefficients[15];
+        }
+    }
+
+    color.map(|component| (component + 0.5).clamp(0.0, 1.0))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Degree 1 coefficients with a DC color of (0.5, 0.5, 0.5)
+    /// and a red component that varies along the X axis.
+    fn get_degree_one_coefficients() -> Vec<Vector3<f32>> {
+        vec![
+            Vector3::zeros(),
+            Vector3::zeros(),
+            Vector3::zeros(),
+            Vector3::new(0.5, 0.0, 0.0),
+        ]
+    }
+
+    #[test]
+    fn opposing_view_directions_see_different_colors() {
+        let coefficients = get_degree_one_coefficients();
+
+        let color_from_the_left = evaluate_spherical_harmonics(&coefficients, &Vector3::x());
+        let color_from_the_right = evaluate_spherical_harmonics(&coefficients, &-Vector3::x());
+
+        let expected_offset = SH_C1 * 0.5;
+        assert!((color_from_the_left - Vector3::new(0.5 - expected_offset, 0.5, 0.5)).norm() < 1e-6);
+        assert!(
+            (color_from_the_right - Vector3::new(0.5 + expected_offset, 0.5, 0.5)).norm() < 1e-6
+        );
+    }
+
+    #[test]
+    fn dc_coefficients_are_view_independent() {
+        let coefficients = vec![Vector3::new(1.0, -0.5, 0.0)];
+
+        let expected_color = Vector3::new(0.5 + SH_C0, 0.5 - SH_C0 * 0.5, 0.5);
+        for view_direction in [Vector3::x(), -Vector3::y(), Vector3::new(0.6, 0.0, 0.8)] {
+            assert!(
+                (evaluate_spherical_harmonics(&coefficients, &view_direction) - expected_color)
+                    .norm()
+                    < 1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn colors_are_clamped() {
+        let coefficients = vec![Vector3::new(10.0, -10.0, 0.0)];
+
+        assert_eq!(
+            evaluate_spherical_harmonics(&coefficients, &Vector3::z()),
+            Vector3::new(1.0, 0.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn coefficient_counts_map_to_degrees() {
+        assert_eq!(degree_from_coefficient_count(1), Some(0));
+        assert_eq!(degree_from_coefficient_count(16), Some(3));
+        assert_eq!(degree_from_coefficient_count(5), None);
+    }
+}